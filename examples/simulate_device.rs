@@ -0,0 +1,82 @@
+//! Replays a recorded engine trace against the telematics endpoint, the way an
+//! ELD device would send it.
+//!
+//! ```sh
+//! cargo run --example simulate_device --features server -- \
+//!     examples/traces/chicago_southwest.jsonl <device id> <device token> \
+//!     [http://localhost:3000] [batch size]
+//! ```
+//!
+//! Each trace line is one engine reading in JSON. Readings are shifted so the trace
+//! ends now, then posted in order in batches of 20 by default. The device token is
+//! issued from the dashboard for the device ID on the driver's profile.
+
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Reading {
+    at: DateTime<Utc>,
+    #[serde(flatten)]
+    rest: Map<String, Value>,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 3 {
+        eprintln!("usage: simulate_device <trace> <device id> <device token> [url] [batch size]");
+        std::process::exit(2);
+    }
+    let url = args
+        .get(3)
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or("http://localhost:3000".to_string());
+    let batch_size: usize = args.get(4).and_then(|size| size.parse().ok()).unwrap_or(20);
+
+    let trace = std::fs::read_to_string(&args[0]).expect("trace must be readable");
+    let mut readings: Vec<Reading> = trace
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).expect("trace lines must be readings"))
+        .collect();
+    readings.sort_by_key(|reading| reading.at);
+    let Some(last) = readings.last().map(|reading| reading.at) else {
+        eprintln!("the trace is empty");
+        return;
+    };
+    let shift = Utc::now() - last;
+    for reading in &mut readings {
+        reading.at += shift;
+    }
+
+    let endpoint = format!("{}/api/telematics/{}", url, args[1]);
+    tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(async move {
+            let client = reqwest::Client::new();
+            for (index, batch) in readings.chunks(batch_size.max(1)).enumerate() {
+                let body = serde_json::json!({ "readings": batch }).to_string();
+                let response = client
+                    .post(&endpoint)
+                    .bearer_auth(&args[2])
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body)
+                    .send()
+                    .await
+                    .expect("the server must be reachable");
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                println!(
+                    "batch {} ({} readings): {} {}",
+                    index + 1,
+                    batch.len(),
+                    status,
+                    text
+                );
+                if !status.is_success() {
+                    std::process::exit(1);
+                }
+            }
+        });
+}
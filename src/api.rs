@@ -0,0 +1,12 @@
+use crate::server::alert::controller::alert_stream;
+use crate::server::telematics::controller::ingest_telematics;
+use axum::routing::{get, post};
+use axum::Router;
+
+/// Plain HTTP routes served next to the app, for what server functions cannot
+/// serve: ELD devices and the dashboard's alert stream.
+pub fn routes() -> Router {
+    Router::new()
+        .route("/api/telematics/:device_id", post(ingest_telematics))
+        .route("/api/alerts", get(alert_stream))
+}
@@ -1,5 +1,7 @@
 // pub(crate) mod analytics;
+pub(crate) mod alerts;
 pub(crate) mod chat;
+pub(crate) mod clocks;
 pub(crate) mod fields;
 pub(crate) mod navbar;
 pub(crate) mod profile;
@@ -0,0 +1,141 @@
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::alert::controller::{dismiss_alert, get_alert_ticket, get_alerts};
+use crate::server::alert::model::{Alert, AlertKind};
+use crate::server::alert::request::{DismissAlertRequest, GetAlertTicketRequest, GetAlertsRequest};
+use chrono::Duration;
+use dioxus::prelude::*;
+
+/// Hours-of-service alerts pushed by the server while the dashboard is open. New
+/// alerts pop up as toasts and stay listed here until the driver dismisses them.
+#[component]
+pub fn AlertsPanel(token: Signal<String>) -> Element {
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut alerts = use_signal(Vec::<Alert>::new);
+
+    use_effect(move || {
+        let token = token();
+        if token.is_empty() {
+            return;
+        }
+        spawn(async move {
+            if let Ok(response) = get_alerts(GetAlertsRequest {
+                token: token.clone(),
+            })
+            .await
+            {
+                alerts.set(response.data);
+            }
+
+            // The browser reconnects the stream on its own after a dropped connection.
+            // Once the ticket has expired the reconnect is refused and the stream
+            // closes, so it is reopened with a new ticket.
+            loop {
+                let Ok(ticket) = get_alert_ticket(GetAlertTicketRequest {
+                    token: token.clone(),
+                })
+                .await
+                else {
+                    break;
+                };
+                let mut eval = document::eval(&format!(
+                    r#"
+                    if (window.eldflowAlerts) {{
+                        window.eldflowAlerts.close();
+                    }}
+                    const source = new EventSource("/api/alerts?ticket=" + encodeURIComponent({}));
+                    window.eldflowAlerts = source;
+                    source.addEventListener("alert", (event) => {{
+                        dioxus.send(event.data);
+                    }});
+                    source.addEventListener("error", () => {{
+                        if (source.readyState === EventSource.CLOSED) {{
+                            setTimeout(() => dioxus.send(null), 5000);
+                        }}
+                    }});
+                    "#,
+                    serde_json::to_string(&ticket.data).unwrap_or_default()
+                ));
+
+                while let Ok(Some(data)) = eval.recv::<Option<String>>().await {
+                    let Ok(alert) = serde_json::from_str::<Alert>(&data) else {
+                        continue;
+                    };
+                    if alerts().iter().any(|known| known.id == alert.id) {
+                        continue;
+                    }
+                    let (title, toast_type) = match alert.kind {
+                        AlertKind::Violation => ("HOS Violation", ToastType::Error),
+                        AlertKind::Approaching => ("HOS Limit Approaching", ToastType::Warning),
+                    };
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                title.into(),
+                                alert.message.clone(),
+                                toast_type,
+                                Some(Duration::seconds(10)),
+                            )
+                            .clone(),
+                    );
+                    alerts.write().insert(0, alert);
+                }
+            }
+        });
+    });
+
+    let dismiss = move |alert: Alert| {
+        spawn(async move {
+            match dismiss_alert(DismissAlertRequest {
+                token: token(),
+                alert_id: alert.id.to_hex(),
+            })
+            .await
+            {
+                Ok(_) => alerts.write().retain(|known| known.id != alert.id),
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
+
+    if alerts().is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "mb-4 space-y-2",
+            for alert in alerts() {
+                div {
+                    key: "{alert.id}",
+                    class: match alert.kind {
+                        AlertKind::Violation => "flex justify-between items-center p-3 border border-red-300 bg-red-50 text-red-900 rounded-md text-sm",
+                        AlertKind::Approaching => "flex justify-between items-center p-3 border border-yellow-300 bg-yellow-50 text-yellow-900 rounded-md text-sm",
+                    },
+                    span {
+                        span { class: "font-semibold mr-2", {alert.at.format("%b %d %H:%M UTC").to_string()} }
+                        "{alert.message}"
+                    }
+                    button {
+                        r#type: "button",
+                        class: "ml-4 px-2 py-1 border rounded-md hover:bg-white",
+                        onclick: {
+                            let alert = alert.clone();
+                            move |_| dismiss(alert.clone())
+                        },
+                        "Dismiss"
+                    }
+                }
+            }
+        }
+    }
+}
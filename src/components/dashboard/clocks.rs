@@ -0,0 +1,132 @@
+use crate::server::alert::rules::WARNING_MINUTES;
+use crate::server::hos::controller::get_hos_clocks;
+use crate::server::hos::engine::{BREAK_AFTER_DRIVING_HOURS, DUTY_WINDOW_HOURS, MAX_DRIVING_HOURS};
+use crate::server::hos::model::DutyState;
+use crate::server::hos::request::GetHosClocksRequest;
+use crate::server::hos::response::HosClocksResponse;
+use chrono::Utc;
+use dioxus::prelude::*;
+
+// Seconds between fresh evaluations of the duty history.
+const REFRESH_SECONDS: i64 = 60;
+
+// Whether the drive, window, break and cycle clocks run down in `status`. The
+// window keeps running off duty once it has started; a 10-hour rest resets it on
+// the next refresh.
+fn running(status: DutyState, window_started: bool) -> [bool; 4] {
+    match status {
+        DutyState::Driving => [true, true, true, true],
+        DutyState::OnDuty | DutyState::YardMove => [false, true, false, true],
+        _ => [false, window_started, false, false],
+    }
+}
+
+fn countdown(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Time left on the drive, duty window, break and cycle clocks. The server
+/// evaluates the driver's duty history every minute and the clocks count down
+/// in between.
+#[component]
+pub fn HosClocksWidget(token: Signal<String>) -> Element {
+    let mut report = use_signal::<Option<HosClocksResponse>>(|| None);
+    let mut now = use_signal(Utc::now);
+
+    client! {
+        let mut eval = document::eval(
+            r#"
+            setInterval(() => {
+                dioxus.send("");
+            }, 1000)
+            "#,
+        );
+
+        use_hook(|| {
+            spawn(async move {
+                loop {
+                    let _ = eval.recv::<String>().await;
+                    now.set(Utc::now());
+                    let stale = report().is_none_or(|report| {
+                        (now() - report.as_of).num_seconds() >= REFRESH_SECONDS
+                    });
+                    if stale && !token().is_empty() {
+                        if let Ok(response) =
+                            get_hos_clocks(GetHosClocksRequest { token: token() }).await
+                        {
+                            report.set(Some(response.data));
+                        }
+                    }
+                }
+            })
+        });
+    }
+
+    let Some(current) = report() else {
+        return rsx! {};
+    };
+    let elapsed = (now() - current.as_of).num_seconds().max(0);
+    let clocks = &current.clocks;
+    let full_window = DUTY_WINDOW_HOURS * 60;
+    let running = running(current.status, clocks.window_minutes < full_window);
+    let rows = [
+        ("Drive", clocks.drive_minutes, MAX_DRIVING_HOURS * 60),
+        ("On-Duty Window", clocks.window_minutes, full_window),
+        (
+            "Until Break",
+            clocks.break_minutes,
+            BREAK_AFTER_DRIVING_HOURS * 60,
+        ),
+        (
+            "Cycle",
+            clocks.cycle_minutes,
+            current.cycle_rule.limit().num_minutes(),
+        ),
+    ]
+    .into_iter()
+    .zip(running)
+    .map(|((label, minutes, limit), running)| {
+        let seconds = minutes * 60 - if running { elapsed } else { 0 };
+        let color = if seconds <= 0 {
+            "bg-red-500"
+        } else if seconds <= WARNING_MINUTES * 60 {
+            "bg-yellow-500"
+        } else {
+            "bg-green-500"
+        };
+        let width = (seconds.max(0) as f64 / (limit * 60).max(1) as f64 * 100.0).min(100.0);
+        (label, countdown(seconds), color, width)
+    })
+    .collect::<Vec<_>>();
+
+    rsx! {
+        div { class: "mb-4 p-3 border rounded-md text-sm",
+            div { class: "flex justify-between mb-2",
+                h3 { class: "font-semibold", "Hours of Service" }
+                span { class: "text-gray-500",
+                    "{current.status.label()} · {current.cycle_rule.label()} cycle"
+                }
+            }
+            div { class: "grid grid-cols-2 md:grid-cols-4 gap-3",
+                for (label, left, color, width) in rows {
+                    div {
+                        p { class: "text-gray-500", "{label}" }
+                        p { class: "text-lg font-mono", "{left}" }
+                        div { class: "h-2 bg-gray-200 rounded",
+                            div {
+                                class: "h-2 rounded {color}",
+                                style: "width: {width}%",
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
+pub(crate) mod carrier;
+pub(crate) mod devices;
 pub(crate) mod edit;
 pub(crate) mod view;
 
+use crate::components::dashboard::profile::carrier::CarrierPanel;
 use crate::components::dashboard::profile::edit::ProfileForm;
 use crate::components::dashboard::profile::view::ProfileDetails;
 use crate::server::auth::controller::about_me;
@@ -18,9 +21,11 @@ pub fn ProfilePagePanel() -> Element {
     let mut user_token = use_signal(|| "".to_string());
     let mut user_data = use_signal(|| None::<User>);
     let mut edit_mode = use_signal(|| false);
+    let mut reload = use_signal(|| 0u32);
     let navigator = use_navigator();
 
     use_effect(move || {
+        let _ = reload();
         spawn(async move {
             let token: String = SessionStorage::get("jwt").unwrap_or_default();
             if token.is_empty() {
@@ -59,6 +64,12 @@ pub fn ProfilePagePanel() -> Element {
                                 } else {
                                     ProfileDetails { user: user.clone(), dark_mode, user_token }
                                 }
+                                CarrierPanel {
+                                    user: user.clone(),
+                                    dark_mode,
+                                    user_token: user_token(),
+                                    on_change: move |_| reload.set(reload() + 1),
+                                }
                             },
                             None => rsx!{ p { "Loading..." }}
                         }
@@ -0,0 +1,223 @@
+use crate::components::dashboard::profile::devices::{DeviceSessionPanel, DevicesPanel};
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::auth::model::{User, ROLE_CARRIER};
+use crate::server::carrier::controller::{
+    create_carrier, get_carrier, get_carrier_members, grant_carrier_role, join_carrier,
+    remove_carrier_member,
+};
+use crate::server::carrier::request::{
+    CreateCarrierRequest, GetCarrierRequest, JoinCarrierRequest, UpdateCarrierMemberRequest,
+};
+use crate::server::carrier::response::CarrierMember;
+use chrono::Duration;
+use dioxus::prelude::*;
+
+#[derive(Props, Clone, PartialEq)]
+pub struct CarrierPanelProps {
+    pub user: User,
+    pub dark_mode: bool,
+    pub user_token: String,
+    pub on_change: EventHandler<()>, // The user's carrier or role changed
+}
+
+/// Lets a user without a carrier create or join one, and lets carrier users
+/// share the invite code and manage who belongs to the carrier.
+#[component]
+pub fn CarrierPanel(props: CarrierPanelProps) -> Element {
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut carrier_name = use_signal(String::new);
+    let mut invite_code = use_signal(String::new);
+    let mut members = use_signal(Vec::<CarrierMember>::new);
+    let mut reload = use_signal(|| 0u32);
+    let is_carrier = props.user.role == ROLE_CARRIER;
+    let has_carrier = props.user.carrier_id.is_some();
+    let user_id = props.user.id;
+
+    let field_class = format!(
+        "p-2 border rounded-md {}",
+        if props.dark_mode {
+            "bg-gray-900 border-gray-700"
+        } else {
+            "border-gray-300 text-gray-900"
+        }
+    );
+
+    let mut notify = move |title: &str, body: String, kind: ToastType| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(title.into(), body, kind, Some(Duration::seconds(5)))
+                .clone(),
+        );
+    };
+
+    let carrier_token = props.user_token.clone();
+    let carrier = use_resource(move || {
+        let token = carrier_token.clone();
+        let _ = reload();
+        async move {
+            if !has_carrier {
+                return None;
+            }
+            if is_carrier {
+                if let Ok(response) = get_carrier_members(GetCarrierRequest {
+                    token: token.clone(),
+                })
+                .await
+                {
+                    members.set(response.data);
+                }
+            }
+            get_carrier(GetCarrierRequest { token })
+                .await
+                .ok()
+                .map(|response| response.data)
+        }
+    });
+
+    let token = props.user_token.clone();
+    let on_change = props.on_change;
+    let create = move |_| {
+        let token = token.clone();
+        let name = carrier_name();
+        spawn(async move {
+            match create_carrier(CreateCarrierRequest { token, name }).await {
+                Ok(_) => on_change.call(()),
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    let token = props.user_token.clone();
+    let join = move |_| {
+        let token = token.clone();
+        let invite_code = invite_code();
+        spawn(async move {
+            match join_carrier(JoinCarrierRequest { token, invite_code }).await {
+                Ok(_) => on_change.call(()),
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    let token = props.user_token.clone();
+    let update_member = move |member_id: String, remove: bool| {
+        let token = token.clone();
+        spawn(async move {
+            let req = UpdateCarrierMemberRequest { token, member_id };
+            let result = if remove {
+                remove_carrier_member(req).await
+            } else {
+                grant_carrier_role(req).await
+            };
+            match result {
+                Ok(_) => reload.set(reload() + 1),
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "mt-6 p-6 rounded-lg",
+            h3 { class: "text-lg font-semibold mb-3", "Carrier" }
+            if !has_carrier {
+                div { class: "grid grid-cols-1 md:grid-cols-2 gap-4",
+                    div {
+                        label { class: "font-medium", "Create a carrier" }
+                        div { class: "flex gap-2 mt-1",
+                            input {
+                                class: "{field_class}",
+                                placeholder: "Carrier name",
+                                value: "{carrier_name}",
+                                oninput: move |e| carrier_name.set(e.value()),
+                            }
+                            button {
+                                r#type: "button",
+                                class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700",
+                                onclick: create,
+                                "Create"
+                            }
+                        }
+                    }
+                    div {
+                        label { class: "font-medium", "Join with an invite code" }
+                        div { class: "flex gap-2 mt-1",
+                            input {
+                                class: "{field_class}",
+                                placeholder: "Invite code",
+                                value: "{invite_code}",
+                                oninput: move |e| invite_code.set(e.value()),
+                            }
+                            button {
+                                r#type: "button",
+                                class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700",
+                                onclick: join,
+                                "Join"
+                            }
+                        }
+                    }
+                }
+            } else {
+                match carrier() {
+                    Some(Some(carrier)) => rsx! {
+                        p { class: "font-medium", "{carrier.name}" }
+                        DeviceSessionPanel { user_token: props.user_token.clone(), dark_mode: props.dark_mode }
+                        if is_carrier {
+                            p { class: "mt-1 text-sm text-gray-500",
+                                "Drivers join with the invite code "
+                                span { class: "font-mono", "{carrier.invite_code}" }
+                            }
+                            table { class: "mt-4 w-full text-sm",
+                                thead {
+                                    tr {
+                                        th { class: "text-left p-2", "Name" }
+                                        th { class: "text-left p-2", "Email" }
+                                        th { class: "text-left p-2", "Role" }
+                                        th { class: "p-2" }
+                                    }
+                                }
+                                tbody {
+                                    for member in members() {
+                                        tr {
+                                            td { class: "p-2", "{member.name}" }
+                                            td { class: "p-2", "{member.email}" }
+                                            td { class: "p-2", "{member.role}" }
+                                            td { class: "p-2 text-right space-x-2",
+                                                if member.role != ROLE_CARRIER {
+                                                    button {
+                                                        r#type: "button",
+                                                        class: "text-blue-500",
+                                                        onclick: {
+                                                            let member_id = member.id.to_hex();
+                                                            let mut update_member = update_member.clone();
+                                                            move |_| update_member(member_id.clone(), false)
+                                                        },
+                                                        "Make carrier user"
+                                                    }
+                                                }
+                                                if member.id != user_id {
+                                                    button {
+                                                        r#type: "button",
+                                                        class: "text-red-500",
+                                                        onclick: {
+                                                            let member_id = member.id.to_hex();
+                                                            let mut update_member = update_member.clone();
+                                                            move |_| update_member(member_id.clone(), true)
+                                                        },
+                                                        "Remove"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            DevicesPanel { user_token: props.user_token.clone(), dark_mode: props.dark_mode }
+                        }
+                    },
+                    Some(None) => rsx! { p { "Could not load your carrier." } },
+                    None => rsx! { p { "Loading..." } },
+                }
+            }
+        }
+    }
+}
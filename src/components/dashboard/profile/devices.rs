@@ -0,0 +1,256 @@
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::telematics::controller::{
+    end_device_session, get_device_session, get_devices, issue_device_token, register_device,
+    start_device_session,
+};
+use crate::server::telematics::request::{
+    DeviceSessionRequest, GetDevicesRequest, IssueDeviceTokenRequest, RegisterDeviceRequest,
+    StartDeviceSessionRequest,
+};
+use chrono::Duration;
+use dioxus::prelude::*;
+
+/// The carrier's ELD devices. Carrier users register them here and issue the
+/// token each device posts its readings with.
+#[component]
+pub fn DevicesPanel(user_token: String, dark_mode: bool) -> Element {
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut device_id = use_signal(String::new);
+    let mut truck_number = use_signal(String::new);
+    let mut device_token = use_signal::<Option<(String, String, String)>>(|| None); // Device, token, expiry
+    let mut reload = use_signal(|| 0u32);
+
+    let field_class = format!(
+        "p-2 border rounded-md {}",
+        if dark_mode {
+            "bg-gray-900 border-gray-700"
+        } else {
+            "border-gray-300 text-gray-900"
+        }
+    );
+
+    let mut notify = move |title: &str, body: String, kind: ToastType| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(title.into(), body, kind, Some(Duration::seconds(5)))
+                .clone(),
+        );
+    };
+
+    let devices_token = user_token.clone();
+    let devices = use_resource(move || {
+        let token = devices_token.clone();
+        let _ = reload();
+        async move {
+            get_devices(GetDevicesRequest { token })
+                .await
+                .map(|response| response.data)
+                .unwrap_or_default()
+        }
+    });
+
+    let token = user_token.clone();
+    let register = move |_| {
+        let token = token.clone();
+        spawn(async move {
+            match register_device(RegisterDeviceRequest {
+                token,
+                device_id: device_id(),
+                truck_number: truck_number(),
+            })
+            .await
+            {
+                Ok(_) => {
+                    device_id.set(String::new());
+                    truck_number.set(String::new());
+                    reload.set(reload() + 1);
+                }
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    let token = user_token.clone();
+    let issue_token = move |device_id: String| {
+        let token = token.clone();
+        spawn(async move {
+            match issue_device_token(IssueDeviceTokenRequest { token, device_id }).await {
+                Ok(response) => device_token.set(Some((
+                    response.data.device_id,
+                    response.data.token,
+                    response.data.expires_at.format("%B %d, %Y").to_string(),
+                ))),
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "mt-6",
+            h4 { class: "font-medium", "ELD Devices" }
+            div { class: "flex gap-2 mt-2",
+                input {
+                    class: "{field_class}",
+                    placeholder: "Device ID",
+                    value: "{device_id}",
+                    oninput: move |e| device_id.set(e.value()),
+                }
+                input {
+                    class: "{field_class}",
+                    placeholder: "Truck number",
+                    value: "{truck_number}",
+                    oninput: move |e| truck_number.set(e.value()),
+                }
+                button {
+                    r#type: "button",
+                    class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700",
+                    onclick: register,
+                    "Register"
+                }
+            }
+            ul { class: "mt-3 space-y-2 text-sm",
+                for device in devices().unwrap_or_default() {
+                    li { class: "flex items-center justify-between",
+                        span { class: "font-mono", "{device.id}" }
+                        span { "{device.truck_number}" }
+                        button {
+                            r#type: "button",
+                            class: "text-blue-500",
+                            onclick: {
+                                let device_id = device.id.clone();
+                                let mut issue_token = issue_token.clone();
+                                move |_| issue_token(device_id.clone())
+                            },
+                            "Issue token"
+                        }
+                    }
+                }
+            }
+            if let Some((device, token, expires)) = device_token() {
+                p { class: "mt-3 text-sm text-gray-500",
+                    "Configure device {device} to send this token; it is valid until {expires}."
+                }
+                textarea {
+                    class: "mt-2 w-full p-2 text-xs font-mono border rounded-md text-gray-900",
+                    readonly: true,
+                    rows: 4,
+                    value: "{token}"
+                }
+            }
+        }
+    }
+}
+
+/// Logs the driver in to one of the carrier's devices. Driving the device records
+/// while nobody is logged in is left for the carrier to assign.
+#[component]
+pub fn DeviceSessionPanel(user_token: String, dark_mode: bool) -> Element {
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut selected = use_signal(String::new);
+    let mut reload = use_signal(|| 0u32);
+
+    let field_class = format!(
+        "p-2 border rounded-md {}",
+        if dark_mode {
+            "bg-gray-900 border-gray-700"
+        } else {
+            "border-gray-300 text-gray-900"
+        }
+    );
+
+    let mut notify = move |title: &str, body: String, kind: ToastType| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(title.into(), body, kind, Some(Duration::seconds(5)))
+                .clone(),
+        );
+    };
+
+    let session_token = user_token.clone();
+    let state = use_resource(move || {
+        let token = session_token.clone();
+        let _ = reload();
+        async move {
+            let devices = get_devices(GetDevicesRequest {
+                token: token.clone(),
+            })
+            .await
+            .map(|response| response.data)
+            .unwrap_or_default();
+            let session = get_device_session(DeviceSessionRequest { token })
+                .await
+                .ok()
+                .and_then(|response| response.data);
+            (devices, session)
+        }
+    });
+
+    let token = user_token.clone();
+    let log_in = move |_| {
+        let token = token.clone();
+        let device_id = selected();
+        spawn(async move {
+            match start_device_session(StartDeviceSessionRequest { token, device_id }).await {
+                Ok(_) => reload.set(reload() + 1),
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    let token = user_token.clone();
+    let log_out = move |_| {
+        let token = token.clone();
+        spawn(async move {
+            match end_device_session(DeviceSessionRequest { token }).await {
+                Ok(_) => reload.set(reload() + 1),
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "mt-6",
+            h4 { class: "font-medium", "ELD Login" }
+            match state() {
+                Some((_, Some(session))) => {
+                    let since = session.started_at.format("%B %d, %H:%M UTC").to_string();
+                    rsx! {
+                        div { class: "flex items-center gap-3 mt-2",
+                            span {
+                                "Logged in to "
+                                span { class: "font-mono", "{session.eld_device_id}" }
+                                " since {since}"
+                            }
+                            button {
+                                r#type: "button",
+                                class: "px-4 py-2 bg-gray-500 text-white rounded-md hover:bg-gray-600",
+                                onclick: log_out,
+                                "Log out"
+                            }
+                        }
+                    }
+                }
+                Some((devices, None)) => rsx! {
+                    div { class: "flex gap-2 mt-2",
+                        select {
+                            class: "{field_class}",
+                            value: "{selected}",
+                            oninput: move |e| selected.set(e.value()),
+                            option { value: "", "Choose a device" }
+                            for device in devices {
+                                option { value: "{device.id}", "{device.id} {device.truck_number}" }
+                            }
+                        }
+                        button {
+                            r#type: "button",
+                            class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700",
+                            onclick: log_in,
+                            "Log in"
+                        }
+                    }
+                },
+                None => rsx! { p { "Loading..." } },
+            }
+        }
+    }
+}
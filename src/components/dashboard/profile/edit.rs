@@ -28,9 +28,9 @@ pub fn ProfileForm(props: ProfileDetailsProps) -> Element {
     let mut license_number_valid = use_signal(|| true);
     let validate_license_number = |license_number: &str| !license_number.is_empty();
 
-    let eld_device_id = use_signal(|| user.eld_device_id.clone().unwrap_or_default());
-    let mut eld_device_id_valid = use_signal(|| true);
-    let validate_eld_device_id = |eld_device_id: &str| !eld_device_id.is_empty();
+    let truck_number = use_signal(|| user.truck_number.clone().unwrap_or_default());
+    let mut truck_number_valid = use_signal(|| true);
+    let validate_truck_number = |truck_number: &str| !truck_number.is_empty();
 
     let mut email_valid = use_signal(|| true);
     let validate_email = |email: &str| email.contains("@") && email.contains(".");
@@ -68,18 +68,18 @@ pub fn ProfileForm(props: ProfileDetailsProps) -> Element {
             email_valid.set(true);
         }
 
-        if !validate_eld_device_id(&eld_device_id()) {
-            eld_device_id_valid.set(false);
+        if !validate_license_number(&license_number()) {
+            license_number_valid.set(false);
             all_valid = false;
         } else {
-            eld_device_id_valid.set(true);
+            license_number_valid.set(true);
         }
 
-        if !validate_license_number(&license_number()) {
-            license_number_valid.set(false);
+        if !validate_truck_number(&truck_number()) {
+            truck_number_valid.set(false);
             all_valid = false;
         } else {
-            license_number_valid.set(true);
+            truck_number_valid.set(true);
         }
 
         if !validate_old_password(&old_password()) {
@@ -111,7 +111,7 @@ pub fn ProfileForm(props: ProfileDetailsProps) -> Element {
                         name: Some(name()),
                         email: Some(email()),
                         license_number: Some(license_number()),
-                        eld_device_id: Some(eld_device_id()),
+                        truck_number: Some(truck_number()),
                         photo: Some(photo()),
                         old_password: Some(old_password()),
                         new_password: Some(new_password()),
@@ -192,10 +192,10 @@ pub fn ProfileForm(props: ProfileDetailsProps) -> Element {
                 required: true
             },
             InputField {
-                label: "ELD Device ID",
-                value: eld_device_id,
-                is_valid: eld_device_id_valid,
-                validate: validate_eld_device_id,
+                label: "Truck Number",
+                value: truck_number,
+                is_valid: truck_number_valid,
+                validate: validate_truck_number,
                 required: true
             },
             InputField {
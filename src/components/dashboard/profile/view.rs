@@ -1,4 +1,10 @@
-use crate::server::auth::model::User;
+use crate::components::dashboard::fields::select::SelectField;
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::auth::model::{User, ROLE_CARRIER};
+use crate::server::carrier::controller::{get_carrier_policy, update_carrier_policy};
+use crate::server::carrier::request::{GetCarrierPolicyRequest, UpdateCarrierPolicyRequest};
+use crate::server::hos::model::CycleRule;
+use chrono::Duration;
 use dioxus::prelude::*;
 
 #[derive(Props, Clone, PartialEq)]
@@ -15,6 +21,75 @@ pub fn ProfileDetails(props: ProfileDetailsProps) -> Element {
     } else {
         "bg-white text-gray-900"
     };
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut pc_limit = use_signal(String::new); // Miles per day, empty for no limit
+    let mut cycle_rule = use_signal(|| CycleRule::default().label().to_string());
+    let is_carrier = props.user.role == ROLE_CARRIER;
+
+    let policy_token = props.user_token.clone();
+    let _ = use_resource(move || {
+        let token = policy_token.clone();
+        async move {
+            if !is_carrier {
+                return;
+            }
+            if let Ok(response) = get_carrier_policy(GetCarrierPolicyRequest { token }).await {
+                cycle_rule.set(response.data.cycle_rule.label().to_string());
+                pc_limit.set(
+                    response
+                        .data
+                        .pc_daily_miles
+                        .map(|miles| miles.to_string())
+                        .unwrap_or_default(),
+                );
+            }
+        }
+    });
+
+    let policy_token = props.user_token.clone();
+    let save_policy = move |_| {
+        let token = policy_token.clone();
+        let pc_daily_miles = match pc_limit().trim() {
+            "" => None,
+            miles => match miles.parse::<f64>() {
+                Ok(miles) => Some(miles),
+                Err(_) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                "The limit must be a number of miles.".into(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                    return;
+                }
+            },
+        };
+        spawn(async move {
+            let (title, body, kind) = match update_carrier_policy(UpdateCarrierPolicyRequest {
+                token,
+                cycle_rule: CycleRule::from_label(&cycle_rule()).unwrap_or_default(),
+                pc_daily_miles,
+            })
+            .await
+            {
+                Ok(_) => (
+                    "Success",
+                    "Carrier policy saved.".to_string(),
+                    ToastType::Success,
+                ),
+                Err(err) => ("Error", err.to_string(), ToastType::Error),
+            };
+            toasts_manager.set(
+                toasts_manager()
+                    .add_toast(title.into(), body, kind, Some(Duration::seconds(5)))
+                    .clone(),
+            );
+        });
+    };
 
     rsx! {
         div { class: "p-6 rounded-lg {theme_class}",
@@ -35,9 +110,35 @@ pub fn ProfileDetails(props: ProfileDetailsProps) -> Element {
                 ProfileItem { icon: "fa-user-tag", label: "Role:", value: &props.user.role }
                 ProfileItem { icon: "fa-check-circle", label: "Verified:", value: if props.user.verified { "Yes" } else { "No" } }
                 ProfileItem { icon: "fa-id-card", label: "License Number:", value: props.user.license_number.as_deref().unwrap_or("N/A") }
-                ProfileItem { icon: "fa-microchip", label: "ELD Device ID:", value: props.user.eld_device_id.as_deref().unwrap_or("N/A") }
+                ProfileItem { icon: "fa-building", label: "Carrier:", value: props.user.carrier_name.as_deref().unwrap_or("N/A") }
+                ProfileItem { icon: "fa-truck", label: "Truck Number:", value: props.user.truck_number.as_deref().unwrap_or("N/A") }
                 ProfileItem { icon: "fa-calendar-alt", label: "Registered At:", value: &props.user.created_at.format("%B %d, %Y").to_string() }
             }
+
+            if is_carrier {
+                div { class: "mt-6 max-w-xs",
+                    SelectField { label: "Cycle Rule", options: vec!["70/8", "60/7"], selected: cycle_rule }
+                }
+                div { class: "mt-4",
+                    label { class: "font-medium", "Personal conveyance limit (miles per day)" }
+                    div { class: "flex gap-2 mt-1",
+                        input {
+                            class: "p-2 border rounded-md text-gray-900",
+                            r#type: "number",
+                            min: 0,
+                            placeholder: "No limit",
+                            value: "{pc_limit}",
+                            oninput: move |e| pc_limit.set(e.value()),
+                        }
+                        button {
+                            r#type: "button",
+                            class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700",
+                            onclick: save_policy,
+                            "Save"
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -1,3 +1,8 @@
 pub(crate) mod create;
+pub(crate) mod fuel;
 pub(crate) mod list;
+pub(crate) mod prompt;
 pub(crate) mod read;
+pub(crate) mod route_map;
+pub(crate) mod signature;
+pub(crate) mod stops;
@@ -0,0 +1,303 @@
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::ifta::controller::get_ifta_report;
+use crate::server::ifta::jurisdiction::JURISDICTIONS;
+use crate::server::ifta::request::GetIftaReportRequest;
+use crate::server::trip::controller::{
+    list_fueling_stops, store_fueling_stop, update_fueling_stop,
+};
+use crate::server::trip::model::FuelingStop;
+use crate::server::trip::request::{
+    ListFuelingStopsRequest, StoreFuelingStopRequest, UpdateFuelingStopRequest,
+};
+use crate::theme::Theme;
+use chrono::{Datelike, Duration, Utc};
+use dioxus::prelude::*;
+
+async fn load_stops(token: String, trip_id: String) -> Vec<FuelingStop> {
+    list_fueling_stops(ListFuelingStopsRequest {
+        token,
+        trip_id: Some(trip_id),
+    })
+    .await
+    .map(|response| response.data)
+    .unwrap_or_default()
+}
+
+fn optional_number(value: &str) -> Option<f64> {
+    value.trim().parse().ok()
+}
+
+/// Fuel purchases of the selected trip, with a form to record or correct one and
+/// the quarterly IFTA report of all the driver's trips.
+#[component]
+pub fn FuelLedger(trip_id: Signal<String>, token: Signal<String>) -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+
+    let mut stops = use_signal(Vec::<FuelingStop>::new);
+    let mut editing = use_signal::<Option<FuelingStop>>(|| None);
+    let mut location = use_signal(String::new);
+    let mut jurisdiction = use_signal(|| JURISDICTIONS[0].0.to_string());
+    let mut gallons = use_signal(String::new);
+    let mut price = use_signal(String::new);
+    let mut odometer = use_signal(String::new);
+    let mut receipt = use_signal(String::new);
+
+    let today = Utc::now();
+    let mut year = use_signal(|| today.year());
+    let mut quarter = use_signal(|| today.month0() / 3 + 1);
+    let mut download = use_signal::<Option<(String, String)>>(|| None); // File name, data URL
+
+    let _ = use_resource(move || async move {
+        if trip_id().is_empty() {
+            return;
+        }
+        stops.set(load_stops(token(), trip_id()).await);
+    });
+
+    let mut show_error = move |message: String| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(
+                    "Error".into(),
+                    message,
+                    ToastType::Error,
+                    Some(Duration::seconds(5)),
+                )
+                .clone(),
+        );
+    };
+
+    let mut reset = move || {
+        editing.set(None);
+        location.set(String::new());
+        gallons.set(String::new());
+        price.set(String::new());
+        odometer.set(String::new());
+        receipt.set(String::new());
+    };
+
+    let mut edit = move |stop: FuelingStop| {
+        location.set(stop.location.clone());
+        if !stop.jurisdiction.is_empty() {
+            jurisdiction.set(stop.jurisdiction.clone());
+        }
+        gallons.set(stop.fuel_amount.to_string());
+        price.set(
+            stop.price_per_gallon
+                .map(|p| p.to_string())
+                .unwrap_or_default(),
+        );
+        odometer.set(stop.odometer.map(|o| o.to_string()).unwrap_or_default());
+        receipt.set(stop.receipt_reference.clone().unwrap_or_default());
+        editing.set(Some(stop));
+    };
+
+    let save = move |e: Event<FormData>| {
+        e.prevent_default();
+        let Some(fuel_amount) = optional_number(&gallons()) else {
+            show_error("Enter the gallons bought".into());
+            return;
+        };
+        spawn(async move {
+            let receipt_reference = Some(receipt()).filter(|r| !r.trim().is_empty());
+            let result = match editing() {
+                Some(stop) => update_fueling_stop(UpdateFuelingStopRequest {
+                    token: token(),
+                    fueling_stop_id: stop.id.to_hex(),
+                    location: location(),
+                    jurisdiction: jurisdiction(),
+                    fuel_amount,
+                    price_per_gallon: optional_number(&price()),
+                    odometer: optional_number(&odometer()),
+                    receipt_reference,
+                    purchased_at: stop.purchased_at,
+                })
+                .await
+                .map(|_| ()),
+                None => store_fueling_stop(StoreFuelingStopRequest {
+                    token: token(),
+                    trip_id: trip_id(),
+                    location: location(),
+                    jurisdiction: jurisdiction(),
+                    fuel_amount,
+                    price_per_gallon: optional_number(&price()),
+                    odometer: optional_number(&odometer()),
+                    receipt_reference,
+                    purchased_at: None,
+                })
+                .await
+                .map(|_| ()),
+            };
+            match result {
+                Ok(_) => {
+                    reset();
+                    stops.set(load_stops(token(), trip_id()).await);
+                }
+                Err(err) => show_error(err.to_string()),
+            }
+        });
+    };
+
+    let ifta_report = move |_| {
+        spawn(async move {
+            match get_ifta_report(GetIftaReportRequest {
+                token: token(),
+                year: year(),
+                quarter: quarter(),
+            })
+            .await
+            {
+                Ok(response) => {
+                    download.set(Some((response.data.file_name, response.data.data_url)))
+                }
+                Err(err) => show_error(err.to_string()),
+            }
+        });
+    };
+
+    let field_class = format!(
+        "mt-1 block w-full p-2 border rounded-md shadow-sm {}",
+        if dark_mode { "bg-gray-900" } else { "" }
+    );
+    let inline_class = format!(
+        "p-2 border rounded-md {}",
+        if dark_mode { "bg-gray-900" } else { "" }
+    );
+
+    rsx! {
+        div { class: "mt-6 mb-4",
+            h2 { class: "text-left text-2xl font-bold mb-4", "Fuel Purchases" }
+
+            if !stops().is_empty() {
+                table { class: "w-full text-sm mb-4",
+                    thead {
+                        tr { class: "text-left",
+                            th { "Date" }
+                            th { "Location" }
+                            th { "Jurisdiction" }
+                            th { "Gallons" }
+                            th { "$/gal" }
+                            th { "Odometer" }
+                            th { "Receipt" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        for stop in stops() {
+                            tr { key: "{stop.id}",
+                                td { {stop.purchased_at.format("%b %d, %Y").to_string()} }
+                                td { "{stop.location}" }
+                                td { "{stop.jurisdiction}" }
+                                td { "{stop.fuel_amount:.2}" }
+                                td { {stop.price_per_gallon.map(|p| format!("{:.3}", p)).unwrap_or_default()} }
+                                td { {stop.odometer.map(|o| format!("{:.0}", o)).unwrap_or_default()} }
+                                td { {stop.receipt_reference.clone().unwrap_or_default()} }
+                                td {
+                                    button {
+                                        r#type: "button",
+                                        class: "px-2 py-1 border rounded-md hover:bg-gray-100",
+                                        onclick: {
+                                            let stop = stop.clone();
+                                            move |_| edit(stop.clone())
+                                        },
+                                        "Edit"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            form { class: "grid grid-cols-2 md:grid-cols-3 gap-3 text-sm", onsubmit: save,
+                input {
+                    class: "{field_class}",
+                    value: "{location}",
+                    oninput: move |e| location.set(e.value()),
+                    placeholder: "Station or city", required: true
+                }
+                select {
+                    class: "{field_class}",
+                    onchange: move |e| jurisdiction.set(e.value()),
+                    for (code, name) in JURISDICTIONS {
+                        option { value: "{code}", selected: jurisdiction() == code, "{code} · {name}" }
+                    }
+                }
+                input {
+                    class: "{field_class}",
+                    r#type: "number", value: "{gallons}",
+                    oninput: move |e| gallons.set(e.value()),
+                    min: 0, step: 0.001, placeholder: "Gallons", required: true
+                }
+                input {
+                    class: "{field_class}",
+                    r#type: "number", value: "{price}",
+                    oninput: move |e| price.set(e.value()),
+                    min: 0, step: 0.001, placeholder: "Price per gallon"
+                }
+                input {
+                    class: "{field_class}",
+                    r#type: "number", value: "{odometer}",
+                    oninput: move |e| odometer.set(e.value()),
+                    min: 0, step: 1, placeholder: "Odometer"
+                }
+                input {
+                    class: "{field_class}",
+                    value: "{receipt}",
+                    oninput: move |e| receipt.set(e.value()),
+                    placeholder: "Receipt number"
+                }
+                div { class: "col-span-2 md:col-span-3 flex gap-2",
+                    button {
+                        r#type: "submit",
+                        class: "px-3 py-2 border rounded-md hover:bg-gray-100",
+                        if editing().is_some() { "Save Purchase" } else { "Add Purchase" }
+                    }
+                    if editing().is_some() {
+                        button {
+                            r#type: "button",
+                            class: "px-3 py-2 border rounded-md hover:bg-gray-100",
+                            onclick: move |_| reset(),
+                            "Cancel"
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex items-center gap-2 mt-4 text-sm",
+                select {
+                    class: "{inline_class}",
+                    onchange: move |e| quarter.set(e.value().parse().unwrap_or(1)),
+                    for q in 1..=4u32 {
+                        option { value: "{q}", selected: quarter() == q, "Q{q}" }
+                    }
+                }
+                input {
+                    class: "w-24 {inline_class}",
+                    r#type: "number", value: "{year}",
+                    oninput: move |e| {
+                        if let Ok(value) = e.value().parse() {
+                            year.set(value);
+                        }
+                    },
+                }
+                button {
+                    r#type: "button",
+                    class: "px-3 py-2 border rounded-md hover:bg-gray-100",
+                    onclick: ifta_report,
+                    "IFTA Report"
+                }
+                if let Some((file_name, data_url)) = download() {
+                    a {
+                        class: "text-blue-600 underline",
+                        href: "{data_url}",
+                        download: "{file_name}",
+                        "Download {file_name}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use crate::components::spinner::{Spinner, SpinnerSize};
-use crate::server::trip::controller::get_trips_for_user;
-use crate::server::trip::model::Trip;
-use crate::server::trip::request::GetTripsForUserRequest;
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::trip::controller::{delete_trip, get_trips_for_user, update_trip};
+use crate::server::trip::model::{Trip, TripStatus};
+use crate::server::trip::request::{DeleteTripRequest, GetTripsForUserRequest, UpdateTripRequest};
 use crate::theme::Theme;
-use chrono::Utc;
+use bson::oid::ObjectId;
+use chrono::{Duration, Utc};
 use dioxus::prelude::*;
 use gloo_storage::{LocalStorage, Storage};
 use serde::{Deserialize, Serialize};
@@ -17,6 +19,16 @@ pub struct CachedTripsData {
 pub const CACHE_KEY: &str = "trips_cache";
 pub const CACHE_TIMEOUT: i64 = 2 * 60 * 60;
 
+// Button text for moving a trip to `status`.
+fn action_label(status: TripStatus) -> &'static str {
+    match status {
+        TripStatus::Pending => "Reset",
+        TripStatus::Ongoing => "Start",
+        TripStatus::Completed => "Complete",
+        TripStatus::Cancelled => "Cancel",
+    }
+}
+
 #[component]
 pub fn TripsPanel(user_token: Signal<String>) -> Element {
     let theme = use_context::<Signal<Theme>>();
@@ -25,6 +37,8 @@ pub fn TripsPanel(user_token: Signal<String>) -> Element {
     let mut displayed_trips = use_signal(Vec::new);
     let mut loading = use_signal(|| true);
     let mut search_query = use_signal(String::new);
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut confirm_delete = use_signal::<Option<ObjectId>>(|| None);
 
     let _ = use_resource(move || async move {
         let now = Utc::now().timestamp();
@@ -79,6 +93,71 @@ pub fn TripsPanel(user_token: Signal<String>) -> Element {
         displayed_trips.set(filtered_trips);
     };
 
+    // Applies a change to the loaded trips and the cache, then re-filters them.
+    let mut apply = move |change: &dyn Fn(&mut Vec<Trip>)| {
+        change(&mut trips.write());
+        let _ = LocalStorage::set(
+            CACHE_KEY,
+            &CachedTripsData {
+                data: trips(),
+                timestamp: Utc::now().timestamp(),
+            },
+        );
+        filter_trips();
+    };
+
+    let mut show_error = move |message: String| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(
+                    "Error".into(),
+                    message,
+                    ToastType::Error,
+                    Some(Duration::seconds(5)),
+                )
+                .clone(),
+        );
+    };
+
+    let change_status = move |trip_id: ObjectId, status: TripStatus| {
+        spawn(async move {
+            match update_trip(UpdateTripRequest {
+                token: user_token(),
+                trip_id: trip_id.to_hex(),
+                status: Some(status),
+                current_location: None,
+            })
+            .await
+            {
+                Ok(response) => apply(&|trips: &mut Vec<Trip>| {
+                    if let Some(trip) = trips.iter_mut().find(|trip| trip.id == trip_id) {
+                        *trip = response.data.clone();
+                    }
+                }),
+                Err(err) => show_error(err.to_string()),
+            }
+        });
+    };
+
+    let mut remove = move |trip_id: ObjectId| {
+        if confirm_delete() != Some(trip_id) {
+            confirm_delete.set(Some(trip_id));
+            return;
+        }
+        confirm_delete.set(None);
+        spawn(async move {
+            match delete_trip(DeleteTripRequest {
+                token: user_token(),
+                trip_id: trip_id.to_hex(),
+            })
+            .await
+            {
+                Ok(_) => apply(&|trips: &mut Vec<Trip>| trips.retain(|trip| trip.id != trip_id)),
+                Err(err) => show_error(err.to_string()),
+            }
+        });
+    };
+
     rsx! {
         div {
             div {
@@ -136,8 +215,29 @@ pub fn TripsPanel(user_token: Signal<String>) -> Element {
                                     class: "w-full h-48 object-cover rounded-md my-2"
                                 }
                                 p { class: "text-sm", "Pickup: {trip.pickup_location}" }
-                                p { class: "text-sm", "Status: {trip.status}" }
-                                p { class: "text-sm", "Cycle Used Hours: {trip.cycle_used_hours}" }
+                                p { class: "text-sm", "Status: {trip.status.label()}" }
+                                div { class: "flex flex-wrap gap-2 my-1",
+                                    for next in trip.status.next() {
+                                        button {
+                                            r#type: "button",
+                                            class: "px-2 py-1 text-xs border rounded-md hover:bg-gray-200",
+                                            onclick: move |_| change_status(trip.id, next),
+                                            "{action_label(next)}"
+                                        }
+                                    }
+                                    if trip.status != TripStatus::Ongoing {
+                                        button {
+                                            r#type: "button",
+                                            class: "px-2 py-1 text-xs border border-red-400 text-red-500 rounded-md hover:bg-red-50",
+                                            onclick: move |_| remove(trip.id),
+                                            if confirm_delete() == Some(trip.id) { "Confirm Delete" } else { "Delete" }
+                                        }
+                                    }
+                                }
+                                p { class: "text-sm", {format!("Cycle Used: {:.1} hrs ({})", trip.cycle_used_hours, trip.cycle_rule.label())} }
+                                if trip.cycle_exceeded {
+                                    p { class: "text-sm text-yellow-500", "Exceeds remaining cycle hours" }
+                                }
                                 p { class: "text-sm", {format!("Distance: {:.2} miles", trip.distance_miles.unwrap_or(0.0))} }
                                 p {
                                     class: "text-sm",
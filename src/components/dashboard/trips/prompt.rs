@@ -0,0 +1,112 @@
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::hos::model::DutyState;
+use crate::server::telematics::controller::{answer_duty_prompt, get_duty_prompt};
+use crate::server::telematics::model::DutyPrompt;
+use crate::server::telematics::request::{AnswerDutyPromptRequest, GetDutyPromptRequest};
+use chrono::Duration;
+use dioxus::prelude::*;
+
+// Seconds between checks for a stopped vehicle.
+const POLL_SECONDS: u32 = 15;
+
+async fn load_prompt(token: String) -> Option<DutyPrompt> {
+    get_duty_prompt(GetDutyPromptRequest { token })
+        .await
+        .ok()
+        .and_then(|response| response.data)
+}
+
+/// Asks the driver whether they are still driving once their vehicle has been
+/// stopped for five minutes. Without an answer the server switches them to on duty.
+#[component]
+pub fn StationaryPrompt(token: Signal<String>, on_answer: EventHandler<()>) -> Element {
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut prompt = use_signal::<Option<DutyPrompt>>(|| None);
+
+    client! {
+        let mut eval = document::eval(&format!(
+            r#"
+            setInterval(() => {{
+                dioxus.send("");
+            }}, {})
+            "#,
+            POLL_SECONDS * 1000
+        ));
+
+        use_hook(|| {
+            spawn(async move {
+                prompt.set(load_prompt(token()).await);
+                loop {
+                    let _ = eval.recv::<String>().await;
+                    prompt.set(load_prompt(token()).await);
+                }
+            })
+        });
+    }
+
+    let answer = move |status: DutyState| {
+        let Some(current) = prompt() else {
+            return;
+        };
+        spawn(async move {
+            match answer_duty_prompt(AnswerDutyPromptRequest {
+                token: token(),
+                prompt_id: current.id.to_hex(),
+                status,
+            })
+            .await
+            {
+                Ok(_) => {
+                    prompt.set(None);
+                    on_answer.call(());
+                }
+                Err(err) => {
+                    prompt.set(load_prompt(token()).await);
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
+
+    let Some(current) = prompt() else {
+        return rsx! {};
+    };
+    let stopped = current.stopped_at.format("%H:%M UTC").to_string();
+    let deadline = current.respond_by.format("%H:%M UTC").to_string();
+
+    rsx! {
+        div { class: "mb-4 p-3 border border-red-300 bg-red-50 text-red-900 rounded-md text-sm",
+            h3 { class: "font-semibold", "Your vehicle has been stopped since {stopped}" }
+            p { class: "mb-2",
+                "Are you still driving? Without an answer by {deadline} you will be switched to On Duty."
+            }
+            div { class: "flex flex-wrap gap-2",
+                button {
+                    r#type: "button",
+                    class: "px-2 py-1 bg-blue-600 text-white rounded-md hover:bg-blue-700",
+                    onclick: move |_| answer(DutyState::Driving),
+                    "Still Driving"
+                }
+                for (status, label) in [DutyState::OnDuty, DutyState::OffDuty, DutyState::Sleeper]
+                    .map(|status| (status, status.label()))
+                {
+                    button {
+                        r#type: "button",
+                        class: "px-2 py-1 border rounded-md hover:bg-gray-100",
+                        onclick: move |_| answer(status),
+                        "{label}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,20 +1,200 @@
+use crate::components::dashboard::trips::fuel::FuelLedger;
+use crate::components::dashboard::trips::prompt::StationaryPrompt;
+use crate::components::dashboard::trips::signature::SignaturePad;
 use crate::components::spinner::{Spinner, SpinnerSize};
 use crate::components::toast::manager::ToastManager;
 use crate::components::toast::manager::ToastType;
-use crate::server::trip::controller::get_elds_for_user;
+use crate::server::export::controller::export_eld_file;
+use crate::server::export::request::ExportEldFileRequest;
+use crate::server::hos::controller::get_duty_days;
+use crate::server::hos::model::DutyState;
+use crate::server::hos::request::GetDutyDaysRequest;
+use crate::server::inspection::controller::create_inspection_link;
+use crate::server::inspection::request::CreateInspectionLinkRequest;
+use crate::server::trip::certification::{certification_status, CertificationStatus};
+use crate::server::trip::controller::certify_daily_log;
+use crate::server::trip::controller::export_daily_log;
+use crate::server::trip::controller::generate_daily_logs;
+use crate::server::trip::controller::get_daily_logs;
 use crate::server::trip::controller::get_trips_for_user;
 use crate::server::trip::controller::store_eld_log;
-use crate::server::trip::request::GetEldsForUserRequest;
+use crate::server::trip::printout::{qr_code, SheetFormat};
+use crate::server::trip::request::CertifyDailyLogRequest;
+use crate::server::trip::request::ExportDailyLogRequest;
+use crate::server::trip::request::GenerateDailyLogsRequest;
+use crate::server::trip::request::GetDailyLogsRequest;
 use crate::server::trip::request::GetTripsForUserRequest;
 use crate::server::trip::request::StoreEldLogRequest;
+use crate::server::trip::response::DailyLogSheet;
+use crate::server::unidentified::controller::{
+    get_unidentified_driving, review_unidentified_driving,
+};
+use crate::server::unidentified::model::{UnidentifiedDriving, UnidentifiedStatus};
+use crate::server::unidentified::request::{
+    GetUnidentifiedDrivingRequest, ReviewUnidentifiedDrivingRequest,
+};
 use crate::theme::Theme;
 use chrono::Duration;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use dioxus::prelude::*;
 use dioxus_logger::tracing;
 use eld::dioxus::Chart;
 use eld::{clear_chart, DutyStatus, Segment};
 
+// Today's sheet when the trip has one, otherwise the first day of the trip.
+fn pick_sheet(sheets: &[DailyLogSheet]) -> Option<&DailyLogSheet> {
+    let today = Utc::now().date_naive();
+    sheets
+        .iter()
+        .find(|sheet| sheet.log.log_date.date_naive() == today)
+        .or(sheets.first())
+}
+
+// Chart segments for a day of status changes, each lasting until the next change
+// and the last one until `until_hour`.
+fn day_segments(
+    day: DateTime<Utc>,
+    until_hour: f32,
+    changes: Vec<(DateTime<Utc>, Option<DutyState>, String, String)>,
+) -> Vec<Segment> {
+    let hour = |time: DateTime<Utc>| ((time - day).num_minutes() as f32 / 60.0).clamp(0.0, 24.0);
+
+    changes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (time, status, location, note))| {
+            let end_hour = changes
+                .get(index + 1)
+                .map(|(next, ..)| hour(*next))
+                .unwrap_or(until_hour)
+                .min(until_hour);
+            if hour(*time) >= end_hour {
+                return None;
+            }
+            Some(Segment {
+                start_hour: hour(*time),
+                end_hour,
+                status: (*status)?.into(),
+                location: location.clone(),
+                note: note.clone(),
+            })
+        })
+        .collect()
+}
+
+fn sheet_segments(sheet: &DailyLogSheet) -> Vec<Segment> {
+    day_segments(
+        sheet.log.log_date,
+        24.0,
+        sheet
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.time,
+                    Some(entry.status),
+                    entry.location.clone(),
+                    entry.remarks.clone().unwrap_or_default(),
+                )
+            })
+            .collect(),
+    )
+}
+
+// The midnight that starts `date` on the UTC charts.
+fn day_start(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+// A day's chart, derived from the duty-status stream up to now.
+async fn load_day(token: String, date: NaiveDate) -> Vec<Segment> {
+    let now = Utc::now();
+    let day = day_start(date);
+    if day > now {
+        return Vec::new();
+    }
+
+    match get_duty_days(GetDutyDaysRequest {
+        token,
+        from: day,
+        to: now.min(day + Duration::days(1)),
+    })
+    .await
+    {
+        Ok(response) => response
+            .data
+            .iter()
+            .find(|sheet| sheet.date == day)
+            .map(|sheet| {
+                day_segments(
+                    day,
+                    ((now - day).num_minutes() as f32 / 60.0).min(24.0),
+                    sheet
+                        .entries
+                        .iter()
+                        .map(|entry| {
+                            (
+                                entry.time,
+                                Some(entry.status),
+                                entry.location.clone(),
+                                entry.remarks.clone().unwrap_or_default(),
+                            )
+                        })
+                        .collect(),
+                )
+            })
+            .unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// The trip's daily logs, for their certification badges.
+async fn load_sheets(token: String, trip_id: String) -> Vec<DailyLogSheet> {
+    get_daily_logs(GetDailyLogsRequest { token, trip_id })
+        .await
+        .map(|response| response.data)
+        .unwrap_or_default()
+}
+
+// Unidentified driving still waiting on a decision.
+async fn load_unidentified(token: String) -> Vec<UnidentifiedDriving> {
+    get_unidentified_driving(GetUnidentifiedDrivingRequest { token })
+        .await
+        .map(|response| {
+            response
+                .data
+                .into_iter()
+                .filter(|record| record.status != UnidentifiedStatus::Assigned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Hours per status: off duty, sleeper berth, driving, on duty, personal
+// conveyance and yard moves.
+fn status_totals(segments: &[Segment]) -> [f32; 6] {
+    segments.iter().fold([0.0; 6], |mut acc, segment| {
+        let duration = segment.end_hour - segment.start_hour;
+        match segment.status {
+            DutyStatus::OffDuty => acc[0] += duration,
+            DutyStatus::Sleeper => acc[1] += duration,
+            DutyStatus::Driving => acc[2] += duration,
+            DutyStatus::OnDuty => acc[3] += duration,
+            DutyStatus::PersonalConveyance => acc[4] += duration,
+            DutyStatus::YardMove => acc[5] += duration,
+        }
+        acc
+    })
+}
+
+fn badge_class(status: CertificationStatus) -> &'static str {
+    match status {
+        CertificationStatus::Certified => "bg-green-100 text-green-800 border-green-300",
+        CertificationStatus::Uncertified => "bg-gray-100 text-gray-700 border-gray-300",
+        CertificationStatus::Recertify => "bg-yellow-100 text-yellow-800 border-yellow-300",
+    }
+}
+
 #[component]
 pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
     let theme = use_context::<Signal<Theme>>();
@@ -24,10 +204,16 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
     let mut status = use_signal(|| DutyStatus::OffDuty);
     let mut location = use_signal(|| "".to_string());
     let mut note = use_signal(|| "".to_string());
+    let mut log_day = use_signal(|| Utc::now().date_naive()); // The day on the chart
     let theme = use_context::<Signal<Theme>>();
     let mut toasts_manager = use_context::<Signal<ToastManager>>();
 
     let mut eld_data = use_signal::<Vec<Segment>>(Vec::new);
+    let mut download = use_signal::<Option<(String, String)>>(|| None); // File name, data URL
+    let mut sheets = use_signal::<Vec<DailyLogSheet>>(Vec::new);
+    let mut signing = use_signal::<Option<DailyLogSheet>>(|| None);
+    let mut inspection_link = use_signal::<Option<(String, String, Option<String>)>>(|| None); // URL, expiry, QR code
+    let mut unidentified = use_signal::<Vec<UnidentifiedDriving>>(Vec::new);
 
     let mut selected_trip = use_signal(|| trip_id.clone());
     let mut trips = use_signal(Vec::new);
@@ -35,132 +221,276 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
 
     let _ = use_resource(move || async move {
         clear_chart();
+        unidentified.set(load_unidentified(token()).await);
         match get_trips_for_user(GetTripsForUserRequest { token: token() }).await {
             Ok(response) => {
                 loading.set(false);
                 trips.set(response.data.clone());
                 selected_trip.set(response.data.clone()[0].id.to_string());
-                match get_elds_for_user(GetEldsForUserRequest {
-                    token: token(),
-                    trip_id: response.data.clone()[0].id.to_string(),
-                })
-                .await
-                {
-                    Ok(response) => {
-                        let mut new_segments = Vec::new();
-
-                        for eld in response.data {
-                            match (*eld.status).into() {
-                                "OffDuty" => {
-                                    new_segments.push(Segment {
-                                        start_hour: eld.start_hour as f32,
-                                        end_hour: eld.end_hour as f32,
-                                        status: DutyStatus::OffDuty,
-                                        location: eld.location.clone(),
-                                        note: eld.note.clone(),
-                                    });
-                                }
-                                "Sleeper" => {
-                                    new_segments.push(Segment {
-                                        start_hour: eld.start_hour as f32,
-                                        end_hour: eld.end_hour as f32,
-                                        status: DutyStatus::Sleeper,
-                                        location: eld.location.clone(),
-                                        note: eld.note.clone(),
-                                    });
-                                }
-                                "Driving" => {
-                                    new_segments.push(Segment {
-                                        start_hour: eld.start_hour as f32,
-                                        end_hour: eld.end_hour as f32,
-                                        status: DutyStatus::Driving,
-                                        location: eld.location.clone(),
-                                        note: eld.note.clone(),
-                                    });
-                                }
-                                "OnDuty" => {
-                                    new_segments.push(Segment {
-                                        start_hour: eld.start_hour as f32,
-                                        end_hour: eld.end_hour as f32,
-                                        status: DutyStatus::OnDuty,
-                                        location: eld.location.clone(),
-                                        note: eld.note.clone(),
-                                    });
-                                }
-                                _ => (),
-                            }
-                        }
-
-                        eld_data.set(new_segments);
-                    }
-                    Err(_) => {
-                        eld_data.set(Vec::new());
-                    }
-                }
+                eld_data.set(load_day(token(), log_day()).await);
+                sheets.set(load_sheets(token(), selected_trip()).await);
             }
             Err(_) => {
                 loading.set(false);
             }
         }
     });
+    // The chart shows the driver's day across trips; the trip only tags new entries.
     let fetch_eld_data = move |trip_id: String| {
         clear_chart();
+        signing.set(None);
+        spawn(async move {
+            eld_data.set(load_day(token(), log_day()).await);
+            sheets.set(load_sheets(token(), trip_id).await);
+        });
+    };
+
+    let certify = move |signature: String| {
+        let Some(sheet) = signing() else {
+            return;
+        };
         spawn(async move {
-            match get_elds_for_user(GetEldsForUserRequest {
+            match certify_daily_log(CertifyDailyLogRequest {
                 token: token(),
-                trip_id: trip_id.clone(),
+                log_id: sheet.log.id.to_hex(),
+                signature,
             })
             .await
             {
                 Ok(response) => {
-                    let mut new_segments = Vec::new();
-
-                    for eld in response.data {
-                        match (*eld.status).into() {
-                            "OffDuty" => {
-                                new_segments.push(Segment {
-                                    start_hour: eld.start_hour as f32,
-                                    end_hour: eld.end_hour as f32,
-                                    status: DutyStatus::OffDuty,
-                                    location: eld.location.clone(),
-                                    note: eld.note.clone(),
-                                });
-                            }
-                            "Sleeper" => {
-                                new_segments.push(Segment {
-                                    start_hour: eld.start_hour as f32,
-                                    end_hour: eld.end_hour as f32,
-                                    status: DutyStatus::Sleeper,
-                                    location: eld.location.clone(),
-                                    note: eld.note.clone(),
-                                });
-                            }
-                            "Driving" => {
-                                new_segments.push(Segment {
-                                    start_hour: eld.start_hour as f32,
-                                    end_hour: eld.end_hour as f32,
-                                    status: DutyStatus::Driving,
-                                    location: eld.location.clone(),
-                                    note: eld.note.clone(),
-                                });
-                            }
-                            "OnDuty" => {
-                                new_segments.push(Segment {
-                                    start_hour: eld.start_hour as f32,
-                                    end_hour: eld.end_hour as f32,
-                                    status: DutyStatus::OnDuty,
-                                    location: eld.location.clone(),
-                                    note: eld.note.clone(),
-                                });
-                            }
-                            _ => (),
+                    signing.set(None);
+                    for sheet in sheets.write().iter_mut() {
+                        if sheet.log.id == response.data.log.id {
+                            *sheet = response.data.clone();
                         }
                     }
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Success".into(),
+                                format!(
+                                    "Log for {} certified.",
+                                    response.data.log.log_date.format("%b %d")
+                                ),
+                                ToastType::Success,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
 
-                    eld_data.set(new_segments);
+    let prefill_from_plan = move |_| {
+        let trip_id = selected_trip();
+        spawn(async move {
+            let sheets = match get_daily_logs(GetDailyLogsRequest {
+                token: token(),
+                trip_id: trip_id.clone(),
+            })
+            .await
+            {
+                Ok(response) if !response.data.is_empty() => Ok(response),
+                _ => generate_daily_logs(GenerateDailyLogsRequest {
+                    token: token(),
+                    trip_id,
+                })
+                .await,
+            };
+
+            match sheets {
+                Ok(response) => {
+                    if let Some(sheet) = pick_sheet(&response.data) {
+                        clear_chart();
+                        log_day.set(sheet.log.log_date.date_naive());
+                        eld_data.set(sheet_segments(sheet));
+                    }
+                    sheets.set(response.data);
                 }
-                Err(_) => {
-                    eld_data.set(Vec::new());
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
+
+    let export_sheet = move |format: SheetFormat| {
+        let trip_id = selected_trip();
+        spawn(async move {
+            let exported = match get_daily_logs(GetDailyLogsRequest {
+                token: token(),
+                trip_id,
+            })
+            .await
+            {
+                Ok(response) => match pick_sheet(&response.data) {
+                    Some(sheet) => {
+                        export_daily_log(ExportDailyLogRequest {
+                            token: token(),
+                            log_id: sheet.log.id.to_hex(),
+                            format,
+                        })
+                        .await
+                    }
+                    None => Err(ServerFnError::new(
+                        "No daily logs yet, pre-fill from the plan first",
+                    )),
+                },
+                Err(err) => Err(err),
+            };
+
+            match exported {
+                Ok(response) => {
+                    download.set(Some((response.data.file_name, response.data.data_url)))
+                }
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
+
+    // The output file covers the driver's records for the last eight days.
+    let export_output_file = move |_| {
+        spawn(async move {
+            let now = Utc::now();
+            match export_eld_file(ExportEldFileRequest {
+                token: token(),
+                from: now - Duration::days(8),
+                to: now,
+                comment: "Requested from the ELD logs panel".into(),
+            })
+            .await
+            {
+                Ok(response) => {
+                    download.set(Some((response.data.file_name, response.data.data_url)))
+                }
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
+
+    let review_unidentified = move |record_id: String, accept: bool| {
+        spawn(async move {
+            match review_unidentified_driving(ReviewUnidentifiedDrivingRequest {
+                token: token(),
+                record_id,
+                accept,
+                comment: None,
+            })
+            .await
+            {
+                Ok(_) => {
+                    unidentified.set(load_unidentified(token()).await);
+                    if accept {
+                        clear_chart();
+                        eld_data.set(load_day(token(), log_day()).await);
+                    }
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Success".into(),
+                                if accept {
+                                    "Driving added to your log.".into()
+                                } else {
+                                    "Driving rejected.".into()
+                                },
+                                ToastType::Success,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
+
+    // A link the driver can hand to an officer at a roadside stop.
+    let share_inspection = move |_| {
+        spawn(async move {
+            match create_inspection_link(CreateInspectionLinkRequest {
+                token: token(),
+                minutes: 60,
+            })
+            .await
+            {
+                Ok(response) => {
+                    // The officer scans the code, so it needs the full address.
+                    let origin = web_sys::window()
+                        .and_then(|window| window.location().origin().ok())
+                        .unwrap_or_default();
+                    let url = format!("{}/inspect/{}", origin, response.data.link);
+                    inspection_link.set(Some((
+                        url.clone(),
+                        response.data.expires_at.format("%H:%M UTC").to_string(),
+                        qr_code(&url),
+                    )));
+                }
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
                 }
             }
         });
@@ -193,37 +523,29 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
                 note: note(),
             });
             let trip_id = selected_trip();
+            // The hours are on the chart's day, which starts at UTC midnight.
+            let day = day_start(log_day());
 
             spawn(async move {
-                let total_hours = eld_data.read().iter().fold([0.0; 4], |mut acc, segment| {
-                    let duration = segment.end_hour - segment.start_hour;
-                    match segment.status {
-                        DutyStatus::OffDuty => acc[0] += duration,
-                        DutyStatus::Sleeper => acc[1] += duration,
-                        DutyStatus::Driving => acc[2] += duration,
-                        DutyStatus::OnDuty => acc[3] += duration,
-                        DutyStatus::PersonalConveyance | DutyStatus::YardMove => (),
-                    }
-                    acc
-                });
+                let total_hours = status_totals(&eld_data.read());
 
                 let store_request = StoreEldLogRequest {
                     token: token(),
                     trip_id: trip_id,
-                    status: status().to_string(),
+                    status: status().into(),
                     note: note(),
-                    start_hour: start as f64,
-                    end_hour: end as f64,
+                    start: day + Duration::seconds((start * 3600.0) as i64),
+                    end: day + Duration::seconds((end * 3600.0) as i64),
                     off_duty_hours: total_hours[0] as f64,
                     sleeper_berth_hours: total_hours[1] as f64,
                     driving_hours: total_hours[2] as f64,
                     on_duty_hours: total_hours[3] as f64,
                     location: location(),
-                    odometer_reading: Some(0.0),
+                    odometer_reading: None,
                 };
 
                 match store_eld_log(store_request).await {
-                    Ok(_) => {
+                    Ok(response) => {
                         toasts_manager.set(
                             toasts_manager()
                                 .add_toast(
@@ -234,6 +556,60 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
                                 )
                                 .clone(),
                         );
+                        if let Some(violation) = response.data.violations.last() {
+                            toasts_manager.set(
+                                toasts_manager()
+                                    .add_toast(
+                                        "HOS Violation".into(),
+                                        format!(
+                                            "{} ({} min over)",
+                                            violation.message, violation.over_minutes
+                                        ),
+                                        ToastType::Warning,
+                                        Some(Duration::seconds(10)),
+                                    )
+                                    .clone(),
+                            );
+                        }
+                        for warning in &response.data.warnings {
+                            toasts_manager.set(
+                                toasts_manager()
+                                    .add_toast(
+                                        "Personal Conveyance".into(),
+                                        warning.message.clone(),
+                                        ToastType::Warning,
+                                        Some(Duration::seconds(10)),
+                                    )
+                                    .clone(),
+                            );
+                        }
+                        if response.data.uncertified_days > 0 {
+                            toasts_manager.set(
+                                toasts_manager()
+                                    .add_toast(
+                                        "Recertify".into(),
+                                        "This entry changed a certified day, please recertify it."
+                                            .into(),
+                                        ToastType::Warning,
+                                        Some(Duration::seconds(10)),
+                                    )
+                                    .clone(),
+                            );
+                            sheets.set(load_sheets(token(), selected_trip()).await);
+                        }
+                        if !response.data.restarts.is_empty() {
+                            toasts_manager.set(
+                                toasts_manager()
+                                    .add_toast(
+                                        "Cycle Reset".into(),
+                                        "34-hour restart detected, your cycle hours were reset."
+                                            .into(),
+                                        ToastType::Info,
+                                        Some(Duration::seconds(10)),
+                                    )
+                                    .clone(),
+                            );
+                        }
                     }
                     Err(err) => {
                         toasts_manager.set(
@@ -283,17 +659,35 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
         )
     };
 
-    let total_hours = eld_data().iter().fold([0.0; 4], |mut acc, segment| {
-        let duration = segment.end_hour - segment.start_hour;
-        match segment.status {
-            DutyStatus::OffDuty => acc[0] += duration,
-            DutyStatus::Sleeper => acc[1] += duration,
-            DutyStatus::Driving => acc[2] += duration,
-            DutyStatus::OnDuty => acc[3] += duration,
-            DutyStatus::PersonalConveyance | DutyStatus::YardMove => (),
-        }
-        acc
-    });
+    let total_hours = status_totals(&eld_data());
+
+    // Certification badge per day of the trip: the sheet, its day and its status.
+    let badges: Vec<(DailyLogSheet, String, CertificationStatus)> = sheets()
+        .into_iter()
+        .map(|sheet| {
+            let day = sheet.log.log_date.format("%b %d").to_string();
+            let certification = certification_status(&sheet);
+            (sheet, day, certification)
+        })
+        .collect();
+
+    // Unidentified driving waiting on the driver: its ID and a one-line summary.
+    let open_driving: Vec<(String, String)> = unidentified()
+        .iter()
+        .map(|record| {
+            let mut summary = format!(
+                "{} – {}, {} → {}",
+                record.start.format("%b %d %H:%M"),
+                record.end.format("%H:%M"),
+                record.start_location,
+                record.end_location
+            );
+            if record.status == UnidentifiedStatus::Pending {
+                summary.push_str(" (assigned by your carrier)");
+            }
+            (record.id.to_hex(), summary)
+        })
+        .collect();
 
     rsx! {
             div {
@@ -326,6 +720,26 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
                         }
                     }
                     div { class: "flex flex-col md:flex-row justify-between gap-6",
+                        div { class: "flex-1 flex flex-col",
+                            label { class: "font-medium", "Day (UTC): " }
+                            input {
+                                class: format!(
+                                    "mt-1 block w-full p-2 border rounded-md shadow-sm {}",
+                                    if dark_mode { "bg-gray-900" } else { "" }
+                                ),
+                                r#type: "date", value: "{log_day}",
+                                oninput: move |e| {
+                                    if let Ok(date) = NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d") {
+                                        log_day.set(date);
+                                        clear_chart();
+                                        spawn(async move {
+                                            eld_data.set(load_day(token(), date).await);
+                                        });
+                                    }
+                                },
+                                required: true
+                            }
+                        }
                         div { class: "flex-1 flex flex-col",
                             label { class: "font-medium", "Start Hour: " }
                             input {
@@ -370,6 +784,14 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
                             div { class: "status-box", "DR" }
                             span { class: "status-label", "Driving" }
                         }
+                        button { r#type: "button", class: "{button_class(DutyStatus::PersonalConveyance)}", onclick: move |_| status.set(DutyStatus::PersonalConveyance),
+                            div { class: "status-box", "PC" }
+                            span { class: "status-label", "Personal Conveyance" }
+                        }
+                        button { r#type: "button", class: "{button_class(DutyStatus::YardMove)}", onclick: move |_| status.set(DutyStatus::YardMove),
+                            div { class: "status-box", "YM" }
+                            span { class: "status-label", "Yard Move" }
+                        }
                     }
 
                     div { class: "mt-4",
@@ -406,7 +828,131 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
 
                 div {
                     class: "rounded-lg",
-                    h2 { class: "text-2xl font-bold mb-4", "ELD Chart" }
+                    div { class: "flex items-center justify-between mb-4",
+                        h2 { class: "text-2xl font-bold", "ELD Chart" }
+                        div { class: "flex items-center gap-2",
+                            button {
+                                r#type: "button",
+                                class: "px-3 py-2 bg-blue-600 text-white text-sm rounded-md hover:bg-blue-700",
+                                onclick: prefill_from_plan,
+                                "Pre-fill from plan"
+                            }
+                            button {
+                                r#type: "button",
+                                class: "px-3 py-2 border text-sm rounded-md hover:bg-gray-100",
+                                onclick: move |_| export_sheet(SheetFormat::Pdf),
+                                "Export PDF"
+                            }
+                            button {
+                                r#type: "button",
+                                class: "px-3 py-2 border text-sm rounded-md hover:bg-gray-100",
+                                onclick: move |_| export_sheet(SheetFormat::Svg),
+                                "Export SVG"
+                            }
+                            button {
+                                r#type: "button",
+                                class: "px-3 py-2 border text-sm rounded-md hover:bg-gray-100",
+                                onclick: export_output_file,
+                                "Export ELD File"
+                            }
+                            button {
+                                r#type: "button",
+                                class: "px-3 py-2 border text-sm rounded-md hover:bg-gray-100",
+                                onclick: share_inspection,
+                                "Inspection Link"
+                            }
+                        }
+                    }
+
+                    StationaryPrompt {
+                        token,
+                        on_answer: move |_| {
+                            spawn(async move {
+                                eld_data.set(load_day(token(), log_day()).await);
+                                sheets.set(load_sheets(token(), selected_trip()).await);
+                            });
+                        },
+                    }
+
+                    if !open_driving.is_empty() {
+                        div { class: "mb-4 p-3 border border-yellow-300 bg-yellow-50 text-yellow-900 rounded-md text-sm",
+                            h3 { class: "font-semibold mb-2", "Unidentified driving on your ELD" }
+                            for (record_id, summary) in open_driving {
+                                div { class: "flex flex-wrap items-center justify-between gap-2 py-1",
+                                    span { "{summary}" }
+                                    div { class: "flex gap-2",
+                                        button {
+                                            r#type: "button",
+                                            class: "px-2 py-1 bg-blue-600 text-white rounded-md hover:bg-blue-700",
+                                            onclick: {
+                                                let record_id = record_id.clone();
+                                                move |_| review_unidentified(record_id.clone(), true)
+                                            },
+                                            "Accept"
+                                        }
+                                        button {
+                                            r#type: "button",
+                                            class: "px-2 py-1 border rounded-md hover:bg-gray-100",
+                                            onclick: move |_| review_unidentified(record_id.clone(), false),
+                                            "Reject"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    if !badges.is_empty() {
+                        div { class: "flex flex-wrap items-center gap-2 mb-4",
+                            for (sheet, day, certification) in badges {
+                                button {
+                                    r#type: "button",
+                                    class: format!("px-2 py-1 border text-xs rounded-full {}", badge_class(certification)),
+                                    title: if certification == CertificationStatus::Certified { "Certified" } else { "Click to certify" },
+                                    onclick: move |_| {
+                                        if certification != CertificationStatus::Certified {
+                                            signing.set(Some(sheet.clone()));
+                                        }
+                                    },
+                                    "{day} · {certification.label()}"
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some(sheet) = signing() {
+                        SignaturePad {
+                            title: format!("Certify {}", sheet.log.log_date.format("%A, %b %d")),
+                            on_sign: certify,
+                            on_cancel: move |_| signing.set(None),
+                        }
+                    }
+
+                    if let Some((url, expires, qr)) = inspection_link() {
+                        div { class: "mb-4 text-sm",
+                            a {
+                                class: "text-blue-600 underline",
+                                href: "{url}",
+                                target: "_blank",
+                                "Open the inspection view"
+                            }
+                            span { " (no login needed, valid until {expires})" }
+                            if let Some(qr) = qr {
+                                img { class: "mt-2 w-60 h-60 bg-white", src: "{qr}", alt: "Inspection link QR code" }
+                            }
+                        }
+                    }
+
+                    if let Some((file_name, data_url)) = download() {
+                        div { class: "mb-4 text-sm",
+                            a {
+                                class: "text-blue-600 underline",
+                                href: "{data_url}",
+                                download: "{file_name}",
+                                "Download {file_name}"
+                            }
+                        }
+                    }
 
                     Chart {
                         data: eld_data,
@@ -418,6 +964,8 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
                         label_color: "#222222",
                         on_duty_color: "#FFD700",
                     }
+
+                    FuelLedger { trip_id: selected_trip, token }
                 }
             }
 
@@ -447,6 +995,14 @@ pub fn EldLogsPanel(trip_id: String, token: Signal<String>) -> Element {
                         td { "On Duty" }
                         td { "{total_hours[3]:.2} hrs" }
                     }
+                    tr { class: "pc",
+                        td { "Personal Conveyance" }
+                        td { "{total_hours[4]:.2} hrs" }
+                    }
+                    tr { class: "ym",
+                        td { "Yard Move" }
+                        td { "{total_hours[5]:.2} hrs" }
+                    }
                 }
             }
         }
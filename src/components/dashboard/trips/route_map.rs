@@ -0,0 +1,98 @@
+use crate::server::routing::geometry::LatLng;
+use crate::server::routing::provider::Route;
+use dioxus::prelude::*;
+
+const WIDTH: f64 = 600.0;
+const HEIGHT: f64 = 400.0;
+const PADDING: f64 = 20.0;
+
+// Projects positions into the drawing, keeping the route's proportions.
+fn project(points: &[LatLng]) -> Vec<(f64, f64)> {
+    if points.is_empty() {
+        return vec![];
+    }
+    let (mut west, mut east) = (f64::INFINITY, f64::NEG_INFINITY);
+    let (mut south, mut north) = (f64::INFINITY, f64::NEG_INFINITY);
+    for point in points {
+        west = west.min(point.longitude);
+        east = east.max(point.longitude);
+        south = south.min(point.latitude);
+        north = north.max(point.latitude);
+    }
+    let stretch = ((south + north) / 2.0).to_radians().cos();
+    let span_x = ((east - west) * stretch).max(1e-6);
+    let span_y = (north - south).max(1e-6);
+    let scale = ((WIDTH - 2.0 * PADDING) / span_x).min((HEIGHT - 2.0 * PADDING) / span_y);
+    let offset_x = (WIDTH - span_x * scale) / 2.0;
+    let offset_y = (HEIGHT - span_y * scale) / 2.0;
+    points
+        .iter()
+        .map(|point| {
+            (
+                offset_x + (point.longitude - west) * stretch * scale,
+                offset_y + (north - point.latitude) * scale,
+            )
+        })
+        .collect()
+}
+
+fn route_path(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .enumerate()
+        .map(|(index, (x, y))| format!("{}{:.1} {:.1}", if index == 0 { "M" } else { " L" }, x, y))
+        .collect()
+}
+
+/// The route line with its start and end, and the distance and driving time of
+/// each leg between `stops`.
+#[component]
+pub fn RouteMap(route: Route, stops: Vec<String>, dark_mode: bool) -> Element {
+    let points = project(&route.polyline);
+    let total = route.total();
+
+    rsx! {
+        div { class: "w-full",
+            svg {
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                class: format!("w-full border rounded-md {}", if dark_mode { "bg-gray-900" } else { "bg-gray-50" }),
+                path {
+                    d: "{route_path(&points)}",
+                    fill: "none",
+                    stroke: "#2563eb",
+                    stroke_width: "3",
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                }
+                if let Some((x, y)) = points.first() {
+                    circle { cx: "{x}", cy: "{y}", r: "6", fill: "#16a34a" }
+                }
+                if let Some((x, y)) = points.last() {
+                    circle { cx: "{x}", cy: "{y}", r: "6", fill: "#dc2626" }
+                }
+            }
+            ul { class: "mt-2 space-y-1 text-sm",
+                for (index, leg) in route.legs.iter().enumerate() {
+                    li {
+                        {format!(
+                            "{} → {} · {:.0} mi · {}h {:02}m",
+                            stops.get(index).cloned().unwrap_or_default(),
+                            stops.get(index + 1).cloned().unwrap_or_default(),
+                            leg.distance_miles,
+                            leg.duration_seconds / 3600,
+                            leg.duration_seconds % 3600 / 60
+                        )}
+                    }
+                }
+                li { class: "font-semibold",
+                    {format!(
+                        "Total · {:.0} mi · {}h {:02}m",
+                        total.distance_miles,
+                        total.duration_seconds / 3600,
+                        total.duration_seconds % 3600 / 60
+                    )}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::server::trip::printout::data_url;
+use dioxus::prelude::*;
+
+const WIDTH: f64 = 400.0;
+const HEIGHT: f64 = 120.0;
+
+type Stroke = Vec<(f64, f64)>;
+
+fn stroke_path(stroke: &Stroke) -> String {
+    stroke
+        .iter()
+        .enumerate()
+        .map(|(index, (x, y))| format!("{}{:.1} {:.1}", if index == 0 { "M" } else { " L" }, x, y))
+        .collect()
+}
+
+// The drawn strokes as a standalone SVG image, which is what gets stored.
+fn signature_svg(strokes: &[Stroke]) -> String {
+    let paths: String = strokes
+        .iter()
+        .map(|stroke| format!(r#"<path d="{}"/>"#, stroke_path(stroke)))
+        .collect();
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}"><g fill="none" stroke="#111827" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">{paths}</g></svg>"##
+    )
+}
+
+#[component]
+pub fn SignaturePad(
+    title: String,
+    on_sign: EventHandler<String>,
+    on_cancel: EventHandler<()>,
+) -> Element {
+    let mut strokes = use_signal(Vec::<Stroke>::new);
+    let mut drawing = use_signal(|| false);
+
+    rsx! {
+        div { class: "mb-4 p-4 border rounded-md",
+            h3 { class: "font-semibold", "{title}" }
+            p { class: "text-sm mb-2", "I certify that these entries are true and correct." }
+            svg {
+                width: "{WIDTH}",
+                height: "{HEIGHT}",
+                class: "border rounded-md bg-white cursor-crosshair",
+                onmousedown: move |evt| {
+                    let point = evt.element_coordinates();
+                    drawing.set(true);
+                    strokes.write().push(vec![(point.x, point.y)]);
+                },
+                onmousemove: move |evt| {
+                    if drawing() {
+                        let point = evt.element_coordinates();
+                        if let Some(stroke) = strokes.write().last_mut() {
+                            stroke.push((point.x, point.y));
+                        }
+                    }
+                },
+                onmouseup: move |_| drawing.set(false),
+                onmouseleave: move |_| drawing.set(false),
+                for stroke in strokes() {
+                    path {
+                        d: "{stroke_path(&stroke)}",
+                        fill: "none",
+                        stroke: "#111827",
+                        stroke_width: "2",
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                    }
+                }
+            }
+            div { class: "flex items-center gap-2 mt-2",
+                button {
+                    r#type: "button",
+                    class: "px-3 py-2 bg-blue-600 text-white text-sm rounded-md hover:bg-blue-700",
+                    disabled: strokes().is_empty(),
+                    onclick: move |_| {
+                        on_sign.call(data_url("image/svg+xml", signature_svg(&strokes()).as_bytes()))
+                    },
+                    "Certify"
+                }
+                button {
+                    r#type: "button",
+                    class: "px-3 py-2 border text-sm rounded-md hover:bg-gray-100",
+                    onclick: move |_| strokes.set(Vec::new()),
+                    "Clear"
+                }
+                button {
+                    r#type: "button",
+                    class: "px-3 py-2 border text-sm rounded-md hover:bg-gray-100",
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+            }
+        }
+    }
+}
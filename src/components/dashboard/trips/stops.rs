@@ -0,0 +1,143 @@
+use crate::server::trip::model::{StopType, TripStop};
+use crate::server::trip::optimizer::MAX_TRIP_STOPS;
+use chrono::prelude::*;
+use dioxus::prelude::*;
+
+const WINDOW_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+// A trip stop as typed in the form; windows are `datetime-local` values in UTC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StopDraft {
+    pub location: String,
+    pub stop_type: StopType,
+    pub window_start: String,
+    pub window_end: String,
+}
+
+impl StopDraft {
+    pub fn new(location: &str, stop_type: StopType) -> Self {
+        Self {
+            location: location.to_string(),
+            stop_type,
+            window_start: String::new(),
+            window_end: String::new(),
+        }
+    }
+
+    pub fn to_trip_stop(&self) -> TripStop {
+        let parse = |value: &str| {
+            NaiveDateTime::parse_from_str(value, WINDOW_FORMAT)
+                .ok()
+                .map(|at| at.and_utc())
+        };
+        TripStop {
+            location: self.location.trim().to_string(),
+            stop_type: self.stop_type,
+            window_start: parse(&self.window_start),
+            window_end: parse(&self.window_end),
+        }
+    }
+}
+
+/// Editable list of pickups and drop-offs in visiting order. Typing a location
+/// calls `on_location_input` with the stop's index and the text.
+#[component]
+pub fn StopsEditor(
+    stops: Signal<Vec<StopDraft>>,
+    dark_mode: bool,
+    on_location_input: EventHandler<(usize, String)>,
+) -> Element {
+    let field_class = format!(
+        "mt-1 block w-full p-2 border rounded-md shadow-sm {}",
+        if dark_mode {
+            "bg-gray-900 border-gray-700"
+        } else {
+            "border-gray-300"
+        }
+    );
+    let label_class = format!(
+        "block text-sm font-medium {}",
+        if dark_mode {
+            "text-gray-300"
+        } else {
+            "text-gray-700"
+        }
+    );
+
+    rsx! {
+        div { class: "space-y-3",
+            for (index, stop) in stops().into_iter().enumerate() {
+                div {
+                    class: format!("p-3 border rounded-md space-y-2 {}", if dark_mode { "border-gray-700" } else { "border-gray-200" }),
+                    div { class: "flex items-end gap-2",
+                        div { class: "flex-1",
+                            label { class: "{label_class}", "Stop {index + 1}" }
+                            input {
+                                class: "{field_class}",
+                                value: "{stop.location}",
+                                required: true,
+                                oninput: move |e| {
+                                    stops.write()[index].location = e.value();
+                                    on_location_input.call((index, e.value()));
+                                },
+                            }
+                        }
+                        select {
+                            class: "{field_class} w-32",
+                            value: "{stop.stop_type.label()}",
+                            oninput: move |e| {
+                                if let Some(stop_type) = StopType::from_label(&e.value()) {
+                                    stops.write()[index].stop_type = stop_type;
+                                }
+                            },
+                            option { value: "Pickup", "Pickup" }
+                            option { value: "Dropoff", "Dropoff" }
+                        }
+                        if stops().len() > 2 {
+                            button {
+                                r#type: "button",
+                                class: "px-3 py-2 text-red-500",
+                                onclick: move |_| {
+                                    stops.write().remove(index);
+                                },
+                                "Remove"
+                            }
+                        }
+                    }
+                    div { class: "flex gap-2",
+                        div { class: "flex-1",
+                            label { class: "{label_class}", "Window opens (UTC)" }
+                            input {
+                                class: "{field_class}",
+                                r#type: "datetime-local",
+                                value: "{stop.window_start}",
+                                oninput: move |e| stops.write()[index].window_start = e.value(),
+                            }
+                        }
+                        div { class: "flex-1",
+                            label { class: "{label_class}", "Window closes (UTC)" }
+                            input {
+                                class: "{field_class}",
+                                r#type: "datetime-local",
+                                value: "{stop.window_end}",
+                                oninput: move |e| stops.write()[index].window_end = e.value(),
+                            }
+                        }
+                    }
+                }
+            }
+            if stops().len() < MAX_TRIP_STOPS {
+                button {
+                    r#type: "button",
+                    class: "text-sm text-blue-500",
+                    onclick: move |_| {
+                        let mut list = stops.write();
+                        let at = list.len().saturating_sub(1);
+                        list.insert(at, StopDraft::new("", StopType::Dropoff));
+                    },
+                    "+ Add Stop"
+                }
+            }
+        }
+    }
+}
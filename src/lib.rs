@@ -1,8 +1,12 @@
 #[cfg(feature = "server")]
 pub(crate) mod ai;
+#[cfg(feature = "server")]
+pub mod api;
 pub mod components;
 #[cfg(feature = "server")]
 pub(crate) mod db;
+#[cfg(feature = "server")]
+pub mod migrate;
 pub(crate) mod pages;
 pub mod router;
 pub(crate) mod server;
@@ -34,12 +34,17 @@ fn main() {
         tokio::runtime::Runtime::new()
             .unwrap()
             .block_on(async move {
+                if let Err(error) = eldflow::migrate::run().await {
+                    tracing::error!("Data migration failed: {}", error);
+                }
+
                 let cors = CorsLayer::new()
                     .allow_origin(Any)
                     .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
                     .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
 
                 let app = Router::new()
+                    .merge(eldflow::api::routes())
                     .layer(cors)
                     .serve_dioxus_application(ServeConfig::new().unwrap(), App);
 
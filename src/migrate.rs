@@ -0,0 +1,200 @@
+use crate::db::get_client;
+use crate::server::hos::controller::migrate_eld_logs;
+use crate::server::hos::model::DutyState;
+use crate::server::telematics::controller::valid_device_id;
+use crate::server::telematics::model::{DeviceSession, EldDevice};
+use crate::server::trip::model::{StopType, TripStatus};
+use bson::{doc, oid::ObjectId, Bson, Document};
+use chrono::prelude::*;
+use dioxus_logger::tracing;
+use futures_util::TryStreamExt;
+use mongodb::error::Error;
+use mongodb::Database;
+use serde::Serialize;
+
+// Rewrites the labels stored in `field` before it was typed ("pending", "Off-Duty",
+// ...) to the values its enum serializes to. Values that do not parse are logged
+// and left for a person to fix. Returns the number of documents changed.
+async fn normalize<T: Serialize>(
+    db: &Database,
+    name: &str,
+    field: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<u64, Error> {
+    let collection = db.collection::<Document>(name);
+    let mut changed = 0;
+    for value in collection.distinct(field, doc! {}).await? {
+        let Bson::String(label) = value else {
+            continue;
+        };
+        match parse(&label).map(|parsed| bson::to_bson(&parsed)) {
+            Some(Ok(Bson::String(stored))) if stored == label => (),
+            Some(Ok(stored)) => {
+                changed += collection
+                    .update_many(doc! { field: &label }, doc! { "$set": { field: stored } })
+                    .await?
+                    .modified_count;
+            }
+            _ => tracing::warn!("{}.{} holds unknown value {:?}", name, field, label),
+        }
+    }
+    Ok(changed)
+}
+
+// Devices used to be set on the driver's profile as `eld_device_id`. Each one is
+// registered to the driver's carrier and the driver is logged in to it from when
+// they signed up, so what it already recorded stays theirs. Drivers without a
+// carrier keep the field until they join one. Returns the number of drivers moved.
+async fn migrate_profile_devices(db: &Database) -> Result<u64, Error> {
+    let users = db.collection::<Document>("users");
+    let devices = db.collection::<EldDevice>("eld_devices");
+    let sessions = db.collection::<DeviceSession>("device_sessions");
+    let mut moved = 0;
+
+    let mut cursor = users
+        .find(doc! { "eld_device_id": { "$type": "string" } })
+        .await?;
+    while let Some(user) = cursor.try_next().await? {
+        let Ok(user_id) = user.get_object_id("_id") else {
+            continue;
+        };
+        let device_id = user.get_str("eld_device_id").unwrap_or_default().trim();
+        if !device_id.is_empty() {
+            if !valid_device_id(device_id) {
+                tracing::warn!("User {} has invalid ELD device ID {:?}", user_id, device_id);
+                continue;
+            }
+            let Ok(carrier_id) = user.get_object_id("carrierId") else {
+                continue;
+            };
+            let now = Utc::now();
+            match devices.find_one(doc! { "_id": device_id }).await? {
+                Some(device) if device.carrier_id != carrier_id => {
+                    tracing::warn!(
+                        "ELD device {} of user {} belongs to another carrier",
+                        device_id,
+                        user_id
+                    );
+                    continue;
+                }
+                Some(_) => (),
+                None => {
+                    devices
+                        .insert_one(EldDevice {
+                            id: device_id.to_string(),
+                            carrier_id,
+                            truck_number: user.get_str("truckNumber").unwrap_or_default().into(),
+                            registered_by: user_id,
+                            created_at: now,
+                            updated_at: now,
+                        })
+                        .await?;
+                }
+            }
+
+            // The session ends where a later login of the driver or on the device begins.
+            let started_at = user
+                .get_datetime("createdAt")
+                .map(|at| at.to_chrono())
+                .unwrap_or(now);
+            let since = bson::DateTime::from_chrono(started_at);
+            let exists = sessions
+                .find_one(
+                    doc! { "driverId": user_id, "eldDeviceId": device_id, "startedAt": since },
+                )
+                .await?
+                .is_some();
+            if !exists {
+                let later = sessions
+                    .find_one(doc! {
+                        "$or": [{ "driverId": user_id }, { "eldDeviceId": device_id }],
+                        "startedAt": { "$gt": since },
+                    })
+                    .sort(doc! { "startedAt": 1 })
+                    .await?;
+                sessions
+                    .insert_one(DeviceSession {
+                        id: ObjectId::new(),
+                        eld_device_id: device_id.to_string(),
+                        driver_id: user_id,
+                        started_at,
+                        ended_at: later.map(|session| session.started_at),
+                    })
+                    .await?;
+            }
+        }
+        users
+            .update_one(
+                doc! { "_id": user_id },
+                doc! { "$unset": { "eld_device_id": "" } },
+            )
+            .await?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// Brings stored documents up to the current models. Safe to run on every start.
+pub async fn run() -> Result<(), Error> {
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+
+    let changed = normalize(&db, "trips", "status", TripStatus::from_label).await?
+        + normalize(&db, "route_stops", "stopType", StopType::from_label).await?
+        + normalize(&db, "log_entries", "status", DutyState::from_label).await?
+        + normalize(&db, "eld_logs", "status", DutyState::from_label).await?;
+    // Fuel purchases recorded before the ledger had a purchase time were entered
+    // as they were made.
+    let backfilled = db
+        .collection::<Document>("fueling_stops")
+        .update_many(
+            doc! { "purchasedAt": { "$exists": false } },
+            vec![doc! { "$set": { "purchasedAt": "$createdAt" } }],
+        )
+        .await?
+        .modified_count;
+    if backfilled > 0 {
+        tracing::info!(
+            "Backfilled the purchase time of {} fueling stops",
+            backfilled
+        );
+    }
+
+    if changed > 0 {
+        tracing::info!("Migrated {} documents to typed statuses", changed);
+    }
+
+    let moved = migrate_profile_devices(&db).await?;
+    if moved > 0 {
+        tracing::info!(
+            "Moved the ELD devices of {} drivers to carrier devices",
+            moved
+        );
+    }
+
+    // Hour-range ELD logs become duty events. A driver whose logs fail to convert
+    // is retried on the next start.
+    let mut converted = 0;
+    for value in db
+        .collection::<Document>("eld_logs")
+        .distinct("driverId", doc! { "migratedAt": { "$exists": false } })
+        .await?
+    {
+        let Bson::ObjectId(driver_id) = value else {
+            continue;
+        };
+        match migrate_eld_logs(driver_id).await {
+            Ok(count) => converted += count,
+            Err(error) => tracing::error!(
+                "Could not convert the ELD logs of driver {}: {}",
+                driver_id,
+                error
+            ),
+        }
+    }
+    if converted > 0 {
+        tracing::info!("Converted {} ELD logs to duty events", converted);
+    }
+    Ok(())
+}
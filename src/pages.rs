@@ -1,5 +1,6 @@
 pub(crate) mod dashboard;
 pub(crate) mod home;
+pub(crate) mod inspection;
 pub(crate) mod login;
 pub(crate) mod signup;
 pub(crate) mod trip;
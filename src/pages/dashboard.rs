@@ -1,4 +1,6 @@
+use crate::components::dashboard::alerts::AlertsPanel;
 use crate::components::dashboard::chat::ChatPanelPage;
+use crate::components::dashboard::clocks::HosClocksWidget;
 use crate::components::dashboard::navbar::Navbar;
 use crate::components::dashboard::profile::ProfilePagePanel;
 use crate::components::dashboard::sidebar::Sidebar;
@@ -51,6 +53,8 @@ pub fn Dashboard() -> Element {
 
             div { class: "flex-1 p-4 md:p-8",
                 Navbar { dark_mode }
+                AlertsPanel { token: user_token }
+                HosClocksWidget { token: user_token }
 
                 div { class: format!("p-4 shadow rounded-lg {}", if dark_mode { "bg-gray-800" } else { "bg-white" }),
                     {current_tab}
@@ -0,0 +1,109 @@
+use crate::components::spinner::{Spinner, SpinnerSize};
+use crate::server::inspection::controller::get_inspection;
+use crate::server::inspection::request::GetInspectionRequest;
+use crate::server::inspection::response::InspectionResponse;
+use crate::server::trip::certification::CertificationStatus;
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+
+fn hours(minutes: i64) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn stamp(at: DateTime<Utc>) -> String {
+    at.format("%b %d %H:%M UTC").to_string()
+}
+
+fn day_title(date: DateTime<Utc>) -> String {
+    date.format("%A, %B %d, %Y").to_string()
+}
+
+// Read-only view of a driver's last eight days for a roadside officer. Access comes
+// from the signed token in the link; there is no login.
+#[component]
+pub fn Inspection(token: String) -> Element {
+    let mut inspection = use_signal::<Option<InspectionResponse>>(|| None);
+    let mut error = use_signal::<Option<String>>(|| None);
+    let mut loading = use_signal(|| true);
+
+    let _ = use_resource(move || {
+        let link = token.clone();
+        async move {
+            match get_inspection(GetInspectionRequest { link }).await {
+                Ok(response) => inspection.set(Some(response.data)),
+                Err(_) => error.set(Some(
+                    "This inspection link is invalid or has expired. Ask the driver for a new one."
+                        .into(),
+                )),
+            }
+            loading.set(false);
+        }
+    });
+
+    rsx! {
+        div { class: "min-h-screen bg-white text-gray-900 p-4 md:p-8 text-lg",
+            if let Some(data) = inspection() {
+                div { class: "mb-6 border-b pb-4",
+                    h1 { class: "text-3xl font-bold", "Driver's Record of Duty Status" }
+                    p { class: "text-sm text-gray-600",
+                        "Read-only inspection view, valid until {stamp(data.expires_at)}"
+                    }
+                    div { class: "grid grid-cols-1 md:grid-cols-3 gap-2 mt-4",
+                        p { span { class: "font-semibold", "Driver: " } "{data.driver_name}" }
+                        p { span { class: "font-semibold", "Carrier: " } "{data.carrier_name}" }
+                        p { span { class: "font-semibold", "Truck: " } "{data.truck_number}" }
+                        p { span { class: "font-semibold", "License: " } "{data.license_number}" }
+                        p { span { class: "font-semibold", "ELD: " } "{data.eld_identifier}" }
+                        p { span { class: "font-semibold", "Generated: " } "{stamp(data.generated_at)}" }
+                    }
+                }
+                for day in data.days {
+                    section { class: "mb-10",
+                        div { class: "flex flex-wrap items-center justify-between gap-2 mb-2",
+                            h2 { class: "text-2xl font-bold", "{day_title(day.date)}" }
+                            span {
+                                class: format!(
+                                    "px-3 py-1 border rounded-full text-base {}",
+                                    if day.certification == CertificationStatus::Certified {
+                                        "bg-green-100 text-green-800 border-green-300"
+                                    } else {
+                                        "bg-yellow-100 text-yellow-800 border-yellow-300"
+                                    },
+                                ),
+                                "{day.certification.label()}"
+                            }
+                        }
+                        if day.recorded {
+                            div { class: "grid grid-cols-2 md:grid-cols-4 gap-2 mb-2",
+                                p { "Off duty: {hours(day.totals.off_duty)}" }
+                                p { "Sleeper berth: {hours(day.totals.sleeper)}" }
+                                p { "Driving: {hours(day.totals.driving)}" }
+                                p { "On duty: {hours(day.totals.on_duty)}" }
+                            }
+                            if !day.categories.is_empty() {
+                                div { class: "grid grid-cols-2 md:grid-cols-4 gap-2 mb-2 text-gray-600",
+                                    p { "Personal conveyance: {hours(day.categories.personal_conveyance)}" }
+                                    p { "Yard moves: {hours(day.categories.yard_moves)}" }
+                                }
+                            }
+                            img { class: "w-full border", src: "{day.sheet}", alt: "Log sheet" }
+                        } else {
+                            p { class: "text-gray-600", "No records for this day." }
+                        }
+                    }
+                }
+            } else if let Some(message) = error() {
+                p { class: "text-xl", "{message}" }
+            } else if loading() {
+                div { class: "flex items-center space-x-2",
+                    Spinner {
+                        aria_label: "Loading spinner".to_string(),
+                        size: SpinnerSize::Md,
+                        dark_mode: false,
+                    }
+                    span { "Loading logs..." }
+                }
+            }
+        }
+    }
+}
@@ -5,6 +5,9 @@ use crate::components::toast::manager::ToastType;
 use crate::router::Route;
 use crate::server::auth::controller::{about_me, login_user};
 use crate::server::auth::response::LoginUserSchema;
+use crate::server::unidentified::controller::get_unidentified_driving;
+use crate::server::unidentified::model::UnidentifiedStatus;
+use crate::server::unidentified::request::GetUnidentifiedDrivingRequest;
 use crate::theme::Theme;
 use chrono::Duration;
 use dioxus::prelude::*;
@@ -93,6 +96,36 @@ pub fn Login() -> Element {
                                         )
                                         .clone(),
                                 );
+                                // Driving recorded while nobody was logged in waits for review.
+                                if let Ok(response) =
+                                    get_unidentified_driving(GetUnidentifiedDrivingRequest {
+                                        token: token.clone(),
+                                    })
+                                    .await
+                                {
+                                    let open = response
+                                        .data
+                                        .iter()
+                                        .filter(|record| {
+                                            record.status != UnidentifiedStatus::Assigned
+                                        })
+                                        .count();
+                                    if open > 0 {
+                                        toasts_manager.set(
+                                            toasts_manager()
+                                                .add_toast(
+                                                    "Unidentified Driving".into(),
+                                                    format!(
+                                                        "{} unidentified driving record(s) need your review in ELD Logs.",
+                                                        open
+                                                    ),
+                                                    ToastType::Warning,
+                                                    Some(Duration::seconds(10)),
+                                                )
+                                                .clone(),
+                                        );
+                                    }
+                                }
                                 loading.set(false);
                             }
                             Err(e) => {
@@ -4,6 +4,7 @@ use crate::components::navbar::HomeNavBar;
 use crate::components::navbar::LoginNavBar;
 use crate::pages::dashboard::Dashboard;
 use crate::pages::home::Home;
+use crate::pages::inspection::Inspection;
 use crate::pages::login::Login;
 use crate::pages::signup::Register;
 use crate::pages::trip::EditTrip;
@@ -28,4 +29,6 @@ pub enum Route {
     EditTrip { id: String },
     #[route("/dashboard")]
     Dashboard {},
+    #[route("/inspect/:token")]
+    Inspection { token: String },
 }
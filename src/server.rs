@@ -1,4 +1,13 @@
+pub(crate) mod alert;
 pub(crate) mod auth;
+pub(crate) mod carrier;
 pub(crate) mod common;
 pub(crate) mod conversation;
+pub(crate) mod export;
+pub(crate) mod hos;
+pub(crate) mod ifta;
+pub(crate) mod inspection;
+pub(crate) mod routing;
+pub(crate) mod telematics;
 pub(crate) mod trip;
+pub(crate) mod unidentified;
@@ -0,0 +1,4 @@
+pub(crate) mod controller;
+pub(crate) mod model;
+pub(crate) mod request;
+pub(crate) mod rules;
@@ -0,0 +1,290 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use bson::doc;
+use dioxus::prelude::*;
+use dioxus_logger::tracing;
+
+use crate::server::alert::model::{Alert, AlertKind, AlertTicketClaims};
+use crate::server::alert::request::{
+    AlertStreamQuery, DismissAlertRequest, GetAlertTicketRequest, GetAlertsRequest,
+};
+use crate::server::alert::rules::{alert_notices, VIOLATION_HOURS};
+use crate::server::auth::controller::auth;
+use crate::server::common::response::{ErrorResponse, SuccessResponse};
+use crate::server::hos::engine::evaluate;
+use crate::server::hos::model::DutyState;
+use crate::server::hos::stream::{periods_from_events, status_at};
+
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use chrono::Duration;
+use futures_util::TryStreamExt;
+use std::collections::VecDeque;
+use std::env;
+use std::str::FromStr;
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::server::hos::controller::{current_cycle_rule, load_duty_events, HISTORY_DAYS},
+    axum::extract::{Json, Query},
+    axum::http::StatusCode,
+    axum::response::sse::{Event, KeepAlive, Sse},
+    futures_util::Stream,
+    jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation},
+    std::convert::Infallible,
+    std::sync::OnceLock,
+    tokio::sync::broadcast,
+};
+
+// Approaching-limit alerts for the same rule are not repeated within this time.
+pub const ALERT_COOLDOWN_MINUTES: i64 = 60;
+// Streams re-check the clocks this often when no duty change wakes them earlier.
+pub const CHECK_SECONDS: u64 = 60;
+// A stream ticket only has to last until the browser opens the stream.
+pub const TICKET_SECONDS: i64 = 60;
+
+#[cfg(feature = "server")]
+fn ticket_secret() -> String {
+    format!(
+        "{}:alerts",
+        env::var("JWT_SECRET").expect("JWT_SECRET must be set")
+    )
+}
+
+#[cfg(feature = "server")]
+static DUTY_CHANGES: OnceLock<broadcast::Sender<ObjectId>> = OnceLock::new();
+
+#[cfg(feature = "server")]
+fn duty_changes() -> &'static broadcast::Sender<ObjectId> {
+    DUTY_CHANGES.get_or_init(|| broadcast::channel(256).0)
+}
+
+/// Wakes the alert streams of a driver whose duty-status stream changed.
+#[cfg(feature = "server")]
+pub fn notify_duty_change(driver_id: ObjectId) {
+    let _ = duty_changes().send(driver_id);
+}
+
+/// Evaluates the driver's clocks and records the alerts they call for; returns
+/// the ones not raised before.
+#[cfg(feature = "server")]
+pub async fn check_alerts(driver_id: ObjectId) -> Result<Vec<Alert>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<bson::Document>("alerts");
+
+    let now = Utc::now();
+    let cycle = current_cycle_rule(driver_id).await?;
+    let events = load_duty_events(driver_id, now - Duration::days(HISTORY_DAYS)).await?;
+    let report = evaluate(&periods_from_events(&events, now), cycle, now);
+    let driving = status_at(&events, now) == DutyState::Driving;
+
+    let mut raised = Vec::new();
+    for notice in alert_notices(&report, cycle, driving, now) {
+        // Violations are raised once; warnings again after the cooldown.
+        let mut filter = doc! {
+            "driverId": driver_id,
+            "kind": bson::to_bson(&notice.kind)?,
+            "rule": bson::to_bson(&notice.rule)?,
+        };
+        match notice.kind {
+            AlertKind::Violation => {
+                filter.insert("at", bson::DateTime::from_chrono(notice.at));
+            }
+            AlertKind::Approaching => {
+                filter.insert(
+                    "createdAt",
+                    doc! { "$gte": bson::DateTime::from_chrono(now - Duration::minutes(ALERT_COOLDOWN_MINUTES)) },
+                );
+            }
+        }
+
+        let id = ObjectId::new();
+        let mut insert = doc! {
+            "_id": id,
+            "message": notice.message.clone(),
+            "dismissed": false,
+            "createdAt": bson::DateTime::from_chrono(now),
+        };
+        if notice.kind == AlertKind::Approaching {
+            insert.insert("at", bson::DateTime::from_chrono(notice.at));
+        }
+        let result = collection
+            .update_one(filter, doc! { "$setOnInsert": insert })
+            .upsert(true)
+            .await?;
+
+        if result.upserted_id.is_some() {
+            raised.push(Alert {
+                id,
+                driver_id,
+                kind: notice.kind,
+                rule: notice.rule,
+                at: notice.at,
+                message: notice.message,
+                dismissed: false,
+                created_at: now,
+            });
+        }
+    }
+    Ok(raised)
+}
+
+// Resolves once the driver's duty status changes or the check interval passes.
+#[cfg(feature = "server")]
+async fn next_check(driver_id: ObjectId, changes: &mut broadcast::Receiver<ObjectId>) {
+    let change = async {
+        loop {
+            match changes.recv().await {
+                Ok(changed) if changed == driver_id => break,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => break,
+                Err(broadcast::error::RecvError::Closed) => std::future::pending::<()>().await,
+            }
+        }
+    };
+    let tick = tokio::time::sleep(std::time::Duration::from_secs(CHECK_SECONDS));
+    futures_util::future::select(std::pin::pin!(change), std::pin::pin!(tick)).await;
+}
+
+#[cfg(feature = "server")]
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Issues the ticket the dashboard opens the alert stream with. It is good for
+/// `TICKET_SECONDS` and for nothing but the stream.
+#[server]
+pub async fn get_alert_ticket(
+    req: GetAlertTicketRequest,
+) -> Result<SuccessResponse<String>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let now = Utc::now();
+    let claims = AlertTicketClaims {
+        sub: user.id.to_hex(),
+        iat: now.timestamp() as usize,
+        exp: (now + Duration::seconds(TICKET_SECONDS)).timestamp() as usize,
+    };
+    let ticket = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(ticket_secret().as_ref()),
+    )?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: ticket,
+    })
+}
+
+/// `GET /api/alerts?ticket=...`: server-sent `alert` events carrying each new alert
+/// as JSON, for as long as the dashboard stays open.
+#[cfg(feature = "server")]
+pub async fn alert_stream(
+    Query(query): Query<AlertStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let unauthorized = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                status: "fail".into(),
+                message: "Invalid or expired stream ticket".into(),
+            }),
+        )
+    };
+    let mut validation = Validation::default();
+    validation.leeway = 0;
+    let claims = decode::<AlertTicketClaims>(
+        &query.ticket,
+        &DecodingKey::from_secret(ticket_secret().as_ref()),
+        &validation,
+    )
+    .map_err(|_| unauthorized())?
+    .claims;
+    let driver_id = ObjectId::parse_str(&claims.sub).map_err(|_| unauthorized())?;
+
+    let changes = duty_changes().subscribe();
+    let stream = futures_util::stream::unfold(
+        (driver_id, changes, VecDeque::new(), true),
+        |(driver_id, mut changes, mut pending, mut due)| async move {
+            loop {
+                if let Some(alert) = pending.pop_front() {
+                    let event = Event::default()
+                        .event("alert")
+                        .json_data(&alert)
+                        .unwrap_or_default();
+                    return Some((Ok(event), (driver_id, changes, pending, due)));
+                }
+                if !due {
+                    next_check(driver_id, &mut changes).await;
+                }
+                due = false;
+                match check_alerts(driver_id).await {
+                    Ok(alerts) => pending.extend(alerts),
+                    Err(error) => tracing::error!("Alert check failed: {}", error),
+                }
+            }
+        },
+    );
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Alerts of the last day the driver has not dismissed, newest first.
+#[server]
+pub async fn get_alerts(
+    req: GetAlertsRequest,
+) -> Result<SuccessResponse<Vec<Alert>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<Alert>("alerts");
+
+    let alerts = collection
+        .find(doc! {
+            "driverId": user.id,
+            "dismissed": false,
+            "createdAt": { "$gte": bson::DateTime::from_chrono(Utc::now() - Duration::hours(VIOLATION_HOURS)) },
+        })
+        .sort(doc! { "createdAt": -1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: alerts,
+    })
+}
+
+#[server]
+pub async fn dismiss_alert(req: DismissAlertRequest) -> Result<SuccessResponse<()>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<Alert>("alerts");
+
+    let alert_id =
+        ObjectId::from_str(&req.alert_id).map_err(|_| ServerFnError::new("Invalid alert ID"))?;
+    let result = collection
+        .update_one(
+            doc! { "_id": alert_id, "driverId": user.id },
+            doc! { "$set": { "dismissed": true } },
+        )
+        .await?;
+    if result.matched_count == 0 {
+        return Err(ServerFnError::new("Alert not found"));
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: (),
+    })
+}
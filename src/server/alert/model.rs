@@ -0,0 +1,38 @@
+#![allow(non_snake_case)]
+
+use crate::server::hos::model::HosRule;
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    Approaching, // A limit will be reached soon at the current pace
+    Violation,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Alert {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "driverId")]
+    pub driver_id: ObjectId,
+    pub kind: AlertKind,
+    pub rule: HosRule,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub at: DateTime<Utc>, // When the violation happened, or when the warning was raised
+    pub message: String,
+    pub dismissed: bool,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+// Claims of a ticket that opens the alert stream. They are signed with a key of
+// their own and expire within a minute, so the URL the ticket travels in is
+// never a login token.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlertTicketClaims {
+    pub sub: String, // Driver ID
+    pub iat: usize,
+    pub exp: usize,
+}
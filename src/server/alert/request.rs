@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetAlertsRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DismissAlertRequest {
+    pub token: String,
+    pub alert_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetAlertTicketRequest {
+    pub token: String,
+}
+
+// Query of `GET /api/alerts`. Browsers cannot set headers on an event stream, so
+// a short-lived ticket from `get_alert_ticket` comes in the URL instead.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlertStreamQuery {
+    pub ticket: String,
+}
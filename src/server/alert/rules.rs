@@ -0,0 +1,143 @@
+use crate::server::alert::model::AlertKind;
+use crate::server::hos::model::{CycleRule, HosReport, HosRule};
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+// Drivers are warned this long before a limit.
+pub const WARNING_MINUTES: i64 = 30;
+// Violations older than this are on the log already and are not alerted.
+pub const VIOLATION_HOURS: i64 = 24;
+
+// An alert the HOS report calls for, before it is recorded.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AlertNotice {
+    pub kind: AlertKind,
+    pub rule: HosRule,
+    pub at: DateTime<Utc>,
+    pub message: String,
+}
+
+fn warning(rule: HosRule, cycle: CycleRule, minutes: i64) -> String {
+    match rule {
+        HosRule::DrivingLimit => {
+            format!("{} min of driving left on the 11-hour limit", minutes)
+        }
+        HosRule::DutyWindow => format!("{} min left in the 14-hour duty window", minutes),
+        HosRule::RestBreak => format!("{} min of driving left before a 30-minute break", minutes),
+        HosRule::Cycle => format!("{} min left on the {} cycle", minutes, cycle.label()),
+    }
+}
+
+/// Recent violations, and clocks within `WARNING_MINUTES` of running out while
+/// the driver is driving.
+pub fn alert_notices(
+    report: &HosReport,
+    cycle: CycleRule,
+    driving: bool,
+    now: DateTime<Utc>,
+) -> Vec<AlertNotice> {
+    let mut notices: Vec<AlertNotice> = report
+        .violations
+        .iter()
+        .filter(|violation| violation.at > now - Duration::hours(VIOLATION_HOURS))
+        .map(|violation| AlertNotice {
+            kind: AlertKind::Violation,
+            rule: violation.rule,
+            at: violation.at,
+            message: violation.message.clone(),
+        })
+        .collect();
+
+    if driving {
+        let clocks = &report.clocks;
+        for (rule, minutes) in [
+            (HosRule::DrivingLimit, clocks.drive_minutes),
+            (HosRule::DutyWindow, clocks.window_minutes),
+            (HosRule::RestBreak, clocks.break_minutes),
+            (HosRule::Cycle, clocks.cycle_minutes),
+        ] {
+            if minutes > 0 && minutes <= WARNING_MINUTES {
+                notices.push(AlertNotice {
+                    kind: AlertKind::Approaching,
+                    rule,
+                    at: now,
+                    message: warning(rule, cycle, minutes),
+                });
+            }
+        }
+    }
+    notices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::hos::model::{HosClocks, HosViolation};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 16, 0, 0).unwrap()
+    }
+
+    // Plenty left on every clock but the cycle.
+    fn report(cycle_minutes: i64) -> HosReport {
+        HosReport {
+            clocks: HosClocks {
+                drive_minutes: 300,
+                window_minutes: 360,
+                break_minutes: 120,
+                cycle_minutes,
+            },
+            ..HosReport::default()
+        }
+    }
+
+    fn violation(hours_ago: i64) -> HosViolation {
+        HosViolation {
+            rule: HosRule::DrivingLimit,
+            at: now() - Duration::hours(hours_ago),
+            over_minutes: 15,
+            message: "Drove 15 min past the 11-hour limit".to_string(),
+        }
+    }
+
+    #[test]
+    fn warns_within_thirty_minutes_while_driving() {
+        let notices = alert_notices(&report(30), CycleRule::SeventyEight, true, now());
+        assert_eq!(
+            notices,
+            [AlertNotice {
+                kind: AlertKind::Approaching,
+                rule: HosRule::Cycle,
+                at: now(),
+                message: "30 min left on the 70/8 cycle".to_string(),
+            }]
+        );
+        assert!(alert_notices(&report(31), CycleRule::SeventyEight, true, now()).is_empty());
+        // Spent clocks are violations, not warnings.
+        assert!(alert_notices(&report(0), CycleRule::SeventyEight, true, now()).is_empty());
+    }
+
+    #[test]
+    fn no_warnings_off_the_wheel() {
+        assert!(alert_notices(&report(10), CycleRule::SeventyEight, false, now()).is_empty());
+    }
+
+    #[test]
+    fn old_violations_are_not_repeated() {
+        let mut report = report(600);
+        report.violations = vec![violation(25), violation(24), violation(2)];
+        let notices = alert_notices(&report, CycleRule::SeventyEight, false, now());
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].kind, AlertKind::Violation);
+        assert_eq!(notices[0].at, now() - Duration::hours(2));
+    }
+
+    #[test]
+    fn cycle_warning_names_the_rule() {
+        let notices = alert_notices(&report(20), CycleRule::SixtySeven, true, now());
+        assert_eq!(notices[0].message, "20 min left on the 60/7 cycle");
+        let notices = alert_notices(&report(20), CycleRule::SeventyEight, true, now());
+        assert_eq!(notices[0].message, "20 min left on the 70/8 cycle");
+    }
+}
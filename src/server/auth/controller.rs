@@ -8,7 +8,7 @@ use chrono::prelude::*;
 use chrono::Duration;
 use dioxus::prelude::*;
 
-use crate::server::auth::model::{TokenClaims, User};
+use crate::server::auth::model::{TokenClaims, User, ROLE_DRIVER};
 use crate::server::auth::request::EditUserSchema;
 use crate::server::auth::response::{
     AuthResponse, DashboardResponse, LoginUserSchema, RegisterUserSchema, UserResponse,
@@ -56,10 +56,12 @@ pub async fn register_user(
         name: body.name,
         email: body.email.to_lowercase(),
         password: hashed_password,
-        role: "user".into(),
+        role: ROLE_DRIVER.into(),
         photo: "".into(),
-        eld_device_id: None,
         license_number: None,
+        carrier_id: None,
+        carrier_name: None,
+        truck_number: None,
         verified: false,
         created_at: Utc::now(),
         updated_at: Utc::now(),
@@ -245,6 +247,19 @@ pub async fn dashboard_overview() -> Result<SuccessResponse<DashboardResponse>,
     })
 }
 
+// The stored fields a profile edit may change, keyed as `User` serializes them.
+fn profile_fields(user: &User) -> bson::Document {
+    doc! {
+        "name": &user.name,
+        "email": &user.email,
+        "password": &user.password,
+        "photo": &user.photo,
+        "license_number": &user.license_number,
+        "truckNumber": &user.truck_number,
+        "updatedAt": bson::DateTime::from_chrono(user.updated_at),
+    }
+}
+
 #[server]
 pub async fn edit_profile(
     body: EditUserSchema,
@@ -286,8 +301,8 @@ pub async fn edit_profile(
         user.license_number = body.license_number;
     }
 
-    if let Some(ref eld_device_id) = body.eld_device_id {
-        user.eld_device_id = body.eld_device_id;
+    if let Some(ref truck_number) = body.truck_number {
+        user.truck_number = body.truck_number;
     }
 
     if let Some(new_email) = body.email {
@@ -354,15 +369,7 @@ pub async fn edit_profile(
     user_collection
         .update_one(
             doc! { "_id": user_id },
-            doc! { "$set": {
-                "name": &user.name,
-                "email": &user.email,
-                "password": &user.password,
-                "photo": &user.photo,
-                "license_number": &user.license_number,
-                "eld_device_id": &user.eld_device_id,
-                "updated_at": &user.updated_at,
-            }},
+            doc! { "$set": profile_fields(&user) },
         )
         .await
         .map_err(|_| ServerFnError::new("Failed to update user data"))?;
@@ -372,3 +379,33 @@ pub async fn edit_profile(
         data: UserResponse { user },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_fields_round_trip() {
+        let stored = User {
+            name: "Old Name".into(),
+            email: "old@example.com".into(),
+            ..Default::default()
+        };
+        let edited = User {
+            name: "New Name".into(),
+            email: "new@example.com".into(),
+            photo: "photo.png".into(),
+            license_number: Some("D1234567".into()),
+            truck_number: Some("T-42".into()),
+            updated_at: Utc.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap(),
+            ..stored.clone()
+        };
+
+        // Apply the `$set` to the stored document the way MongoDB would.
+        let mut document = bson::to_document(&stored).unwrap();
+        document.extend(profile_fields(&edited));
+        let reloaded: User = bson::from_document(document).unwrap();
+
+        assert_eq!(reloaded, edited);
+    }
+}
@@ -12,8 +12,13 @@ pub struct User {
     pub password: String,
     pub role: String,
     pub photo: String,
-    pub eld_device_id: Option<String>,
     pub license_number: Option<String>,
+    #[serde(rename = "carrierId", default)]
+    pub carrier_id: Option<ObjectId>, // Set by the server when the user creates or joins a carrier
+    #[serde(rename = "carrierName", default)]
+    pub carrier_name: Option<String>, // Copy of the carrier's name, for display
+    #[serde(rename = "truckNumber", default)]
+    pub truck_number: Option<String>,
     pub verified: bool,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
     pub created_at: DateTime<Utc>,
@@ -21,6 +26,22 @@ pub struct User {
     pub updated_at: DateTime<Utc>,
 }
 
+// Carrier users manage the logs of the drivers that belong to their carrier.
+pub const ROLE_CARRIER: &str = "carrier";
+pub const ROLE_DRIVER: &str = "user";
+
+impl User {
+    // The carrier this user manages, if they are a carrier user.
+    pub fn managed_carrier(&self) -> Option<ObjectId> {
+        self.carrier_id.filter(|_| self.role == ROLE_CARRIER)
+    }
+
+    pub fn is_carrier_of(&self, driver: &User) -> bool {
+        self.managed_carrier()
+            .is_some_and(|carrier_id| driver.carrier_id == Some(carrier_id))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
     pub sub: String,
@@ -6,7 +6,7 @@ pub struct EditUserSchema {
     pub name: Option<String>,
     pub email: Option<String>,
     pub license_number: Option<String>,
-    pub eld_device_id: Option<String>,
+    pub truck_number: Option<String>,
     pub photo: Option<String>,
     pub old_password: Option<String>,
     pub new_password: Option<String>,
@@ -0,0 +1,358 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use bson::doc;
+use dioxus::prelude::*;
+
+use crate::server::auth::controller::auth;
+use crate::server::auth::model::{User, ROLE_CARRIER, ROLE_DRIVER};
+use crate::server::carrier::model::{Carrier, CarrierPolicy};
+use crate::server::carrier::request::{
+    CreateCarrierRequest, GetCarrierPolicyRequest, GetCarrierRequest, JoinCarrierRequest,
+    UpdateCarrierMemberRequest, UpdateCarrierPolicyRequest,
+};
+use crate::server::carrier::response::CarrierMember;
+use crate::server::common::response::SuccessResponse;
+
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use futures_util::TryStreamExt;
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    rand::distributions::Alphanumeric,
+    rand::{thread_rng, Rng},
+};
+
+pub const INVITE_CODE_CHARS: usize = 10;
+
+#[cfg(feature = "server")]
+fn invite_code() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(INVITE_CODE_CHARS)
+        .map(|c| char::from(c).to_ascii_uppercase())
+        .collect()
+}
+
+// The carrier's policy, or the defaults when it has never set one.
+#[cfg(feature = "server")]
+pub async fn carrier_policy(carrier_id: ObjectId) -> Result<CarrierPolicy, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<CarrierPolicy>("carrier_policies");
+
+    Ok(collection
+        .find_one(doc! { "_id": carrier_id })
+        .await?
+        .unwrap_or(CarrierPolicy {
+            carrier_id,
+            ..Default::default()
+        }))
+}
+
+/// Policy of the carrier the user drives or works for.
+#[server]
+pub async fn get_carrier_policy(
+    req: GetCarrierPolicyRequest,
+) -> Result<SuccessResponse<CarrierPolicy>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let carrier_id = user
+        .carrier_id
+        .ok_or(ServerFnError::new("You do not belong to a carrier"))?;
+    let policy = carrier_policy(carrier_id).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: policy,
+    })
+}
+
+#[server]
+pub async fn update_carrier_policy(
+    req: UpdateCarrierPolicyRequest,
+) -> Result<SuccessResponse<CarrierPolicy>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let carrier_id = user.managed_carrier().ok_or(ServerFnError::new(
+        "Only carriers can change carrier policies",
+    ))?;
+    if req
+        .pc_daily_miles
+        .is_some_and(|miles| !miles.is_finite() || miles < 0.0)
+    {
+        return Err(ServerFnError::new("Invalid personal conveyance limit"));
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<CarrierPolicy>("carrier_policies");
+
+    let policy = CarrierPolicy {
+        carrier_id,
+        cycle_rule: req.cycle_rule,
+        pc_daily_miles: req.pc_daily_miles,
+        updated_by: Some(user.id),
+        updated_at: Utc::now(),
+    };
+    collection
+        .replace_one(doc! { "_id": policy.carrier_id }, policy.clone())
+        .upsert(true)
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: policy,
+    })
+}
+
+/// Registers a carrier. The user who creates it becomes its first carrier user.
+#[server]
+pub async fn create_carrier(
+    req: CreateCarrierRequest,
+) -> Result<SuccessResponse<Carrier>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ServerFnError::new("The carrier name is required"));
+    }
+    if user.carrier_id.is_some() {
+        return Err(ServerFnError::new("You already belong to a carrier"));
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let carrier_collection = db.collection::<Carrier>("carriers");
+    let user_collection = db.collection::<User>("users");
+
+    let now = Utc::now();
+    let carrier = Carrier {
+        id: ObjectId::new(),
+        name,
+        invite_code: invite_code(),
+        created_by: user.id,
+        created_at: now,
+        updated_at: now,
+    };
+    carrier_collection.insert_one(carrier.clone()).await?;
+
+    let joined = user_collection
+        .update_one(
+            doc! { "_id": user.id, "carrierId": null },
+            doc! { "$set": {
+                "role": ROLE_CARRIER,
+                "carrierId": carrier.id,
+                "carrierName": &carrier.name,
+                "updatedAt": bson::DateTime::from_chrono(now),
+            }},
+        )
+        .await?;
+    if joined.modified_count == 0 {
+        carrier_collection
+            .delete_one(doc! { "_id": carrier.id })
+            .await?;
+        return Err(ServerFnError::new("You already belong to a carrier"));
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: carrier,
+    })
+}
+
+/// Joins the carrier whose invite code the driver was given.
+#[server]
+pub async fn join_carrier(
+    req: JoinCarrierRequest,
+) -> Result<SuccessResponse<Carrier>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    if user.carrier_id.is_some() {
+        return Err(ServerFnError::new("You already belong to a carrier"));
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let carrier_collection = db.collection::<Carrier>("carriers");
+    let user_collection = db.collection::<User>("users");
+
+    let mut carrier = carrier_collection
+        .find_one(doc! { "inviteCode": req.invite_code.trim().to_ascii_uppercase() })
+        .await?
+        .ok_or(ServerFnError::new("Invalid invite code"))?;
+
+    let joined = user_collection
+        .update_one(
+            doc! { "_id": user.id, "carrierId": null },
+            doc! { "$set": {
+                "role": ROLE_DRIVER,
+                "carrierId": carrier.id,
+                "carrierName": &carrier.name,
+                "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+            }},
+        )
+        .await?;
+    if joined.modified_count == 0 {
+        return Err(ServerFnError::new("You already belong to a carrier"));
+    }
+
+    carrier.invite_code.clear();
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: carrier,
+    })
+}
+
+/// The user's carrier. The invite code is left out for drivers.
+#[server]
+pub async fn get_carrier(
+    req: GetCarrierRequest,
+) -> Result<SuccessResponse<Carrier>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let carrier_id = user
+        .carrier_id
+        .ok_or(ServerFnError::new("You do not belong to a carrier"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<Carrier>("carriers");
+
+    let mut carrier = collection
+        .find_one(doc! { "_id": carrier_id })
+        .await?
+        .ok_or(ServerFnError::new("Carrier not found"))?;
+    if user.managed_carrier().is_none() {
+        carrier.invite_code.clear();
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: carrier,
+    })
+}
+
+/// Drivers and carrier users of the carrier the user manages.
+#[server]
+pub async fn get_carrier_members(
+    req: GetCarrierRequest,
+) -> Result<SuccessResponse<Vec<CarrierMember>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let carrier_id = user
+        .managed_carrier()
+        .ok_or(ServerFnError::new("Only carrier users can list members"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<User>("users");
+
+    let members: Vec<User> = collection
+        .find(doc! { "carrierId": carrier_id })
+        .sort(doc! { "name": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: members
+            .into_iter()
+            .map(|member| CarrierMember {
+                id: member.id,
+                name: member.name,
+                email: member.email,
+                role: member.role,
+            })
+            .collect(),
+    })
+}
+
+/// Makes a member of the user's carrier a carrier user too.
+#[server]
+pub async fn grant_carrier_role(
+    req: UpdateCarrierMemberRequest,
+) -> Result<SuccessResponse<ObjectId>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let carrier_id = user.managed_carrier().ok_or(ServerFnError::new(
+        "Only carrier users can grant the carrier role",
+    ))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<User>("users");
+
+    let member_id = ObjectId::parse_str(&req.member_id)?;
+    let granted = collection
+        .update_one(
+            doc! { "_id": member_id, "carrierId": carrier_id },
+            doc! { "$set": {
+                "role": ROLE_CARRIER,
+                "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+            }},
+        )
+        .await?;
+    if granted.matched_count == 0 {
+        return Err(ServerFnError::new("Member not found"));
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: member_id,
+    })
+}
+
+/// Removes a driver or another carrier user from the user's carrier.
+#[server]
+pub async fn remove_carrier_member(
+    req: UpdateCarrierMemberRequest,
+) -> Result<SuccessResponse<ObjectId>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let carrier_id = user
+        .managed_carrier()
+        .ok_or(ServerFnError::new("Only carrier users can remove members"))?;
+
+    let member_id = ObjectId::parse_str(&req.member_id)?;
+    if member_id == user.id {
+        return Err(ServerFnError::new("Another carrier user has to remove you"));
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<User>("users");
+
+    let removed = collection
+        .update_one(
+            doc! { "_id": member_id, "carrierId": carrier_id },
+            doc! { "$set": {
+                "role": ROLE_DRIVER,
+                "carrierId": null,
+                "carrierName": null,
+                "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+            }},
+        )
+        .await?;
+    if removed.matched_count == 0 {
+        return Err(ServerFnError::new("Member not found"));
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: member_id,
+    })
+}
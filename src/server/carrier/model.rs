@@ -0,0 +1,38 @@
+#![allow(non_snake_case)]
+
+use crate::server::hos::model::CycleRule;
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// A motor carrier. Its ID is assigned by the server and is what ties drivers and
+// carrier users to it; the name is only for display.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Carrier {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String,
+    #[serde(rename = "inviteCode")]
+    pub invite_code: String, // Drivers join with it; only shown to carrier users
+    #[serde(rename = "createdBy")]
+    pub created_by: ObjectId,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// Rules a carrier sets for all of its drivers, keyed on the carrier's ID.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct CarrierPolicy {
+    #[serde(rename = "_id")]
+    pub carrier_id: ObjectId,
+    #[serde(rename = "cycleRule", default)]
+    pub cycle_rule: CycleRule, // The 60/7 or 70/8 cycle every driver of the carrier is on
+    #[serde(rename = "pcDailyMiles")]
+    pub pc_daily_miles: Option<f64>, // Personal conveyance allowed per day; None for no limit
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<ObjectId>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,37 @@
+use crate::server::hos::model::CycleRule;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetCarrierPolicyRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateCarrierPolicyRequest {
+    pub token: String,
+    pub cycle_rule: CycleRule,
+    pub pc_daily_miles: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateCarrierRequest {
+    pub token: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JoinCarrierRequest {
+    pub token: String,
+    pub invite_code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetCarrierRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateCarrierMemberRequest {
+    pub token: String,
+    pub member_id: String,
+}
@@ -0,0 +1,11 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+// A user who belongs to a carrier, as its carrier users see them.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CarrierMember {
+    pub id: ObjectId,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+}
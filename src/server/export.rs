@@ -0,0 +1,5 @@
+pub(crate) mod controller;
+pub(crate) mod output;
+pub(crate) mod request;
+pub(crate) mod response;
+pub(crate) mod validator;
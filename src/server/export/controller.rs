@@ -0,0 +1,255 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use bson::doc;
+use dioxus::prelude::*;
+
+use crate::server::auth::controller::auth;
+use crate::server::common::response::SuccessResponse;
+use crate::server::export::output::*;
+use crate::server::export::request::{ExportEldFileRequest, ValidateEldFileRequest};
+use crate::server::export::response::EldFileResponse;
+use crate::server::export::validator::{validate, ValidationIssue};
+use crate::server::hos::engine::normalize;
+use crate::server::hos::model::{DutyEventOrigin, DutyEventType, DutyPeriod};
+use crate::server::hos::stream::periods_from_events;
+use crate::server::telematics::model::DeviceSession;
+use crate::server::trip::model::{DailyLog, LogEntry};
+use crate::server::trip::printout::data_url;
+use crate::server::unidentified::model::UnidentifiedDriving;
+
+use chrono::prelude::*;
+use chrono::Duration;
+use futures_util::TryStreamExt;
+use std::collections::HashSet;
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::server::hos::controller::{cycle_rule_for, load_duty_events},
+    crate::server::telematics::controller::driver_device,
+};
+
+#[server]
+pub async fn export_eld_file(
+    req: ExportEldFileRequest,
+) -> Result<SuccessResponse<EldFileResponse>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    if req.to <= req.from {
+        return Err(ServerFnError::new("The export range is empty"));
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let daily_collection = db.collection::<DailyLog>("daily_logs");
+    let entry_collection = db.collection::<LogEntry>("log_entries");
+    let session_collection = db.collection::<DeviceSession>("device_sessions");
+    let unidentified_collection = db.collection::<UnidentifiedDriving>("unidentified_driving");
+
+    // The duty-status stream first; generated daily sheets fill the days without any.
+    let events = load_duty_events(user.id, req.from).await?;
+    let mut records: Vec<DutyRecord> = periods_from_events(&events, req.to.min(Utc::now()))
+        .into_iter()
+        .map(|period| {
+            let event = events
+                .iter()
+                .filter(|event| {
+                    event.active
+                        && event.event_type == DutyEventType::DutyStatus
+                        && event.at == period.start
+                })
+                .max_by_key(|event| event.sequence);
+            DutyRecord {
+                origin: match event.map(|event| event.origin) {
+                    Some(DutyEventOrigin::Auto) => ORIGIN_AUTOMATIC,
+                    Some(DutyEventOrigin::CarrierEdit) => ORIGIN_OTHER_USER,
+                    Some(DutyEventOrigin::Unidentified) => ORIGIN_UNIDENTIFIED,
+                    _ => ORIGIN_DRIVER,
+                },
+                odometer: event.and_then(|event| event.odometer_reading),
+                note: event
+                    .map(|event| event.note.clone())
+                    .filter(|note| !note.is_empty()),
+                period,
+            }
+        })
+        .collect();
+    let recorded_days: HashSet<NaiveDate> = records
+        .iter()
+        .map(|record| record.period.start.date_naive())
+        .collect();
+
+    let daily_logs: Vec<DailyLog> = daily_collection
+        .find(doc! { "driverId": user.id })
+        .sort(doc! { "logDate": 1 })
+        .await?
+        .try_collect()
+        .await?;
+    let mut certifications = Vec::new();
+    for log in daily_logs
+        .iter()
+        .filter(|log| log.log_date + Duration::days(1) > req.from && log.log_date < req.to)
+    {
+        if let (Some(_), Some(certified_at)) = (&log.signature, log.certified_at) {
+            certifications.push((certified_at, log.log_date.date_naive()));
+        }
+        if recorded_days.contains(&log.log_date.date_naive()) {
+            continue;
+        }
+        let entries: Vec<LogEntry> = entry_collection
+            .find(doc! { "logId": log.id })
+            .await?
+            .try_collect()
+            .await?;
+        for period in DutyPeriod::from_log_entries(&entries, log.log_date + Duration::days(1)) {
+            records.push(DutyRecord {
+                period,
+                origin: ORIGIN_AUTOMATIC,
+                odometer: None,
+                note: None,
+            });
+        }
+    }
+
+    // Normalizing trims overlaps; each trimmed period keeps the details of its record.
+    let normalized = normalize(
+        &records
+            .iter()
+            .map(|record| record.period.clone())
+            .collect::<Vec<_>>(),
+    );
+    let records: Vec<DutyRecord> = normalized
+        .into_iter()
+        .filter(|period| period.end > req.from && period.start < req.to)
+        .map(|mut period| {
+            period.start = period.start.max(req.from);
+            let source = records.iter().find(|record| {
+                record.period.status == period.status
+                    && record.period.start <= period.start
+                    && record.period.end >= period.end
+            });
+            DutyRecord {
+                origin: source.map_or(ORIGIN_DRIVER, |record| record.origin),
+                odometer: source.and_then(|record| record.odometer),
+                note: source.and_then(|record| record.note.clone()),
+                period,
+            }
+        })
+        .collect();
+
+    let events = duty_events(&records);
+    let certifications: Vec<Certification> = certifications
+        .into_iter()
+        .enumerate()
+        .map(|(index, (at, date))| Certification {
+            sequence: (events.len() + index + 1) as u16,
+            at,
+            date,
+        })
+        .collect();
+
+    // Driving recorded while nobody was logged in to the devices the driver used,
+    // unless it has since been assigned to a driver.
+    let devices: Vec<String> = session_collection
+        .find(doc! {
+            "driverId": user.id,
+            "startedAt": { "$lt": bson::DateTime::from_chrono(req.to) },
+            "$or": [
+                { "endedAt": null },
+                { "endedAt": { "$gt": bson::DateTime::from_chrono(req.from) } },
+            ],
+        })
+        .await?
+        .try_collect::<Vec<DeviceSession>>()
+        .await?
+        .into_iter()
+        .map(|session| session.eld_device_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let drives: Vec<UnidentifiedDrive> = unidentified_collection
+        .find(doc! {
+            "eldDeviceId": { "$in": devices },
+            "status": { "$ne": "Assigned" },
+            "start": { "$lt": bson::DateTime::from_chrono(req.to) },
+            "end": { "$gt": bson::DateTime::from_chrono(req.from) },
+        })
+        .sort(doc! { "start": 1 })
+        .await?
+        .try_collect::<Vec<UnidentifiedDriving>>()
+        .await?
+        .into_iter()
+        .map(|record| UnidentifiedDrive {
+            start: record.start,
+            end: record.end,
+            start_location: record.start_location,
+            end_location: record.end_location,
+            start_odometer: record.start_odometer,
+            end_odometer: record.end_odometer,
+        })
+        .collect();
+    let unidentified =
+        unidentified_events(&drives, (events.len() + certifications.len() + 1) as u16);
+
+    let (first_name, last_name) = user
+        .name
+        .trim()
+        .rsplit_once(' ')
+        .map(|(first, last)| (first.to_string(), last.to_string()))
+        .unwrap_or((String::new(), user.name.clone()));
+
+    let file = OutputFile {
+        header: FileHeader {
+            driver_last_name: last_name,
+            driver_first_name: first_name,
+            eld_username: user.email.clone(),
+            license_state: String::new(),
+            license_number: user.license_number.clone().unwrap_or_default(),
+            power_unit_number: user.truck_number.clone().unwrap_or_default(),
+            vin: String::new(),
+            usdot_number: String::new(),
+            carrier_name: user.carrier_name.clone().unwrap_or_default(),
+            multiday_basis: cycle_rule_for(&user).await?.days(),
+            shipping_document: String::new(), // Trips carry no shipping document number
+            eld_identifier: driver_device(user.id).await?.unwrap_or_default(),
+            comment: req.comment,
+            generated_at: Utc::now(),
+        },
+        events,
+        certifications,
+        unidentified,
+    };
+
+    let content = render(&file);
+    if let Some(issue) = validate(&content).first() {
+        return Err(ServerFnError::new(format!(
+            "Generated ELD file is invalid at line {}: {}",
+            issue.line, issue.message
+        )));
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: EldFileResponse {
+            file_name: file_name(&file.header),
+            data_url: data_url("text/csv", content.as_bytes()),
+            events: file.events.len(),
+        },
+    })
+}
+
+#[server]
+pub async fn validate_eld_file(
+    req: ValidateEldFileRequest,
+) -> Result<SuccessResponse<Vec<ValidationIssue>>, ServerFnError> {
+    auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: validate(&req.content),
+    })
+}
@@ -0,0 +1,481 @@
+use crate::server::hos::model::{DutyPeriod, DutyState};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Section headers of the ELD output file, in the order they must appear
+// (49 CFR 395 Subpart B, Appendix A, 4.8.2.1).
+pub const SECTIONS: [&str; 11] = [
+    "ELD File Header Segment:",
+    "User List:",
+    "CMV List:",
+    "ELD Event List:",
+    "ELD Event Annotations or Comments:",
+    "Driver's Certification/Recertification Actions:",
+    "Malfunctions and Data Diagnostic Events:",
+    "ELD Login/Logout Report:",
+    "CMV Engine Power-Up and Shut Down Activity:",
+    "Unidentified Driver Profile Records:",
+    "End of File:",
+];
+
+pub const LINE_END: &str = "\r\n";
+
+// Event types and codes (Appendix A, 7.25 and 7.20).
+pub const EVENT_DUTY_STATUS: u8 = 1;
+pub const EVENT_SPECIAL_CATEGORY: u8 = 3;
+pub const RECORD_ACTIVE: u8 = 1;
+pub const ORIGIN_AUTOMATIC: u8 = 1;
+pub const ORIGIN_DRIVER: u8 = 2;
+pub const ORIGIN_OTHER_USER: u8 = 3; // Edit requested by someone other than the driver
+pub const ORIGIN_UNIDENTIFIED: u8 = 4; // Assumed from the unidentified driver profile
+
+// The driver is always the first user and the power unit the first CMV.
+pub const DRIVER_ORDER: u32 = 1;
+pub const CMV_ORDER: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FileHeader {
+    pub driver_last_name: String,
+    pub driver_first_name: String,
+    pub eld_username: String,
+    pub license_state: String,
+    pub license_number: String,
+    pub power_unit_number: String,
+    pub vin: String,
+    pub usdot_number: String,
+    pub carrier_name: String,
+    pub multiday_basis: i64, // 7 or 8 days
+    pub shipping_document: String,
+    pub eld_identifier: String,
+    pub comment: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutputEvent {
+    pub sequence: u16,
+    pub origin: u8,
+    pub event_type: u8,
+    pub event_code: u8,
+    pub at: DateTime<Utc>,
+    pub vehicle_miles: u64,
+    pub location: String,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Certification {
+    pub sequence: u16,
+    pub at: DateTime<Utc>,
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OutputFile {
+    pub header: FileHeader,
+    pub events: Vec<OutputEvent>,
+    pub certifications: Vec<Certification>,
+    pub unidentified: Vec<OutputEvent>, // Events of the unidentified driver profile
+}
+
+// A stretch of duty status and where it came from.
+#[derive(Debug, Clone)]
+pub struct DutyRecord {
+    pub period: DutyPeriod,
+    pub origin: u8,
+    pub odometer: Option<f64>,
+    pub note: Option<String>,
+}
+
+/// Event type and code for a change of duty status. Personal conveyance and yard
+/// moves are recorded as off duty and on duty respectively, each with a separate
+/// special-category event (see `special_category`).
+pub fn duty_event_code(status: DutyState) -> u8 {
+    match status {
+        DutyState::OffDuty | DutyState::PersonalConveyance => 1,
+        DutyState::Sleeper => 2,
+        DutyState::Driving => 3,
+        DutyState::OnDuty | DutyState::YardMove => 4,
+    }
+}
+
+// Special driving category codes: 1 personal conveyance, 2 yard moves, 0 cleared.
+pub fn special_category(status: DutyState) -> u8 {
+    match status {
+        DutyState::PersonalConveyance => 1,
+        DutyState::YardMove => 2,
+        _ => 0,
+    }
+}
+
+/// Turns normalized duty records into the event list, numbering events from 1.
+/// A special driving category is indicated after the duty status it applies to
+/// and cleared when the driver leaves it.
+pub fn duty_events(records: &[DutyRecord]) -> Vec<OutputEvent> {
+    let mut events: Vec<OutputEvent> = Vec::new();
+    let mut previous: Option<(u8, u8)> = None;
+    let mut vehicle_miles = 0;
+
+    for record in records {
+        if let Some(odometer) = record.odometer {
+            vehicle_miles = odometer.max(0.0) as u64;
+        }
+        let code = duty_event_code(record.period.status);
+        let category = special_category(record.period.status);
+
+        let mut push = |event_type: u8, event_code: u8, comment: Option<String>| {
+            events.push(OutputEvent {
+                sequence: events.len() as u16 + 1,
+                origin: record.origin,
+                event_type,
+                event_code,
+                at: record.period.start,
+                vehicle_miles,
+                location: record.period.location.clone(),
+                comment,
+            });
+        };
+
+        if previous.map(|(code, _)| code) != Some(code) {
+            push(EVENT_DUTY_STATUS, code, record.note.clone());
+        }
+        if previous.map_or(0, |(_, category)| category) != category {
+            push(EVENT_SPECIAL_CATEGORY, category, None);
+        }
+        previous = Some((code, category));
+    }
+    events
+}
+
+// Driving recorded on the vehicle's ELD while no driver was logged in.
+#[derive(Debug, Clone)]
+pub struct UnidentifiedDrive {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub start_location: String,
+    pub end_location: String,
+    pub start_odometer: Option<f64>,
+    pub end_odometer: Option<f64>,
+}
+
+/// Events of the unidentified driver profile: driving from the start of each
+/// drive and on duty from its end, numbered from `first_sequence`.
+pub fn unidentified_events(drives: &[UnidentifiedDrive], first_sequence: u16) -> Vec<OutputEvent> {
+    let mut events: Vec<OutputEvent> = Vec::new();
+    let mut vehicle_miles = 0;
+    for drive in drives {
+        let stops = [
+            (drive.start, &drive.start_location, drive.start_odometer, 3),
+            (drive.end, &drive.end_location, drive.end_odometer, 4),
+        ];
+        for (at, location, odometer, event_code) in stops {
+            if let Some(odometer) = odometer {
+                vehicle_miles = odometer.max(0.0) as u64;
+            }
+            events.push(OutputEvent {
+                sequence: first_sequence + events.len() as u16,
+                origin: ORIGIN_AUTOMATIC,
+                event_type: EVENT_DUTY_STATUS,
+                event_code,
+                at,
+                vehicle_miles,
+                location: location.clone(),
+                comment: None,
+            });
+        }
+    }
+    events
+}
+
+// Character values used by the check value calculations (Appendix A, table 3):
+// digits and letters count from '0', every other character counts as zero.
+fn character_value(c: char) -> u32 {
+    if c.is_ascii_alphanumeric() {
+        c as u32 - '0' as u32
+    } else {
+        0
+    }
+}
+
+fn character_sum(fields: &[&str]) -> u32 {
+    fields
+        .iter()
+        .flat_map(|field| field.chars())
+        .map(character_value)
+        .sum()
+}
+
+/// Event data check value (Appendix A, 4.4.5.1).
+pub fn event_check(fields: &[&str]) -> u8 {
+    (character_sum(fields) as u8).rotate_left(3) ^ 0xC3
+}
+
+/// Line data check value (Appendix A, 4.4.5.2).
+pub fn line_check(line: &str) -> u8 {
+    (character_sum(&[line]) as u8).rotate_left(3) ^ 0x96
+}
+
+/// File data check value over all line data check values (Appendix A, 4.4.5.3).
+pub fn file_check(line_checks: &[u8]) -> u16 {
+    let sum = line_checks
+        .iter()
+        .fold(0u16, |sum, check| sum.wrapping_add(*check as u16));
+    sum.rotate_left(3) ^ 0x969C
+}
+
+pub fn format_date(at: DateTime<Utc>) -> String {
+    at.format("%m%d%y").to_string()
+}
+
+pub fn format_time(at: DateTime<Utc>) -> String {
+    at.format("%H%M%S").to_string()
+}
+
+// Fields are comma-delimited and free text may not carry the delimiter or line breaks.
+fn field(text: &str, max: usize) -> String {
+    text.chars()
+        .map(|c| if c == ',' || c.is_control() { ' ' } else { c })
+        .take(max)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+struct Writer {
+    output: String,
+    checks: Vec<u8>,
+}
+
+impl Writer {
+    fn section(&mut self, title: &str) {
+        self.output.push_str(title);
+        self.output.push_str(LINE_END);
+    }
+
+    fn line(&mut self, fields: &[String]) {
+        let line = fields.join(",");
+        let check = line_check(&line);
+        self.output
+            .push_str(&format!("{},{:02X}{}", line, check, LINE_END));
+        self.checks.push(check);
+    }
+}
+
+/// Serializes the records into the comma-delimited ELD output file.
+///
+/// Positions are not recorded by this app, so latitude and longitude are reported
+/// as unavailable ("X") and engine hours as zero.
+pub fn render(file: &OutputFile) -> String {
+    let header = &file.header;
+    let generated_at = header.generated_at;
+    let mut writer = Writer {
+        output: String::new(),
+        checks: Vec::new(),
+    };
+
+    writer.section(SECTIONS[0]);
+    writer.line(&[
+        field(&header.driver_last_name, 35),
+        field(&header.driver_first_name, 35),
+        field(&header.eld_username, 60),
+        field(&header.license_state, 2),
+        field(&header.license_number, 20),
+    ]);
+    // No co-driver.
+    writer.line(&[String::new(), String::new(), String::new()]);
+    writer.line(&[
+        field(&header.power_unit_number, 10),
+        field(&header.vin, 18),
+        String::new(),
+    ]);
+    writer.line(&[
+        field(&header.usdot_number, 9),
+        field(&header.carrier_name, 120),
+        header.multiday_basis.to_string(),
+        "000000".to_string(), // 24-hour period starts at midnight
+        "00".to_string(),     // Records are kept in UTC
+    ]);
+    writer.line(&[field(&header.shipping_document, 40), "0".to_string()]);
+    writer.line(&[
+        format_date(generated_at),
+        format_time(generated_at),
+        "X".to_string(),
+        "X".to_string(),
+        file.events
+            .last()
+            .map(|event| event.vehicle_miles)
+            .unwrap_or_default()
+            .to_string(),
+        "0.0".to_string(),
+    ]);
+    writer.line(&[
+        String::new(),
+        field(&header.eld_identifier, 6),
+        String::new(),
+        field(&header.comment, 60),
+    ]);
+
+    writer.section(SECTIONS[1]);
+    writer.line(&[
+        DRIVER_ORDER.to_string(),
+        "D".to_string(),
+        field(&header.driver_last_name, 35),
+        field(&header.driver_first_name, 35),
+    ]);
+
+    writer.section(SECTIONS[2]);
+    writer.line(&[
+        CMV_ORDER.to_string(),
+        field(&header.power_unit_number, 10),
+        field(&header.vin, 18),
+    ]);
+
+    writer.section(SECTIONS[3]);
+    for event in &file.events {
+        let fields = event_fields(event);
+        let check = event_check(&[
+            &fields[3],
+            &fields[4],
+            &fields[5],
+            &fields[6],
+            &fields[7],
+            &fields[8],
+            &fields[9],
+            &fields[10],
+            &field(&header.power_unit_number, 10),
+            &field(&header.eld_username, 60),
+        ]);
+        let mut fields = fields;
+        fields.push(format!("{:02X}", check));
+        writer.line(&fields);
+    }
+
+    writer.section(SECTIONS[4]);
+    for event in file.events.iter().filter(|event| event.comment.is_some()) {
+        writer.line(&[
+            format!("{:X}", event.sequence),
+            field(&header.eld_username, 60),
+            field(event.comment.as_deref().unwrap_or_default(), 60),
+            format_date(event.at),
+            format_time(event.at),
+            field(&event.location, 60),
+        ]);
+    }
+
+    writer.section(SECTIONS[5]);
+    for certification in &file.certifications {
+        writer.line(&[
+            format!("{:X}", certification.sequence),
+            "1".to_string(),
+            format_date(certification.at),
+            format_time(certification.at),
+            certification.date.format("%m%d%y").to_string(),
+            CMV_ORDER.to_string(),
+        ]);
+    }
+
+    // Malfunctions, logins and engine power cycles are not captured yet; their
+    // sections are present but empty.
+    for section in &SECTIONS[6..9] {
+        writer.section(section);
+    }
+
+    // The unidentified driver profile has no username.
+    writer.section(SECTIONS[9]);
+    for event in &file.unidentified {
+        let fields = unidentified_fields(event);
+        let check = event_check(&[
+            &fields[3],
+            &fields[4],
+            &fields[5],
+            &fields[6],
+            &fields[7],
+            &fields[8],
+            &fields[9],
+            &fields[10],
+            &field(&header.power_unit_number, 10),
+            "",
+        ]);
+        let mut fields = fields;
+        fields.push(format!("{:02X}", check));
+        writer.line(&fields);
+    }
+
+    writer.section(SECTIONS[10]);
+    let check = file_check(&writer.checks);
+    writer
+        .output
+        .push_str(&format!("{:04X}{}", check, LINE_END));
+    writer.output
+}
+
+fn event_fields(event: &OutputEvent) -> Vec<String> {
+    vec![
+        format!("{:X}", event.sequence),
+        RECORD_ACTIVE.to_string(),
+        event.origin.to_string(),
+        event.event_type.to_string(),
+        event.event_code.to_string(),
+        format_date(event.at),
+        format_time(event.at),
+        event.vehicle_miles.to_string(),
+        "0.0".to_string(), // Elapsed engine hours
+        "X".to_string(),
+        "X".to_string(),
+        "0".to_string(), // Distance since last valid coordinates
+        CMV_ORDER.to_string(),
+        DRIVER_ORDER.to_string(),
+        "0".to_string(), // Malfunction indicator
+        "0".to_string(), // Data diagnostic indicator
+    ]
+}
+
+fn unidentified_fields(event: &OutputEvent) -> Vec<String> {
+    vec![
+        format!("{:X}", event.sequence),
+        RECORD_ACTIVE.to_string(),
+        event.origin.to_string(),
+        event.event_type.to_string(),
+        event.event_code.to_string(),
+        format_date(event.at),
+        format_time(event.at),
+        event.vehicle_miles.to_string(),
+        "0.0".to_string(), // Elapsed engine hours
+        "X".to_string(),
+        "X".to_string(),
+        "0".to_string(), // Distance since last valid coordinates
+        CMV_ORDER.to_string(),
+        "0".to_string(), // Malfunction indicator
+    ]
+}
+
+/// Output file name: up to five letters of the last name, the last four characters
+/// of the licence number, the file date and a short comment (Appendix A, 4.8.2.2).
+pub fn file_name(header: &FileHeader) -> String {
+    let last_name: String = header
+        .driver_last_name
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .take(5)
+        .collect();
+    let license: Vec<char> = header
+        .license_number
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    let license: String = license[license.len().saturating_sub(4)..].iter().collect();
+    let comment: String = header
+        .comment
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(10)
+        .collect();
+
+    format!(
+        "{}{:0>4}{}-{}.csv",
+        last_name,
+        license,
+        format_date(header.generated_at),
+        comment
+    )
+}
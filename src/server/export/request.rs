@@ -0,0 +1,16 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportEldFileRequest {
+    pub token: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub comment: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ValidateEldFileRequest {
+    pub token: String,
+    pub content: String,
+}
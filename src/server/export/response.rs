@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EldFileResponse {
+    pub file_name: String,
+    pub data_url: String,
+    pub events: usize,
+}
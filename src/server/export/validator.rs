@@ -0,0 +1,340 @@
+use crate::server::export::output::{event_check, file_check, line_check, SECTIONS};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub line: usize, // 1-based
+    pub message: String,
+}
+
+fn issue(line: usize, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Checks an ELD output file against the spec's structure and check value rules:
+/// section order, line data check values, event data check values, unique event
+/// sequence IDs and the file data check value.
+pub fn validate(content: &str) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let lines: Vec<&str> = content
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    let lines = match lines.split_last() {
+        Some((last, rest)) if last.is_empty() => rest,
+        _ => &lines[..],
+    };
+
+    let mut section: Option<usize> = None;
+    let mut line_checks = Vec::new();
+    let mut header_lines: Vec<Vec<&str>> = Vec::new();
+    let mut cmvs: Vec<Vec<&str>> = Vec::new();
+    let mut events: Vec<(usize, Vec<&str>)> = Vec::new();
+    let mut unidentified: Vec<(usize, Vec<&str>)> = Vec::new();
+    let mut file_check_line: Option<(usize, &str)> = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let number = index + 1;
+
+        if let Some(position) = SECTIONS.iter().position(|title| title == line) {
+            let expected = section.map_or(0, |current| current + 1);
+            if position != expected {
+                issues.push(issue(
+                    number,
+                    format!("Expected \"{}\"", SECTIONS.get(expected).unwrap_or(&"")),
+                ));
+            }
+            section = Some(position);
+            continue;
+        }
+
+        match section {
+            None => {
+                issues.push(issue(number, "Content before the file header segment"));
+                continue;
+            }
+            Some(10) => {
+                if file_check_line.is_some() {
+                    issues.push(issue(number, "Content after the file data check value"));
+                }
+                file_check_line = Some((number, line));
+                continue;
+            }
+            _ => (),
+        }
+
+        let Some((body, check)) = line.rsplit_once(',') else {
+            issues.push(issue(number, "Missing line data check value"));
+            continue;
+        };
+        match u8::from_str_radix(check, 16) {
+            Ok(value) if check.len() == 2 => {
+                if value != line_check(body) {
+                    issues.push(issue(
+                        number,
+                        format!(
+                            "Line data check value {} should be {:02X}",
+                            check,
+                            line_check(body)
+                        ),
+                    ));
+                }
+                line_checks.push(value);
+            }
+            _ => issues.push(issue(
+                number,
+                format!("Invalid line data check value {}", check),
+            )),
+        }
+
+        let fields: Vec<&str> = body.split(',').collect();
+        match section {
+            Some(0) => header_lines.push(fields),
+            Some(2) => cmvs.push(fields),
+            Some(3) => events.push((number, fields)),
+            Some(9) => unidentified.push((number, fields)),
+            _ => (),
+        }
+    }
+
+    if section != Some(10) {
+        issues.push(issue(
+            lines.len(),
+            "Missing sections; the file is incomplete",
+        ));
+    }
+
+    // Event data check values cover the power unit number and the driver's username;
+    // the unidentified driver profile has none.
+    let username = header_lines
+        .first()
+        .and_then(|fields| fields.get(2))
+        .copied()
+        .unwrap_or_default();
+    let records = events
+        .iter()
+        .map(|(number, fields)| (*number, fields, username, 17))
+        .chain(
+            unidentified
+                .iter()
+                .map(|(number, fields)| (*number, fields, "", 15)),
+        );
+    let mut sequences = Vec::new();
+    for (number, fields, username, length) in records {
+        if fields.len() != length {
+            issues.push(issue(
+                number,
+                if length == 17 {
+                    "Event records have 17 fields"
+                } else {
+                    "Unidentified driver records have 15 fields"
+                },
+            ));
+            continue;
+        }
+        match u16::from_str_radix(fields[0], 16) {
+            Ok(sequence) if sequences.contains(&sequence) => issues.push(issue(
+                number,
+                format!("Duplicate sequence ID {}", fields[0]),
+            )),
+            Ok(sequence) => sequences.push(sequence),
+            Err(_) => issues.push(issue(number, format!("Invalid sequence ID {}", fields[0]))),
+        }
+
+        let power_unit = cmvs
+            .iter()
+            .find(|cmv| cmv.first() == Some(&fields[12]))
+            .and_then(|cmv| cmv.get(1))
+            .copied()
+            .unwrap_or_default();
+        let expected = event_check(&[
+            fields[3], fields[4], fields[5], fields[6], fields[7], fields[8], fields[9],
+            fields[10], power_unit, username,
+        ]);
+        let check = fields[length - 1];
+        if u8::from_str_radix(check, 16).ok() != Some(expected) {
+            issues.push(issue(
+                number,
+                format!(
+                    "Event data check value {} should be {:02X}",
+                    check, expected
+                ),
+            ));
+        }
+    }
+
+    match file_check_line {
+        Some((number, check)) => {
+            let expected = file_check(&line_checks);
+            if check.len() != 4 || u16::from_str_radix(check, 16).ok() != Some(expected) {
+                issues.push(issue(
+                    number,
+                    format!("File data check value {} should be {:04X}", check, expected),
+                ));
+            }
+        }
+        None => issues.push(issue(lines.len(), "Missing file data check value")),
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::export::output::*;
+    use chrono::prelude::*;
+    use chrono::Duration;
+
+    fn sample() -> String {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, 6, 0, 0).unwrap();
+        let event = |sequence, event_code, minutes, comment: Option<&str>| OutputEvent {
+            sequence,
+            origin: ORIGIN_DRIVER,
+            event_type: EVENT_DUTY_STATUS,
+            event_code,
+            at: at + Duration::minutes(minutes),
+            vehicle_miles: 1200,
+            location: "Joliet, IL".to_string(),
+            comment: comment.map(str::to_string),
+        };
+        render(&OutputFile {
+            header: FileHeader {
+                driver_last_name: "Rivera".to_string(),
+                driver_first_name: "Sam".to_string(),
+                eld_username: "sam@example.com".to_string(),
+                license_state: "IL".to_string(),
+                license_number: "R16204519".to_string(),
+                power_unit_number: "T-104".to_string(),
+                carrier_name: "Prairie Freight, LLC".to_string(),
+                multiday_basis: 8,
+                eld_identifier: "EF0001".to_string(),
+                comment: "roadside".to_string(),
+                generated_at: at + Duration::hours(12),
+                ..Default::default()
+            },
+            events: vec![
+                event(1, 4, 0, Some("pre-trip")),
+                event(2, 3, 30, None),
+                event(3, 1, 600, None),
+            ],
+            certifications: vec![Certification {
+                sequence: 4,
+                at: at + Duration::hours(11),
+                date: at.date_naive(),
+            }],
+            unidentified: unidentified_events(
+                &[UnidentifiedDrive {
+                    start: at - Duration::hours(3),
+                    end: at - Duration::hours(2),
+                    start_location: "Aurora, IL".to_string(),
+                    end_location: "Joliet, IL".to_string(),
+                    start_odometer: Some(1150.0),
+                    end_odometer: Some(1190.0),
+                }],
+                5,
+            ),
+        })
+    }
+
+    // Replaces the line after `section`, returning the file and the line's number.
+    fn edit_line(content: &str, section: &str, edit: impl Fn(&str) -> String) -> (String, usize) {
+        let mut lines: Vec<String> = content.split(LINE_END).map(str::to_string).collect();
+        let index = lines.iter().position(|line| line == section).unwrap() + 1;
+        lines[index] = edit(&lines[index]);
+        (lines.join(LINE_END), index + 1)
+    }
+
+    // Swaps a line's body, keeping its line data check value consistent.
+    fn with_line_check(body: &str) -> String {
+        format!("{},{:02X}", body, line_check(body))
+    }
+
+    // Recomputes the file data check value after lines were changed.
+    fn with_file_check(content: &str) -> String {
+        let (body, _) = content
+            .trim_end_matches(LINE_END)
+            .rsplit_once(LINE_END)
+            .unwrap();
+        let checks: Vec<u8> = body
+            .split(LINE_END)
+            .filter(|line| !SECTIONS.contains(line))
+            .map(|line| u8::from_str_radix(&line[line.len() - 2..], 16).unwrap())
+            .collect();
+        format!(
+            "{}{}{:04X}{}",
+            body,
+            LINE_END,
+            file_check(&checks),
+            LINE_END
+        )
+    }
+
+    #[test]
+    fn rendered_files_validate() {
+        assert_eq!(validate(&sample()), vec![]);
+        assert_eq!(with_file_check(&sample()), sample());
+        assert_eq!(validate(&sample().replace(LINE_END, "\n")), vec![]);
+    }
+
+    #[test]
+    fn wrong_line_check() {
+        let (content, number) =
+            edit_line(&sample(), SECTIONS[1], |line| line.replace(",D,", ",S,"));
+        let issues = validate(&content);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, number);
+        assert!(issues[0].message.starts_with("Line data check value"));
+    }
+
+    #[test]
+    fn wrong_event_check() {
+        // Turns the on-duty event into sleeper berth without its event check value.
+        let (content, number) = edit_line(&sample(), SECTIONS[3], |line| {
+            let (body, _) = line.rsplit_once(',').unwrap();
+            with_line_check(&body.replacen("1,1,2,1,4,", "1,1,2,1,2,", 1))
+        });
+        let issues = validate(&with_file_check(&content));
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, number);
+        assert!(issues[0].message.starts_with("Event data check value"));
+    }
+
+    #[test]
+    fn unidentified_records_are_checked() {
+        let content = sample();
+        assert!(content.contains(&format!(
+            "{}{}5,1,1,1,3,030226,030000,1150,",
+            SECTIONS[9], LINE_END
+        )));
+
+        // Turns the unidentified driving into off duty without its event check value.
+        let (content, number) = edit_line(&content, SECTIONS[9], |line| {
+            let (body, _) = line.rsplit_once(',').unwrap();
+            with_line_check(&body.replacen("5,1,1,1,3,", "5,1,1,1,1,", 1))
+        });
+        let issues = validate(&with_file_check(&content));
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, number);
+        assert!(issues[0].message.starts_with("Event data check value"));
+    }
+
+    #[test]
+    fn wrong_file_check() {
+        let (content, number) = edit_line(&sample(), SECTIONS[10], |line| {
+            let check = u16::from_str_radix(line, 16).unwrap();
+            format!("{:04X}", check.wrapping_add(1))
+        });
+        let issues = validate(&content);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, number);
+        assert!(issues[0].message.starts_with("File data check value"));
+    }
+}
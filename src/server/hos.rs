@@ -0,0 +1,7 @@
+pub(crate) mod category;
+pub(crate) mod controller;
+pub(crate) mod engine;
+pub(crate) mod model;
+pub(crate) mod request;
+pub(crate) mod response;
+pub(crate) mod stream;
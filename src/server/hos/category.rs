@@ -0,0 +1,103 @@
+use crate::server::hos::model::{CategoryTotals, DutyPeriod, DutyState};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Something about a special driving category the driver should review.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CategoryWarning {
+    pub at: DateTime<Utc>,
+    pub message: String,
+}
+
+/// Minutes of personal conveyance and of yard moves.
+pub fn category_minutes(periods: &[DutyPeriod]) -> CategoryTotals {
+    let mut totals = CategoryTotals::default();
+    for period in periods {
+        totals.add(period.status, period.duration().num_minutes());
+    }
+    totals
+}
+
+// Distance between the first and last odometer reading taken in [start, end].
+fn miles_between(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    odometers: &[(DateTime<Utc>, f64)],
+) -> f64 {
+    let mut readings = odometers
+        .iter()
+        .filter(|(at, _)| *at >= start && *at <= end)
+        .map(|(_, odometer)| *odometer);
+    let Some(first) = readings.next() else {
+        return 0.0;
+    };
+    let last = readings.next_back().unwrap_or(first);
+    (last - first).max(0.0)
+}
+
+/// Personal conveyance miles per day, from odometer readings sorted by time. A
+/// period is counted on the day it starts.
+pub fn pc_miles(
+    periods: &[DutyPeriod],
+    odometers: &[(DateTime<Utc>, f64)],
+) -> Vec<(NaiveDate, f64)> {
+    let mut days: Vec<(NaiveDate, f64)> = Vec::new();
+    for period in periods
+        .iter()
+        .filter(|period| period.status == DutyState::PersonalConveyance)
+    {
+        let miles = miles_between(period.start, period.end, odometers);
+        let date = period.start.date_naive();
+        match days.iter_mut().find(|(day, _)| *day == date) {
+            Some((_, total)) => *total += miles,
+            None => days.push((date, miles)),
+        }
+    }
+    days
+}
+
+/// Personal conveyance over the carrier's daily mileage limit, or while a load is
+/// on board. `loaded` holds the spans from pickup to drop-off.
+pub fn category_warnings(
+    periods: &[DutyPeriod],
+    odometers: &[(DateTime<Utc>, f64)],
+    daily_limit: Option<f64>,
+    loaded: &[(DateTime<Utc>, DateTime<Utc>)],
+) -> Vec<CategoryWarning> {
+    let mut warnings = Vec::new();
+    for period in periods
+        .iter()
+        .filter(|period| period.status == DutyState::PersonalConveyance)
+    {
+        if loaded
+            .iter()
+            .any(|(start, end)| period.start < *end && period.end > *start)
+        {
+            warnings.push(CategoryWarning {
+                at: period.start,
+                message: format!(
+                    "Personal conveyance at {} while a load is on board",
+                    period.start.format("%b %d %H:%M")
+                ),
+            });
+        }
+    }
+
+    if let Some(limit) = daily_limit {
+        for (date, miles) in pc_miles(periods, odometers) {
+            if miles > limit {
+                warnings.push(CategoryWarning {
+                    at: date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+                    message: format!(
+                        "Personal conveyance on {} covered {:.1} mi, over the carrier's {:.0} mi daily limit",
+                        date.format("%b %d"),
+                        miles,
+                        limit
+                    ),
+                });
+            }
+        }
+    }
+    warnings.sort_by_key(|warning| warning.at);
+    warnings
+}
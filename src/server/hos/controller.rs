@@ -0,0 +1,678 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use bson::doc;
+use dioxus::prelude::*;
+
+use crate::server::auth::controller::auth;
+use crate::server::auth::model::User;
+use crate::server::common::response::SuccessResponse;
+use crate::server::hos::category::{category_warnings, CategoryWarning};
+use crate::server::hos::engine::evaluate;
+use crate::server::hos::model::*;
+use crate::server::hos::request::{
+    GetDutyDaysRequest, GetDutyEditsRequest, GetHosClocksRequest, GetHosEventsRequest,
+    GetHosReportRequest, ProposeDutyEditRequest, ReviewDutyEditRequest,
+};
+use crate::server::hos::response::{HosClocksResponse, HosReportResponse};
+use crate::server::hos::stream::{
+    changes_from_eld_logs, events_in_range, periods_from_events, record_range, status_at,
+    DutyChange,
+};
+use crate::server::trip::daily::{daily_sheets, DailySheet};
+use crate::server::trip::model::{EldLog, Route, RouteStop, StopType, Trip};
+
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use chrono::Duration;
+use futures_util::TryStreamExt;
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client, crate::server::alert::controller::notify_duty_change,
+    crate::server::carrier::controller::carrier_policy,
+    crate::server::telematics::controller::driver_readings,
+    crate::server::trip::controller::uncertify_days,
+    crate::server::unidentified::controller::settle_assignment, mongodb::options::ReturnDocument,
+};
+
+// Enough history to cover a 70/8 cycle plus the shift that straddles it.
+pub const HISTORY_DAYS: i64 = 14;
+
+// Edits must say why the record changed (49 CFR 395.30(c)).
+pub const MIN_ANNOTATION_CHARS: usize = 4;
+
+// Reserves `count` consecutive sequence numbers for a driver's stream; returns the first.
+#[cfg(feature = "server")]
+async fn reserve_sequences(driver_id: ObjectId, count: i64) -> Result<i64, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<bson::Document>("counters");
+
+    let counter = collection
+        .find_one_and_update(
+            doc! { "_id": format!("duty_events:{}", driver_id.to_hex()) },
+            doc! { "$inc": { "value": count } },
+        )
+        .upsert(true)
+        .return_document(ReturnDocument::After)
+        .await?
+        .ok_or(ServerFnError::new("Could not reserve sequence numbers"))?;
+
+    Ok(counter.get_i64("value").unwrap_or(count) - count + 1)
+}
+
+#[cfg(feature = "server")]
+pub async fn append_duty_events(
+    driver_id: ObjectId,
+    trip_id: Option<ObjectId>,
+    changes: &[DutyChange],
+    origin: DutyEventOrigin,
+) -> Result<Vec<DutyEvent>, ServerFnError> {
+    if changes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyEvent>("duty_events");
+
+    let first = reserve_sequences(driver_id, changes.len() as i64).await?;
+    let events: Vec<DutyEvent> = changes
+        .iter()
+        .enumerate()
+        .map(|(index, change)| DutyEvent {
+            id: ObjectId::new(),
+            driver_id,
+            trip_id,
+            sequence: first + index as i64,
+            event_type: DutyEventType::DutyStatus,
+            origin,
+            status: change.status,
+            at: change.at,
+            location: change.location.clone(),
+            note: change.note.clone(),
+            odometer_reading: change.odometer,
+            active: true,
+            source_id: change.source,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+        .collect();
+
+    collection.insert_many(events.clone()).await?;
+    notify_duty_change(driver_id);
+    Ok(events)
+}
+
+#[cfg(feature = "server")]
+pub async fn deactivate_duty_events(ids: &[ObjectId]) -> Result<(), ServerFnError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyEvent>("duty_events");
+
+    collection
+        .update_many(
+            doc! { "_id": { "$in": ids.to_vec() } },
+            doc! { "$set": {
+                "active": false,
+                "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+            }},
+        )
+        .await?;
+    Ok(())
+}
+
+// Converts a driver's hour-range `eld_logs` rows into duty events. Rows are
+// flagged once converted, and events left by an interrupted run are replaced.
+// Only `migrate::run` calls this, once at startup before requests are served.
+#[cfg(feature = "server")]
+pub async fn migrate_eld_logs(driver_id: ObjectId) -> Result<usize, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let log_collection = db.collection::<EldLog>("eld_logs");
+    let event_collection = db.collection::<DutyEvent>("duty_events");
+
+    let logs: Vec<EldLog> = log_collection
+        .find(doc! { "driverId": driver_id, "migratedAt": { "$exists": false } })
+        .await?
+        .try_collect()
+        .await?;
+    if logs.is_empty() {
+        return Ok(0);
+    }
+
+    let ids: Vec<ObjectId> = logs.iter().map(|log| log.id).collect();
+    event_collection
+        .delete_many(doc! { "sourceId": { "$in": ids.clone() } })
+        .await?;
+
+    // The stream is per driver; each run of changes keeps the trip of its source row.
+    let changes = changes_from_eld_logs(&logs);
+    let trip_of = |change: &DutyChange| {
+        logs.iter()
+            .find(|log| Some(log.id) == change.source)
+            .map(|log| log.trip_id)
+    };
+    for run in changes.chunk_by(|a, b| trip_of(a) == trip_of(b)) {
+        append_duty_events(driver_id, trip_of(&run[0]), run, DutyEventOrigin::Auto).await?;
+    }
+
+    log_collection
+        .update_many(
+            doc! { "_id": { "$in": ids } },
+            doc! { "$set": { "migratedAt": bson::DateTime::from_chrono(Utc::now()) } },
+        )
+        .await?;
+    Ok(logs.len())
+}
+
+// Active events from `since` on, plus the one in effect at `since`.
+#[cfg(feature = "server")]
+pub async fn load_duty_events(
+    driver_id: ObjectId,
+    since: DateTime<Utc>,
+) -> Result<Vec<DutyEvent>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyEvent>("duty_events");
+
+    let since = bson::DateTime::from_chrono(since);
+    let mut events: Vec<DutyEvent> = collection
+        .find(doc! {
+            "driverId": driver_id,
+            "active": true,
+            "eventType": "DutyStatus",
+            "at": { "$lt": since },
+        })
+        .sort(doc! { "at": -1, "sequence": -1 })
+        .limit(1)
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?
+        .try_collect()
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?;
+    let recent: Vec<DutyEvent> = collection
+        .find(doc! { "driverId": driver_id, "active": true, "at": { "$gte": since } })
+        .sort(doc! { "at": 1, "sequence": 1 })
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?
+        .try_collect()
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?;
+
+    events.extend(recent);
+    Ok(events)
+}
+
+#[cfg(feature = "server")]
+pub async fn load_duty_history(
+    driver_id: ObjectId,
+    since: DateTime<Utc>,
+) -> Result<Vec<DutyPeriod>, ServerFnError> {
+    let events = load_duty_events(driver_id, since).await?;
+    Ok(periods_from_events(&events, Utc::now()))
+}
+
+// Cycle the driver's carrier put them on; the 70-hour/8-day cycle without a carrier.
+#[cfg(feature = "server")]
+pub async fn cycle_rule_for(user: &User) -> Result<CycleRule, ServerFnError> {
+    match user.carrier_id {
+        Some(carrier_id) => Ok(carrier_policy(carrier_id).await?.cycle_rule),
+        None => Ok(CycleRule::default()),
+    }
+}
+
+#[cfg(feature = "server")]
+pub async fn current_cycle_rule(driver_id: ObjectId) -> Result<CycleRule, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<User>("users");
+
+    match collection.find_one(doc! { "_id": driver_id }).await? {
+        Some(driver) => cycle_rule_for(&driver).await,
+        None => Ok(CycleRule::default()),
+    }
+}
+
+// Upserts a timeline event per restart, keyed on its start; returns the new ones.
+#[cfg(feature = "server")]
+pub async fn record_restarts(
+    driver_id: ObjectId,
+    restarts: &[Restart],
+) -> Result<Vec<Restart>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<HosEvent>("hos_events");
+
+    let mut recorded = Vec::new();
+    for restart in restarts {
+        let now = Utc::now();
+        let annotation = format!(
+            "34-hour restart: off duty from {} to {}",
+            restart.start.format("%b %d %H:%M"),
+            restart.end.format("%b %d %H:%M")
+        );
+        let result = collection
+            .update_one(
+                doc! {
+                    "driverId": driver_id,
+                    "kind": "CycleRestart",
+                    "start": bson::DateTime::from_chrono(restart.start),
+                },
+                doc! {
+                    "$set": {
+                        "end": bson::DateTime::from_chrono(restart.end),
+                        "annotation": annotation,
+                        "updatedAt": bson::DateTime::from_chrono(now),
+                    },
+                    "$setOnInsert": { "createdAt": bson::DateTime::from_chrono(now) },
+                },
+            )
+            .upsert(true)
+            .await?;
+
+        if result.upserted_id.is_some() {
+            recorded.push(restart.clone());
+        }
+    }
+    Ok(recorded)
+}
+
+// From the end of each route's first planned pickup to the end of its last drop-off.
+#[cfg(feature = "server")]
+async fn loaded_spans(
+    driver_id: ObjectId,
+) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
+    let route_collection = db.collection::<Route>("routes");
+    let stop_collection = db.collection::<RouteStop>("route_stops");
+
+    let trips: Vec<Trip> = trip_collection
+        .find(doc! { "driverId": driver_id })
+        .await?
+        .try_collect()
+        .await?;
+    let trip_ids: Vec<ObjectId> = trips.iter().map(|trip| trip.id).collect();
+    let routes: Vec<Route> = route_collection
+        .find(doc! { "tripId": { "$in": trip_ids } })
+        .await?
+        .try_collect()
+        .await?;
+    let route_ids: Vec<ObjectId> = routes.iter().map(|route| route.id).collect();
+    let stops: Vec<RouteStop> = stop_collection
+        .find(doc! {
+            "routeId": { "$in": route_ids },
+            "stopType": { "$in": [StopType::Pickup.label(), StopType::Dropoff.label()] },
+        })
+        .await?
+        .try_collect()
+        .await?;
+
+    let scheduled_ends = |stop_type: StopType, route_id: ObjectId| {
+        stops
+            .iter()
+            .filter(move |stop| stop.route_id == route_id && stop.stop_type == stop_type)
+            .filter_map(|stop| {
+                Some(stop.scheduled_at? + Duration::minutes(stop.duration_minutes as i64))
+            })
+    };
+    Ok(routes
+        .iter()
+        .filter_map(|route| {
+            Some((
+                scheduled_ends(StopType::Pickup, route.id).min()?,
+                scheduled_ends(StopType::Dropoff, route.id).max()?,
+            ))
+        })
+        .collect())
+}
+
+// Special driving category warnings for the days from `start` to `end`.
+#[cfg(feature = "server")]
+pub async fn category_warnings_for(
+    user: &User,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<CategoryWarning>, ServerFnError> {
+    let day_start = start
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .ok_or(ServerFnError::new("Invalid date"))?
+        .and_utc();
+    let day_end = end
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .ok_or(ServerFnError::new("Invalid date"))?
+        .and_utc()
+        + Duration::days(1);
+
+    let events = load_duty_events(user.id, day_start).await?;
+    let periods: Vec<DutyPeriod> = periods_from_events(&events, Utc::now().min(day_end))
+        .into_iter()
+        .filter(|period| period.end > day_start)
+        .collect();
+    if !periods
+        .iter()
+        .any(|period| period.status == DutyState::PersonalConveyance)
+    {
+        return Ok(Vec::new());
+    }
+
+    // Odometers come from the ELD when it reports, and from the events otherwise.
+    let mut odometers: Vec<(DateTime<Utc>, f64)> = events
+        .iter()
+        .filter_map(|event| Some((event.at, event.odometer_reading?)))
+        .collect();
+    let readings: Vec<(DateTime<Utc>, f64)> = driver_readings(user.id, day_start, day_end)
+        .await?
+        .iter()
+        .flatten()
+        .filter_map(|reading| Some((reading.at, reading.odometer?)))
+        .collect();
+    if !readings.is_empty() {
+        odometers = readings;
+    }
+    odometers.sort_by_key(|(at, _)| *at);
+
+    let limit = match user.carrier_id {
+        Some(carrier_id) => carrier_policy(carrier_id).await?.pc_daily_miles,
+        None => None,
+    };
+    let loaded = loaded_spans(user.id).await?;
+    Ok(category_warnings(&periods, &odometers, limit, &loaded))
+}
+
+#[server]
+pub async fn get_hos_events(
+    req: GetHosEventsRequest,
+) -> Result<SuccessResponse<Vec<HosEvent>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<HosEvent>("hos_events");
+
+    let events: Vec<HosEvent> = collection
+        .find(doc! { "driverId": user.id })
+        .sort(doc! { "start": -1 })
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?
+        .try_collect()
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: events,
+    })
+}
+
+#[server]
+pub async fn get_hos_report(
+    req: GetHosReportRequest,
+) -> Result<SuccessResponse<HosReportResponse>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let now = Utc::now();
+    let periods = load_duty_history(user.id, now - Duration::days(HISTORY_DAYS)).await?;
+    let report = evaluate(&periods, cycle_rule_for(&user).await?, now);
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: HosReportResponse {
+            available_minutes: report.clocks.available_minutes(),
+            clocks: report.clocks,
+            violations: report.violations,
+            restarts: report.restarts,
+        },
+    })
+}
+
+/// Time left on each clock right now, under the cycle the driver's carrier set.
+#[server]
+pub async fn get_hos_clocks(
+    req: GetHosClocksRequest,
+) -> Result<SuccessResponse<HosClocksResponse>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let now = Utc::now();
+    let cycle_rule = cycle_rule_for(&user).await?;
+    let events = load_duty_events(user.id, now - Duration::days(HISTORY_DAYS)).await?;
+    let report = evaluate(&periods_from_events(&events, now), cycle_rule, now);
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: HosClocksResponse {
+            available_minutes: report.clocks.available_minutes(),
+            clocks: report.clocks,
+            status: status_at(&events, now),
+            cycle_rule,
+            as_of: now,
+        },
+    })
+}
+
+#[server]
+pub async fn get_duty_days(
+    req: GetDutyDaysRequest,
+) -> Result<SuccessResponse<Vec<DailySheet>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let to = req.to.min(Utc::now());
+    let events = load_duty_events(user.id, req.from).await?;
+    let periods: Vec<DutyPeriod> = periods_from_events(&events, to)
+        .into_iter()
+        .filter(|period| period.end > req.from)
+        .map(|mut period| {
+            period.start = period.start.max(req.from);
+            period
+        })
+        .collect();
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: daily_sheets(&periods),
+    })
+}
+
+// Applies an edit to the driver's stream as it stands now: the events it replaces
+// are deactivated, never rewritten, and the edit keeps a copy of them.
+#[cfg(feature = "server")]
+pub async fn apply_duty_edit(
+    edit: &mut DutyEdit,
+    origin: DutyEventOrigin,
+) -> Result<(), ServerFnError> {
+    let events = load_duty_events(edit.driver_id, edit.start).await?;
+    let original = events_in_range(&events, edit.start, edit.end);
+    let (changes, superseded) = record_range(
+        &events,
+        edit.duty_status,
+        edit.start,
+        edit.end,
+        &edit.location,
+        &edit.annotation,
+        None,
+    );
+
+    deactivate_duty_events(&superseded).await?;
+    let trip_id = original.last().and_then(|event| event.trip_id);
+    edit.edited = append_duty_events(edit.driver_id, trip_id, &changes, origin).await?;
+    uncertify_days(edit.driver_id, edit.start, edit.end).await?;
+    edit.original = original;
+    edit.status = DutyEditStatus::Accepted;
+    edit.updated_at = Utc::now();
+    Ok(())
+}
+
+/// Corrects a past stretch of duty status. Drivers' own edits apply right away;
+/// edits proposed by a carrier user wait for the driver to accept them.
+#[server]
+pub async fn propose_duty_edit(
+    req: ProposeDutyEditRequest,
+) -> Result<SuccessResponse<DutyEdit>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let annotation = req.annotation.trim().to_string();
+    if annotation.chars().count() < MIN_ANNOTATION_CHARS {
+        return Err(ServerFnError::new(format!(
+            "An annotation of at least {} characters is required",
+            MIN_ANNOTATION_CHARS
+        )));
+    }
+    if req.end <= req.start {
+        return Err(ServerFnError::new("The edited range is empty"));
+    }
+    if req.end > Utc::now() {
+        return Err(ServerFnError::new("Only past records can be edited"));
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let user_collection = db.collection::<User>("users");
+    let edit_collection = db.collection::<DutyEdit>("duty_edits");
+
+    let driver_id = match req.driver_id {
+        Some(driver_id) => ObjectId::parse_str(&driver_id)?,
+        None => user.id,
+    };
+    let by_carrier = driver_id != user.id;
+    if by_carrier {
+        let driver = user_collection
+            .find_one(doc! { "_id": driver_id })
+            .await?
+            .ok_or(ServerFnError::new("Driver not found"))?;
+        if !user.is_carrier_of(&driver) {
+            return Err(ServerFnError::new(
+                "Only the driver's carrier can propose edits to this log",
+            ));
+        }
+    }
+
+    let now = Utc::now();
+    let mut edit = DutyEdit {
+        id: ObjectId::new(),
+        driver_id,
+        requested_by: user.id,
+        by_carrier,
+        status: DutyEditStatus::Pending,
+        duty_status: req.status,
+        start: req.start,
+        end: req.end,
+        location: req.location,
+        annotation,
+        original: Vec::new(),
+        edited: Vec::new(),
+        review_comment: None,
+        unidentified_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    if by_carrier {
+        // A preview of what the edit would replace; refreshed when the driver accepts.
+        let events = load_duty_events(driver_id, edit.start).await?;
+        edit.original = events_in_range(&events, edit.start, edit.end);
+    } else {
+        apply_duty_edit(&mut edit, DutyEventOrigin::Edited).await?;
+    }
+
+    edit_collection.insert_one(edit.clone()).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: edit,
+    })
+}
+
+#[server]
+pub async fn review_duty_edit(
+    req: ReviewDutyEditRequest,
+) -> Result<SuccessResponse<DutyEdit>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let edit_collection = db.collection::<DutyEdit>("duty_edits");
+
+    let edit_id = ObjectId::parse_str(&req.edit_id)?;
+    let mut edit = edit_collection
+        .find_one(doc! { "_id": edit_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Edit not found"))?;
+    if edit.status != DutyEditStatus::Pending {
+        return Err(ServerFnError::new("This edit has already been reviewed"));
+    }
+
+    edit.review_comment = req
+        .comment
+        .map(|comment| comment.trim().to_string())
+        .filter(|comment| !comment.is_empty());
+    if req.accept {
+        let origin = match edit.unidentified_id {
+            Some(_) => DutyEventOrigin::Unidentified,
+            None => DutyEventOrigin::CarrierEdit,
+        };
+        apply_duty_edit(&mut edit, origin).await?;
+    } else {
+        edit.status = DutyEditStatus::Rejected;
+        edit.updated_at = Utc::now();
+    }
+
+    edit_collection
+        .replace_one(doc! { "_id": edit.id }, edit.clone())
+        .await?;
+    if edit.unidentified_id.is_some() {
+        settle_assignment(&edit).await?;
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: edit,
+    })
+}
+
+/// Edits of the user's own log and those they proposed to other drivers, newest first.
+#[server]
+pub async fn get_duty_edits(
+    req: GetDutyEditsRequest,
+) -> Result<SuccessResponse<Vec<DutyEdit>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyEdit>("duty_edits");
+
+    let edits: Vec<DutyEdit> = collection
+        .find(doc! { "$or": [{ "driverId": user.id }, { "requestedBy": user.id }] })
+        .sort(doc! { "createdAt": -1 })
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?
+        .try_collect()
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: edits,
+    })
+}
@@ -0,0 +1,703 @@
+use crate::server::hos::model::*;
+use chrono::prelude::*;
+use chrono::Duration;
+
+// FMCSA property-carrying limits (49 CFR 395.3).
+pub const MAX_DRIVING_HOURS: i64 = 11;
+pub const DUTY_WINDOW_HOURS: i64 = 14;
+pub const BREAK_AFTER_DRIVING_HOURS: i64 = 8;
+pub const BREAK_MINUTES: i64 = 30;
+pub const SHIFT_RESET_HOURS: i64 = 10;
+pub const RESTART_HOURS: i64 = 34;
+pub const SPLIT_SLEEPER_HOURS: i64 = 7;
+pub const SPLIT_SHORT_HOURS: i64 = 2;
+
+// A rest period that may form one half of a split-sleeper pair.
+#[derive(Debug, Clone)]
+struct SplitRest {
+    end: DateTime<Utc>,
+    duration: Duration,
+    sleeper: Duration, // Longest consecutive sleeper-berth stretch
+}
+
+impl SplitRest {
+    fn is_long(&self) -> bool {
+        self.sleeper >= Duration::hours(SPLIT_SLEEPER_HOURS)
+    }
+
+    // 7/3 and 8/2 splits: one period of at least 7 hours in the sleeper berth, the
+    // other at least 2 hours, together at least 10 hours, in either order.
+    fn pairs_with(&self, other: &SplitRest) -> bool {
+        let short = Duration::hours(SPLIT_SHORT_HOURS);
+
+        let covered = (self.is_long() && other.duration >= short)
+            || (other.is_long() && self.duration >= short);
+        covered && self.duration + other.duration >= Duration::hours(SHIFT_RESET_HOURS)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Shift {
+    start: Option<DateTime<Utc>>,
+    driving: Duration,
+    since_break: Duration,
+    excluded: Duration, // Paired split rest that does not count against the window
+    rest_run: Duration,
+    sleeper_run: Duration,
+    longest_sleeper: Duration,
+    non_driving_run: Duration,
+    pending_split: Option<SplitRest>,
+    driving_since_split: Duration,
+    paired_at: Option<DateTime<Utc>>, // End of the first rest of the last completed pair
+}
+
+impl Shift {
+    fn new() -> Self {
+        Shift {
+            start: None,
+            driving: Duration::zero(),
+            since_break: Duration::zero(),
+            excluded: Duration::zero(),
+            rest_run: Duration::zero(),
+            sleeper_run: Duration::zero(),
+            longest_sleeper: Duration::zero(),
+            non_driving_run: Duration::zero(),
+            pending_split: None,
+            driving_since_split: Duration::zero(),
+            paired_at: None,
+        }
+    }
+
+    fn window_end(&self, fallback: DateTime<Utc>) -> DateTime<Utc> {
+        self.start.unwrap_or(fallback) + Duration::hours(DUTY_WINDOW_HOURS) + self.excluded
+    }
+
+    fn rest(&mut self, duration: Duration, sleeper: bool) {
+        self.rest_run = self.rest_run + duration;
+        if sleeper {
+            self.sleeper_run = self.sleeper_run + duration;
+            self.longest_sleeper = self.longest_sleeper.max(self.sleeper_run);
+        } else {
+            self.sleeper_run = Duration::zero();
+        }
+        self.pause(duration);
+
+        if self.rest_run >= Duration::hours(SHIFT_RESET_HOURS) {
+            *self = Shift {
+                rest_run: self.rest_run,
+                sleeper_run: self.sleeper_run,
+                longest_sleeper: self.longest_sleeper,
+                non_driving_run: self.non_driving_run,
+                ..Shift::new()
+            };
+        }
+    }
+
+    // Called when the driver comes back on duty. A qualifying rest either completes a
+    // split pair, moving the calculation point to the end of the first period and
+    // leaving the second out of the window, or waits for its partner. A long sleeper
+    // stretch is kept over a shorter rest that could not pair with it, since only the
+    // long one can still complete a split. Nothing is left out of the window before
+    // the pair is complete.
+    fn end_rest(&mut self, at: DateTime<Utc>) {
+        let short = Duration::hours(SPLIT_SHORT_HOURS);
+        let full = Duration::hours(SHIFT_RESET_HOURS);
+
+        if self.rest_run >= short && self.rest_run < full {
+            let rest = SplitRest {
+                end: at,
+                duration: self.rest_run,
+                sleeper: self.longest_sleeper,
+            };
+
+            match self.pending_split.take() {
+                Some(first) if first.pairs_with(&rest) => {
+                    self.start = Some(first.end);
+                    self.excluded = rest.duration;
+                    self.driving = self.driving_since_split;
+                    self.pending_split = Some(rest);
+                    self.driving_since_split = Duration::zero();
+                    self.paired_at = Some(first.end);
+                }
+                Some(first) if first.is_long() && !rest.is_long() => {
+                    self.pending_split = Some(first);
+                }
+                _ => {
+                    self.pending_split = Some(rest);
+                    self.driving_since_split = Duration::zero();
+                }
+            }
+        }
+
+        self.rest_run = Duration::zero();
+        self.sleeper_run = Duration::zero();
+        self.longest_sleeper = Duration::zero();
+    }
+
+    fn work(&mut self, start: DateTime<Utc>, duration: Duration) {
+        self.end_rest(start);
+        self.start.get_or_insert(start);
+        self.pause(duration);
+    }
+
+    fn drive(&mut self, start: DateTime<Utc>, duration: Duration) {
+        self.end_rest(start);
+        self.start.get_or_insert(start);
+        self.non_driving_run = Duration::zero();
+        self.driving = self.driving + duration;
+        self.since_break = self.since_break + duration;
+        self.driving_since_split = self.driving_since_split + duration;
+    }
+
+    // Any 30 consecutive minutes off the wheel satisfy the break requirement.
+    fn pause(&mut self, duration: Duration) {
+        self.non_driving_run = self.non_driving_run + duration;
+        if self.non_driving_run >= Duration::minutes(BREAK_MINUTES) {
+            self.since_break = Duration::zero();
+        }
+    }
+}
+
+/// Sorts periods and trims overlaps so that each instant has a single status.
+pub fn normalize(periods: &[DutyPeriod]) -> Vec<DutyPeriod> {
+    let mut sorted: Vec<DutyPeriod> = periods
+        .iter()
+        .filter(|period| period.end > period.start)
+        .cloned()
+        .collect();
+    sorted.sort_by_key(|period| period.start);
+
+    let mut normalized: Vec<DutyPeriod> = Vec::with_capacity(sorted.len());
+    for mut period in sorted {
+        if let Some(previous) = normalized.last() {
+            if period.start < previous.end {
+                period.start = previous.end;
+            }
+        }
+        if period.end > period.start {
+            normalized.push(period);
+        }
+    }
+    normalized
+}
+
+/// Total on-duty time (driving included) that falls between `from` and `to`.
+pub fn on_duty_between(periods: &[DutyPeriod], from: DateTime<Utc>, to: DateTime<Utc>) -> Duration {
+    periods
+        .iter()
+        .filter(|period| period.status.is_on_duty())
+        .fold(Duration::zero(), |acc, period| {
+            let start = period.start.max(from);
+            let end = period.end.min(to);
+            if end > start {
+                acc + (end - start)
+            } else {
+                acc
+            }
+        })
+}
+
+/// Runs of off-duty and sleeper time (gaps included) of at least 34 hours before `until`.
+pub fn find_restarts(periods: &[DutyPeriod], until: DateTime<Utc>) -> Vec<Restart> {
+    let mut restarts = Vec::new();
+    let mut rest_start: Option<DateTime<Utc>> = None;
+
+    let mut close = |start: DateTime<Utc>, end: DateTime<Utc>| {
+        if end - start >= Duration::hours(RESTART_HOURS) {
+            restarts.push(Restart { start, end });
+        }
+    };
+
+    for period in periods.iter().filter(|period| period.start < until) {
+        if period.status.is_on_duty() {
+            if let Some(start) = rest_start.take() {
+                close(start, period.start);
+            }
+            rest_start = Some(period.end);
+        } else if rest_start.is_none() {
+            rest_start = Some(period.start);
+        }
+    }
+    if let Some(start) = rest_start {
+        if until > start {
+            close(start, until);
+        }
+    }
+    restarts
+}
+
+/// On-duty time in the rolling 7 or 8 days ending at `at`, counted from the last
+/// 34-hour restart if there was one; expects normalized periods.
+pub fn cycle_used(periods: &[DutyPeriod], rule: CycleRule, at: DateTime<Utc>) -> Duration {
+    let window_start = at - Duration::days(rule.days());
+    let from = find_restarts(periods, at)
+        .last()
+        .map(|restart| restart.end.max(window_start))
+        .unwrap_or(window_start);
+    on_duty_between(periods, from, at)
+}
+
+fn minutes_left(remaining: Duration) -> i64 {
+    remaining.num_minutes().max(0)
+}
+
+fn violation(rule: HosRule, at: DateTime<Utc>, over: Duration, message: &str) -> HosViolation {
+    HosViolation {
+        rule,
+        at,
+        over_minutes: over.num_minutes(),
+        message: message.to_string(),
+    }
+}
+
+fn window_violation(window_end: DateTime<Utc>, period: &DutyPeriod) -> Option<HosViolation> {
+    (period.end > window_end).then(|| {
+        let at = period.start.max(window_end);
+        violation(
+            HosRule::DutyWindow,
+            at,
+            period.end - at,
+            "Driving after the 14-hour duty window",
+        )
+    })
+}
+
+// Window violations found while a split rest waits for its partner. Whether they
+// stand depends on whether that rest ends up paired.
+struct Provisional {
+    rest_end: DateTime<Utc>,
+    unpaired: Vec<HosViolation>,
+    paired: Vec<HosViolation>, // Against a window that starts when the rest ended
+}
+
+// Settles the provisional violations once their rest pairs or can no longer pair.
+fn settle(
+    shift: &Shift,
+    provisional: &mut Option<Provisional>,
+    violations: &mut Vec<HosViolation>,
+) {
+    let Some(waiting) = provisional.take() else {
+        return;
+    };
+    if shift.paired_at == Some(waiting.rest_end) {
+        violations.extend(waiting.paired);
+    } else if shift
+        .pending_split
+        .as_ref()
+        .is_some_and(|rest| rest.end == waiting.rest_end)
+    {
+        *provisional = Some(waiting);
+    } else {
+        violations.extend(waiting.unpaired);
+    }
+}
+
+fn check_driving(
+    shift: &Shift,
+    period: &DutyPeriod,
+    periods: &[DutyPeriod],
+    rule: CycleRule,
+    violations: &mut Vec<HosViolation>,
+    provisional: &mut Option<Provisional>,
+) {
+    let duration = period.duration();
+
+    let driving_left = Duration::hours(MAX_DRIVING_HOURS) - shift.driving;
+    if duration > driving_left {
+        let offset = driving_left.max(Duration::zero());
+        violations.push(violation(
+            HosRule::DrivingLimit,
+            period.start + offset,
+            duration - offset,
+            "Driving beyond the 11-hour limit",
+        ));
+    }
+
+    if let Some(unpaired) = window_violation(shift.window_end(period.start), period) {
+        match &shift.pending_split {
+            Some(rest) => {
+                let waiting = provisional.get_or_insert_with(|| Provisional {
+                    rest_end: rest.end,
+                    unpaired: Vec::new(),
+                    paired: Vec::new(),
+                });
+                waiting.unpaired.push(unpaired);
+                waiting.paired.extend(window_violation(
+                    rest.end + Duration::hours(DUTY_WINDOW_HOURS),
+                    period,
+                ));
+            }
+            None => violations.push(unpaired),
+        }
+    }
+
+    let break_left = Duration::hours(BREAK_AFTER_DRIVING_HOURS) - shift.since_break;
+    if duration > break_left {
+        let offset = break_left.max(Duration::zero());
+        violations.push(violation(
+            HosRule::RestBreak,
+            period.start + offset,
+            duration - offset,
+            "30-minute break required after 8 hours of driving",
+        ));
+    }
+
+    let over = cycle_used(periods, rule, period.end) - rule.limit();
+    if over > Duration::zero() {
+        violations.push(violation(
+            HosRule::Cycle,
+            period.start.max(period.end - over),
+            over.min(duration),
+            match rule {
+                CycleRule::SixtySeven => "Driving beyond the 60-hour/7-day limit",
+                CycleRule::SeventyEight => "Driving beyond the 70-hour/8-day limit",
+            },
+        ));
+    }
+}
+
+/// Replays a driver's duty history and reports violations plus the clocks at `now`.
+///
+/// Gaps between periods are treated as off-duty time. Driving past the window
+/// while a split rest is still unpaired counts as a violation unless the rest
+/// pairs later.
+pub fn evaluate(periods: &[DutyPeriod], rule: CycleRule, now: DateTime<Utc>) -> HosReport {
+    let periods = normalize(periods);
+    let mut shift = Shift::new();
+    let mut violations = Vec::new();
+    let mut provisional = None;
+    let mut cursor: Option<DateTime<Utc>> = None;
+
+    for period in &periods {
+        if let Some(previous_end) = cursor {
+            if period.start > previous_end {
+                shift.rest(period.start - previous_end, false);
+            }
+        }
+
+        match period.status {
+            DutyState::Driving => {
+                shift.end_rest(period.start);
+                settle(&shift, &mut provisional, &mut violations);
+                check_driving(
+                    &shift,
+                    period,
+                    &periods,
+                    rule,
+                    &mut violations,
+                    &mut provisional,
+                );
+                shift.drive(period.start, period.duration());
+            }
+            status if status.is_rest() => {
+                shift.rest(period.duration(), status == DutyState::Sleeper)
+            }
+            _ => shift.work(period.start, period.duration()),
+        }
+        settle(&shift, &mut provisional, &mut violations);
+        cursor = Some(period.end);
+    }
+
+    if let Some(last_end) = cursor {
+        if now > last_end {
+            shift.rest(now - last_end, false);
+        }
+    }
+    // Clocks show what the driver would have if they came back on duty now.
+    shift.end_rest(now);
+    settle(&shift, &mut provisional, &mut violations);
+    if let Some(waiting) = provisional {
+        violations.extend(waiting.unpaired);
+    }
+    violations.sort_by_key(|violation| violation.at);
+
+    let window = match shift.start {
+        Some(_) => shift.window_end(now) - now,
+        None => Duration::hours(DUTY_WINDOW_HOURS),
+    };
+
+    HosReport {
+        clocks: HosClocks {
+            drive_minutes: minutes_left(Duration::hours(MAX_DRIVING_HOURS) - shift.driving),
+            window_minutes: minutes_left(window),
+            break_minutes: minutes_left(
+                Duration::hours(BREAK_AFTER_DRIVING_HOURS) - shift.since_break,
+            ),
+            cycle_minutes: minutes_left(rule.limit() - cycle_used(&periods, rule, now)),
+        },
+        violations,
+        restarts: find_restarts(&periods, now),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DutyState::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+    }
+
+    fn period(status: DutyState, start: DateTime<Utc>, end: DateTime<Utc>) -> DutyPeriod {
+        DutyPeriod {
+            status,
+            start,
+            end,
+            location: "Joliet, IL".to_string(),
+        }
+    }
+
+    fn rules(report: &HosReport) -> Vec<HosRule> {
+        report.violations.iter().map(|v| v.rule).collect()
+    }
+
+    #[test]
+    fn driving_past_eleven_hours() {
+        let periods = vec![
+            period(OnDuty, at(2, 6, 0), at(2, 7, 0)),
+            period(Driving, at(2, 7, 0), at(2, 13, 0)),
+            period(OffDuty, at(2, 13, 0), at(2, 13, 30)),
+            period(Driving, at(2, 13, 30), at(2, 19, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 19, 0));
+
+        assert_eq!(rules(&report), vec![HosRule::DrivingLimit]);
+        assert_eq!(report.violations[0].at, at(2, 18, 30));
+        assert_eq!(report.violations[0].over_minutes, 30);
+        assert_eq!(report.clocks.drive_minutes, 0);
+    }
+
+    #[test]
+    fn driving_after_the_fourteen_hour_window() {
+        let periods = vec![
+            period(OnDuty, at(2, 6, 0), at(2, 12, 0)),
+            period(Driving, at(2, 12, 0), at(2, 16, 0)),
+            period(OnDuty, at(2, 16, 0), at(2, 19, 0)),
+            period(Driving, at(2, 19, 0), at(2, 21, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 21, 0));
+
+        assert_eq!(rules(&report), vec![HosRule::DutyWindow]);
+        assert_eq!(report.violations[0].at, at(2, 20, 0));
+        assert_eq!(report.violations[0].over_minutes, 60);
+        assert_eq!(report.clocks.window_minutes, 0);
+        assert_eq!(report.clocks.drive_minutes, 5 * 60);
+    }
+
+    #[test]
+    fn break_after_eight_hours_of_driving() {
+        let periods = vec![period(Driving, at(2, 6, 0), at(2, 15, 0))];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 15, 0));
+
+        assert_eq!(rules(&report), vec![HosRule::RestBreak]);
+        assert_eq!(report.violations[0].at, at(2, 14, 0));
+        assert_eq!(report.violations[0].over_minutes, 60);
+
+        // A 30-minute stop before the eighth hour avoids it.
+        let periods = vec![
+            period(Driving, at(2, 6, 0), at(2, 13, 0)),
+            period(OnDuty, at(2, 13, 0), at(2, 13, 30)),
+            period(Driving, at(2, 13, 30), at(2, 15, 30)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 15, 30));
+        assert!(report.violations.is_empty());
+        assert_eq!(report.clocks.break_minutes, 6 * 60);
+    }
+
+    // Six days of ten on-duty hours, then three hours of driving on the seventh.
+    fn busy_week() -> Vec<DutyPeriod> {
+        let mut periods = Vec::new();
+        for day in 1..=6 {
+            periods.push(period(OnDuty, at(day, 6, 0), at(day, 12, 0)));
+            periods.push(period(Driving, at(day, 12, 0), at(day, 16, 0)));
+        }
+        periods.push(period(Driving, at(7, 6, 0), at(7, 9, 0)));
+        periods
+    }
+
+    #[test]
+    fn sixty_hour_cycle_is_exceeded_before_seventy() {
+        let report = evaluate(&busy_week(), CycleRule::SixtySeven, at(7, 9, 0));
+        assert_eq!(rules(&report), vec![HosRule::Cycle]);
+        assert_eq!(report.violations[0].at, at(7, 6, 0));
+        assert_eq!(report.violations[0].over_minutes, 3 * 60);
+        assert_eq!(report.clocks.cycle_minutes, 0);
+
+        let report = evaluate(&busy_week(), CycleRule::SeventyEight, at(7, 9, 0));
+        assert!(report.violations.is_empty());
+        assert_eq!(report.clocks.cycle_minutes, 7 * 60);
+    }
+
+    #[test]
+    fn gaps_count_towards_a_restart() {
+        // Nothing is logged between the 2nd at 16:00 and the 4th at 03:00: 35 hours.
+        let periods = normalize(&[
+            period(Driving, at(1, 6, 0), at(1, 16, 0)),
+            period(OnDuty, at(2, 6, 0), at(2, 16, 0)),
+            period(Driving, at(4, 3, 0), at(4, 5, 0)),
+        ]);
+
+        let restarts = find_restarts(&periods, at(4, 6, 0));
+        assert_eq!(restarts.len(), 1);
+        assert_eq!(restarts[0].start, at(2, 16, 0));
+        assert_eq!(restarts[0].end, at(4, 3, 0));
+
+        assert_eq!(
+            cycle_used(&periods, CycleRule::SeventyEight, at(4, 6, 0)),
+            Duration::hours(2)
+        );
+        // Without the restart, both earlier days would still be in the cycle.
+        assert_eq!(
+            cycle_used(&periods, CycleRule::SeventyEight, at(2, 16, 0)),
+            Duration::hours(20)
+        );
+
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(4, 6, 0));
+        assert_eq!(report.restarts, restarts);
+        assert_eq!(report.clocks.cycle_minutes, 68 * 60);
+    }
+
+    #[test]
+    fn short_gaps_are_not_a_restart() {
+        let periods = normalize(&[
+            period(OnDuty, at(1, 6, 0), at(1, 16, 0)),
+            period(Driving, at(2, 6, 0), at(2, 8, 0)),
+        ]);
+
+        assert!(find_restarts(&periods, at(2, 8, 0)).is_empty());
+        assert_eq!(
+            cycle_used(&periods, CycleRule::SeventyEight, at(2, 8, 0)),
+            Duration::hours(12)
+        );
+    }
+
+    #[test]
+    fn seven_three_split() {
+        let periods = vec![
+            period(Driving, at(2, 0, 0), at(2, 4, 0)),
+            period(Sleeper, at(2, 4, 0), at(2, 11, 0)),
+            period(Driving, at(2, 11, 0), at(2, 17, 0)),
+            period(OffDuty, at(2, 17, 0), at(2, 20, 0)),
+            period(Driving, at(2, 20, 0), at(2, 23, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 23, 0));
+
+        assert!(report.violations.is_empty());
+        // Counted from 11:00: 6 + 3 hours driven, window to 11:00 + 14 + 3.
+        assert_eq!(report.clocks.drive_minutes, 2 * 60);
+        assert_eq!(report.clocks.window_minutes, 5 * 60);
+    }
+
+    #[test]
+    fn eight_two_split() {
+        let periods = vec![
+            period(Driving, at(2, 0, 0), at(2, 5, 0)),
+            period(Sleeper, at(2, 5, 0), at(2, 13, 0)),
+            period(Driving, at(2, 13, 0), at(2, 19, 0)),
+            period(OffDuty, at(2, 19, 0), at(2, 21, 0)),
+            period(Driving, at(2, 21, 0), at(3, 2, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(3, 2, 0));
+
+        assert!(report.violations.is_empty());
+        assert_eq!(report.clocks.drive_minutes, 0);
+        assert_eq!(report.clocks.window_minutes, 3 * 60);
+    }
+
+    #[test]
+    fn short_rest_first() {
+        let periods = vec![
+            period(Driving, at(2, 0, 0), at(2, 4, 0)),
+            period(OffDuty, at(2, 4, 0), at(2, 6, 0)),
+            period(Driving, at(2, 6, 0), at(2, 10, 0)),
+            period(Sleeper, at(2, 10, 0), at(2, 18, 0)),
+            period(Driving, at(2, 18, 0), at(2, 23, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 23, 0));
+
+        assert!(report.violations.is_empty());
+        // Counted from 06:00: 4 + 5 hours driven, window to 06:00 + 14 + 8.
+        assert_eq!(report.clocks.drive_minutes, 2 * 60);
+        assert_eq!(report.clocks.window_minutes, 5 * 60);
+    }
+
+    #[test]
+    fn seven_two_is_not_a_pair() {
+        let periods = vec![
+            period(Driving, at(2, 0, 0), at(2, 4, 0)),
+            period(Sleeper, at(2, 4, 0), at(2, 11, 0)),
+            period(Driving, at(2, 11, 0), at(2, 15, 0)),
+            period(OffDuty, at(2, 15, 0), at(2, 17, 0)),
+            period(Driving, at(2, 17, 0), at(2, 18, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 18, 0));
+
+        // Nine hours of rest leave the window anchored at midnight.
+        assert_eq!(
+            rules(&report),
+            vec![HosRule::DutyWindow, HosRule::DutyWindow]
+        );
+        assert_eq!(report.violations[0].at, at(2, 14, 0));
+        assert_eq!(report.violations[0].over_minutes, 60);
+        assert_eq!(report.violations[1].at, at(2, 17, 0));
+        assert_eq!(report.clocks.window_minutes, 0);
+        assert_eq!(report.clocks.drive_minutes, 2 * 60);
+    }
+
+    #[test]
+    fn long_rest_waits_past_a_short_rest_for_its_pair() {
+        // Seven hours and two do not pair, so the sleeper period waits for the three.
+        let periods = vec![
+            period(Driving, at(2, 0, 0), at(2, 3, 0)),
+            period(Sleeper, at(2, 3, 0), at(2, 10, 0)),
+            period(Driving, at(2, 10, 0), at(2, 12, 0)),
+            period(OffDuty, at(2, 12, 0), at(2, 14, 0)),
+            period(Driving, at(2, 14, 0), at(2, 16, 0)),
+            period(OffDuty, at(2, 16, 0), at(2, 19, 0)),
+            period(Driving, at(2, 19, 0), at(2, 22, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 22, 0));
+
+        assert!(report.violations.is_empty());
+        // Counted from 10:00: 2 + 2 + 3 hours driven, window to 10:00 + 14 + 3.
+        assert_eq!(report.clocks.drive_minutes, 4 * 60);
+        assert_eq!(report.clocks.window_minutes, 5 * 60);
+    }
+
+    #[test]
+    fn long_rest_between_two_short_rests_pairs_forward() {
+        // The sleeper period pairs with the first three hours and then again with
+        // the second; the later pair wins and the window runs from its end.
+        let periods = vec![
+            period(Driving, at(2, 0, 0), at(2, 3, 0)),
+            period(OffDuty, at(2, 3, 0), at(2, 6, 0)),
+            period(Driving, at(2, 6, 0), at(2, 9, 0)),
+            period(Sleeper, at(2, 9, 0), at(2, 16, 0)),
+            period(Driving, at(2, 16, 0), at(2, 19, 0)),
+            period(OffDuty, at(2, 19, 0), at(2, 22, 0)),
+            period(Driving, at(2, 22, 0), at(3, 0, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(3, 0, 0));
+
+        assert!(report.violations.is_empty());
+        // Counted from 16:00: 3 + 2 hours driven, window to 16:00 + 14 + 3.
+        assert_eq!(report.clocks.drive_minutes, 6 * 60);
+        assert_eq!(report.clocks.window_minutes, 9 * 60);
+    }
+
+    #[test]
+    fn unpaired_long_rest_stays_in_the_window() {
+        let periods = vec![
+            period(Driving, at(2, 0, 0), at(2, 5, 0)),
+            period(Sleeper, at(2, 5, 0), at(2, 12, 0)),
+            period(Driving, at(2, 12, 0), at(2, 17, 0)),
+        ];
+        let report = evaluate(&periods, CycleRule::SeventyEight, at(2, 17, 0));
+
+        assert_eq!(rules(&report), vec![HosRule::DutyWindow]);
+        assert_eq!(report.violations[0].at, at(2, 14, 0));
+        assert_eq!(report.violations[0].over_minutes, 3 * 60);
+        assert_eq!(report.clocks.window_minutes, 0);
+    }
+}
@@ -0,0 +1,397 @@
+#![allow(non_snake_case)]
+
+use crate::server::trip::model::{EldLog, LogEntry};
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use chrono::Duration;
+use eld::DutyStatus;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DutyState {
+    OffDuty,
+    Sleeper,
+    Driving,
+    OnDuty,
+    PersonalConveyance,
+    YardMove,
+}
+
+impl DutyState {
+    /// Parses the labels we have stored over time ("OffDuty", "Off-Duty", "Sleeper Berth", "SB", ...).
+    pub fn from_label(label: &str) -> Option<Self> {
+        let normalized: String = label
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+
+        match normalized.as_str() {
+            "offduty" | "off" => Some(DutyState::OffDuty),
+            "sleeper" | "sleeperberth" | "sb" => Some(DutyState::Sleeper),
+            "driving" | "dr" | "d" => Some(DutyState::Driving),
+            "onduty" | "on" | "ondutynotdriving" => Some(DutyState::OnDuty),
+            "personalconveyance" | "pc" => Some(DutyState::PersonalConveyance),
+            "yardmove" | "ym" => Some(DutyState::YardMove),
+            _ => None,
+        }
+    }
+
+    // Labels shown on logs and printouts.
+    pub fn label(&self) -> &'static str {
+        match self {
+            DutyState::OffDuty => "Off-Duty",
+            DutyState::Sleeper => "Sleeper Berth",
+            DutyState::Driving => "Driving",
+            DutyState::OnDuty => "On-Duty",
+            DutyState::PersonalConveyance => "Personal Conveyance",
+            DutyState::YardMove => "Yard Move",
+        }
+    }
+
+    // Off-duty time, including personal conveyance, counts towards rest periods.
+    pub fn is_rest(&self) -> bool {
+        matches!(
+            self,
+            DutyState::OffDuty | DutyState::Sleeper | DutyState::PersonalConveyance
+        )
+    }
+
+    // Time that counts against the 14-hour window and the 60/70-hour cycle.
+    pub fn is_on_duty(&self) -> bool {
+        matches!(
+            self,
+            DutyState::Driving | DutyState::OnDuty | DutyState::YardMove
+        )
+    }
+}
+
+impl From<DutyState> for DutyStatus {
+    fn from(state: DutyState) -> Self {
+        match state {
+            DutyState::OffDuty => DutyStatus::OffDuty,
+            DutyState::Sleeper => DutyStatus::Sleeper,
+            DutyState::Driving => DutyStatus::Driving,
+            DutyState::OnDuty => DutyStatus::OnDuty,
+            DutyState::PersonalConveyance => DutyStatus::PersonalConveyance,
+            DutyState::YardMove => DutyStatus::YardMove,
+        }
+    }
+}
+
+impl From<DutyStatus> for DutyState {
+    fn from(status: DutyStatus) -> Self {
+        match status {
+            DutyStatus::OffDuty => DutyState::OffDuty,
+            DutyStatus::Sleeper => DutyState::Sleeper,
+            DutyStatus::Driving => DutyState::Driving,
+            DutyStatus::OnDuty => DutyState::OnDuty,
+            DutyStatus::PersonalConveyance => DutyState::PersonalConveyance,
+            DutyStatus::YardMove => DutyState::YardMove,
+        }
+    }
+}
+
+// Minutes on each duty line of the log grid; personal conveyance is logged on
+// the off-duty line and yard moves on the on-duty line.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StatusTotals {
+    pub off_duty: i64,
+    pub sleeper: i64,
+    pub driving: i64,
+    pub on_duty: i64,
+}
+
+impl StatusTotals {
+    pub fn add(&mut self, status: DutyState, minutes: i64) {
+        match status {
+            DutyState::OffDuty | DutyState::PersonalConveyance => self.off_duty += minutes,
+            DutyState::Sleeper => self.sleeper += minutes,
+            DutyState::Driving => self.driving += minutes,
+            DutyState::OnDuty | DutyState::YardMove => self.on_duty += minutes,
+        }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.off_duty + self.sleeper + self.driving + self.on_duty
+    }
+}
+
+// Minutes of the special driving categories, which `StatusTotals` includes.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CategoryTotals {
+    pub personal_conveyance: i64,
+    pub yard_moves: i64,
+}
+
+impl CategoryTotals {
+    pub fn add(&mut self, status: DutyState, minutes: i64) {
+        match status {
+            DutyState::PersonalConveyance => self.personal_conveyance += minutes,
+            DutyState::YardMove => self.yard_moves += minutes,
+            _ => (),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.personal_conveyance == 0 && self.yard_moves == 0
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DutyPeriod {
+    pub status: DutyState,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub location: String,
+}
+
+impl DutyPeriod {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    // `EldLog` hours are relative to the day the log was stored.
+    pub fn from_eld_log(log: &EldLog) -> Option<Self> {
+        let status = log.status;
+        let day = log.created_at.date_naive().and_hms_opt(0, 0, 0)?.and_utc();
+
+        let start = day + Duration::seconds((log.start_hour * 3600.0) as i64);
+        let end = day + Duration::seconds((log.end_hour * 3600.0) as i64);
+        if end <= start {
+            return None;
+        }
+
+        Some(DutyPeriod {
+            status,
+            start,
+            end,
+            location: log.location.clone(),
+        })
+    }
+
+    // Each `LogEntry` is a status change that lasts until the next entry.
+    pub fn from_log_entries(entries: &[LogEntry], until: DateTime<Utc>) -> Vec<Self> {
+        let mut entries: Vec<&LogEntry> = entries.iter().collect();
+        entries.sort_by_key(|entry| entry.time);
+
+        let mut periods = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            let status = entry.status;
+            let end = entries
+                .get(index + 1)
+                .map(|next| next.time)
+                .unwrap_or(until);
+            if end > entry.time {
+                periods.push(DutyPeriod {
+                    status,
+                    start: entry.time,
+                    end,
+                    location: entry.location.clone(),
+                });
+            }
+        }
+        periods
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CycleRule {
+    #[serde(rename = "60/7")]
+    SixtySeven,
+    #[default]
+    #[serde(rename = "70/8")]
+    SeventyEight,
+}
+
+impl CycleRule {
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim() {
+            "60/7" => Some(CycleRule::SixtySeven),
+            "70/8" => Some(CycleRule::SeventyEight),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CycleRule::SixtySeven => "60/7",
+            CycleRule::SeventyEight => "70/8",
+        }
+    }
+
+    pub fn limit(&self) -> Duration {
+        match self {
+            CycleRule::SixtySeven => Duration::hours(60),
+            CycleRule::SeventyEight => Duration::hours(70),
+        }
+    }
+
+    pub fn days(&self) -> i64 {
+        match self {
+            CycleRule::SixtySeven => 7,
+            CycleRule::SeventyEight => 8,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum HosRule {
+    DrivingLimit, // 11 hours of driving per shift
+    DutyWindow,   // No driving after the 14th hour on duty
+    RestBreak,    // 30 minutes off the wheel after 8 hours of driving
+    Cycle,        // 60 hours in 7 days or 70 hours in 8 days
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct HosViolation {
+    pub rule: HosRule,
+    pub at: DateTime<Utc>,
+    #[serde(rename = "overMinutes")]
+    pub over_minutes: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct HosClocks {
+    #[serde(rename = "driveMinutes")]
+    pub drive_minutes: i64,
+    #[serde(rename = "windowMinutes")]
+    pub window_minutes: i64,
+    #[serde(rename = "breakMinutes")]
+    pub break_minutes: i64,
+    #[serde(rename = "cycleMinutes")]
+    pub cycle_minutes: i64,
+}
+
+impl HosClocks {
+    // Driving time left before the first limit is hit.
+    pub fn available_minutes(&self) -> i64 {
+        self.drive_minutes
+            .min(self.window_minutes)
+            .min(self.break_minutes)
+            .min(self.cycle_minutes)
+    }
+}
+
+// 34 or more consecutive hours off duty that reset the 60/70-hour cycle.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Restart {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct HosReport {
+    pub clocks: HosClocks,
+    pub violations: Vec<HosViolation>,
+    pub restarts: Vec<Restart>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum HosEventKind {
+    CycleRestart,
+}
+
+// Annotated entries on the driver's timeline, derived from their duty history.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HosEvent {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "driverId")]
+    pub driver_id: ObjectId,
+    pub kind: HosEventKind,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub start: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub end: DateTime<Utc>,
+    pub annotation: String,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum DutyEventType {
+    DutyStatus,   // Change of duty status
+    Intermediate, // Periodic record while driving; does not change the status
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum DutyEventOrigin {
+    Driver,       // Entered by the driver
+    Auto,         // Recorded automatically or converted from earlier records
+    Edited,       // Replaces an earlier event after the driver's edit
+    CarrierEdit,  // Replaces an earlier event after a carrier edit the driver accepted
+    Unidentified, // Unidentified driving the driver accepted
+}
+
+// One entry of a driver's duty-status stream; daily logs are derived from these.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DutyEvent {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "driverId")]
+    pub driver_id: ObjectId,
+    #[serde(rename = "tripId")]
+    pub trip_id: Option<ObjectId>,
+    pub sequence: i64, // Per driver, in recording order
+    #[serde(rename = "eventType")]
+    pub event_type: DutyEventType,
+    pub origin: DutyEventOrigin,
+    pub status: DutyState,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub at: DateTime<Utc>,
+    pub location: String,
+    pub note: String,
+    #[serde(rename = "odometerReading")]
+    pub odometer_reading: Option<f64>,
+    pub active: bool, // False once superseded by a later record
+    #[serde(rename = "sourceId")]
+    pub source_id: Option<ObjectId>, // The `eld_logs` row a migrated event came from
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum DutyEditStatus {
+    Pending,  // Proposed by the carrier, waiting on the driver
+    Accepted, // Applied to the driver's stream
+    Rejected, // Declined by the driver; the stream is unchanged
+}
+
+// A correction of a driver's duty status over [start, end). The events it replaces
+// stay in the stream as inactive records and are copied here as they were.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DutyEdit {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "driverId")]
+    pub driver_id: ObjectId,
+    #[serde(rename = "requestedBy")]
+    pub requested_by: ObjectId,
+    #[serde(rename = "byCarrier")]
+    pub by_carrier: bool,
+    pub status: DutyEditStatus,
+    #[serde(rename = "dutyStatus")]
+    pub duty_status: DutyState,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub start: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub end: DateTime<Utc>,
+    pub location: String,
+    pub annotation: String,
+    pub original: Vec<DutyEvent>, // Events in effect over the range before the edit
+    pub edited: Vec<DutyEvent>,   // Events recorded by the edit once accepted
+    #[serde(rename = "reviewComment")]
+    pub review_comment: Option<String>,
+    #[serde(rename = "unidentifiedId", default)]
+    pub unidentified_id: Option<ObjectId>, // Unidentified driving this edit assigns
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,49 @@
+use crate::server::hos::model::DutyState;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetHosReportRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetHosClocksRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetHosEventsRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetDutyDaysRequest {
+    pub token: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProposeDutyEditRequest {
+    pub token: String,
+    pub driver_id: Option<String>, // Another driver's log, for carrier users
+    pub status: DutyState,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub location: String,
+    pub annotation: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewDutyEditRequest {
+    pub token: String,
+    pub edit_id: String,
+    pub accept: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetDutyEditsRequest {
+    pub token: String,
+}
@@ -0,0 +1,20 @@
+use crate::server::hos::model::{CycleRule, DutyState, HosClocks, HosViolation, Restart};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HosReportResponse {
+    pub clocks: HosClocks,
+    pub violations: Vec<HosViolation>,
+    pub restarts: Vec<Restart>,
+    pub available_minutes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HosClocksResponse {
+    pub clocks: HosClocks,
+    pub available_minutes: i64,
+    pub status: DutyState,
+    pub cycle_rule: CycleRule,
+    pub as_of: DateTime<Utc>,
+}
@@ -0,0 +1,250 @@
+use crate::server::hos::engine::normalize;
+use crate::server::hos::model::*;
+use crate::server::ifta::report::odometer_miles;
+use crate::server::trip::model::EldLog;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// A change of duty status that has not been written to the stream yet.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DutyChange {
+    pub status: DutyState,
+    pub at: DateTime<Utc>,
+    pub location: String,
+    pub note: String,
+    pub odometer: Option<f64>,
+    pub source: Option<bson::oid::ObjectId>,
+}
+
+// Later events win over earlier ones at the same instant.
+fn ordered(events: &[DutyEvent]) -> Vec<&DutyEvent> {
+    let mut events: Vec<&DutyEvent> = events
+        .iter()
+        .filter(|event| event.active && event.event_type == DutyEventType::DutyStatus)
+        .collect();
+    events.sort_by_key(|event| (event.at, event.sequence));
+    events
+}
+
+/// Replays the active duty-status events; each status lasts until the next event,
+/// and the last one until `until`.
+pub fn periods_from_events(events: &[DutyEvent], until: DateTime<Utc>) -> Vec<DutyPeriod> {
+    let events = ordered(events);
+    let mut periods = Vec::new();
+    for (index, event) in events.iter().enumerate() {
+        let end = events
+            .get(index + 1)
+            .map(|next| next.at)
+            .unwrap_or(until)
+            .min(until);
+        if end > event.at {
+            periods.push(DutyPeriod {
+                status: event.status,
+                start: event.at,
+                end,
+                location: event.location.clone(),
+            });
+        }
+    }
+    periods
+}
+
+/// Miles driven in [from, to) by the odometer readings on the stream, or `None`
+/// without any. Resets are skipped and empty readings ignored.
+pub fn odometer_miles_between(
+    events: &[DutyEvent],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Option<f64> {
+    let mut readings: Vec<&DutyEvent> = events
+        .iter()
+        .filter(|event| event.at >= from && event.at < to)
+        .filter(|event| event.odometer_reading.is_some_and(|reading| reading > 0.0))
+        .collect();
+    readings.sort_by_key(|event| (event.at, event.sequence));
+    (!readings.is_empty())
+        .then(|| odometer_miles(readings.iter().filter_map(|event| event.odometer_reading)))
+}
+
+/// Status in effect at `at`; drivers are off duty before their first event.
+pub fn status_at(events: &[DutyEvent], at: DateTime<Utc>) -> DutyState {
+    ordered(events)
+        .into_iter()
+        .filter(|event| event.at <= at)
+        .last()
+        .map(|event| event.status)
+        .unwrap_or(DutyState::OffDuty)
+}
+
+/// Active duty-status events in effect at some point of [start, end): the one in
+/// effect at `start` and those that begin inside the range.
+pub fn events_in_range(
+    events: &[DutyEvent],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<DutyEvent> {
+    let events = ordered(events);
+    let first = events
+        .iter()
+        .rposition(|event| event.at <= start)
+        .unwrap_or(0);
+    events[first..]
+        .iter()
+        .filter(|event| event.at < end)
+        .map(|event| (*event).clone())
+        .collect()
+}
+
+/// Records `status` from `start` to `end` over an existing stream: events inside
+/// the range are superseded and the status that was in effect at `end` resumes
+/// there, unless the stream already changes status at that instant.
+///
+/// Returns the changes to append and the events to deactivate.
+pub fn record_range(
+    events: &[DutyEvent],
+    status: DutyState,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    location: &str,
+    note: &str,
+    odometer: Option<f64>,
+) -> (Vec<DutyChange>, Vec<bson::oid::ObjectId>) {
+    let active = ordered(events);
+    let superseded = active
+        .iter()
+        .filter(|event| event.at >= start && event.at < end)
+        .map(|event| event.id)
+        .collect();
+
+    let mut changes = vec![DutyChange {
+        status,
+        at: start,
+        location: location.to_string(),
+        note: note.to_string(),
+        odometer,
+        source: None,
+    }];
+    let resumed = status_at(events, end);
+    if resumed != status && !active.iter().any(|event| event.at == end) {
+        changes.push(DutyChange {
+            status: resumed,
+            at: end,
+            location: location.to_string(),
+            note: String::new(),
+            odometer,
+            source: None,
+        });
+    }
+    (changes, superseded)
+}
+
+/// Converts hour-range `EldLog` rows into status changes. Overlaps are trimmed as
+/// the HOS engine does, and gaps between rows become explicit off-duty time.
+pub fn changes_from_eld_logs(logs: &[EldLog]) -> Vec<DutyChange> {
+    let mut logs: Vec<(&EldLog, DutyPeriod)> = logs
+        .iter()
+        .filter_map(|log| Some((log, DutyPeriod::from_eld_log(log)?)))
+        .collect();
+    logs.sort_by_key(|(_, period)| period.start);
+    let periods = normalize(
+        &logs
+            .iter()
+            .map(|(_, period)| period.clone())
+            .collect::<Vec<_>>(),
+    );
+
+    let mut changes: Vec<DutyChange> = Vec::new();
+    for (index, period) in periods.iter().enumerate() {
+        let log = logs
+            .iter()
+            .find(|(_, source)| source.start <= period.start && source.end >= period.end)
+            .map(|(log, _)| *log);
+
+        let current = changes.last().map(|change| change.status);
+        if current != Some(period.status) {
+            changes.push(DutyChange {
+                status: period.status,
+                at: period.start,
+                location: period.location.clone(),
+                note: log.map(|log| log.note.clone()).unwrap_or_default(),
+                odometer: log.and_then(|log| log.odometer_reading),
+                source: log.map(|log| log.id),
+            });
+        }
+
+        let next_start = periods.get(index + 1).map(|next| next.start);
+        if next_start != Some(period.end) && period.status != DutyState::OffDuty {
+            changes.push(DutyChange {
+                status: DutyState::OffDuty,
+                at: period.end,
+                location: period.location.clone(),
+                note: String::new(),
+                odometer: log.and_then(|log| log.odometer_reading),
+                source: log.map(|log| log.id),
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bson::oid::ObjectId;
+
+    fn event(hour: u32, odometer_reading: Option<f64>) -> DutyEvent {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, hour, 0, 0).unwrap();
+        DutyEvent {
+            id: ObjectId::new(),
+            driver_id: ObjectId::new(),
+            trip_id: None,
+            sequence: hour as i64,
+            event_type: DutyEventType::DutyStatus,
+            origin: DutyEventOrigin::Driver,
+            status: DutyState::Driving,
+            at,
+            location: String::new(),
+            note: String::new(),
+            odometer_reading,
+            active: true,
+            source_id: None,
+            created_at: at,
+            updated_at: at,
+        }
+    }
+
+    fn day() -> (DateTime<Utc>, DateTime<Utc>) {
+        let from = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+        (from, from + chrono::Duration::days(1))
+    }
+
+    #[test]
+    fn miles_are_summed_across_an_odometer_reset() {
+        // 1000 to 1100, reset to 0 after a repair, then 40 more.
+        let events = [
+            event(8, Some(1000.0)),
+            event(10, None),
+            event(12, Some(1100.0)),
+            event(14, Some(10.0)),
+            event(16, Some(50.0)),
+        ];
+        let (from, to) = day();
+        assert_eq!(odometer_miles_between(&events, from, to), Some(140.0));
+    }
+
+    #[test]
+    fn readings_outside_the_day_are_ignored() {
+        let mut late = event(9, Some(2000.0));
+        late.at += chrono::Duration::days(1);
+        let events = [event(8, Some(1000.0)), event(12, Some(1100.0)), late];
+        let (from, to) = day();
+        assert_eq!(odometer_miles_between(&events, from, to), Some(100.0));
+    }
+
+    #[test]
+    fn no_readings_means_no_miles() {
+        let events = [event(8, None), event(12, Some(0.0))];
+        let (from, to) = day();
+        assert_eq!(odometer_miles_between(&events, from, to), None);
+    }
+}
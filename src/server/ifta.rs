@@ -0,0 +1,7 @@
+pub(crate) mod boundary;
+pub(crate) mod controller;
+pub(crate) mod jurisdiction;
+pub(crate) mod mileage;
+pub(crate) mod report;
+pub(crate) mod request;
+pub(crate) mod response;
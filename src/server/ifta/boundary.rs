@@ -0,0 +1,183 @@
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+// Generalized outlines of the member jurisdictions as GeoJSON, in longitude and
+// latitude. Neighbours share their border vertices so land falls in exactly one
+// outline; coasts and lake shores are coarse.
+const BOUNDARIES: &str = include_str!("boundaries.json");
+
+// Points outside every outline but this close to one are placed in it, which
+// covers coastal roads, bridges and causeways the outlines cut off.
+pub const BORDER_TOLERANCE_MILES: f64 = 10.0;
+
+const MILES_PER_DEGREE: f64 = 69.09;
+
+#[derive(Deserialize)]
+struct FeatureCollection {
+    features: Vec<Feature>,
+}
+
+#[derive(Deserialize)]
+struct Feature {
+    properties: Properties,
+    geometry: Geometry,
+}
+
+#[derive(Deserialize)]
+struct Properties {
+    code: String,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "coordinates")]
+enum Geometry {
+    Polygon(Vec<Vec<[f64; 2]>>),
+    MultiPolygon(Vec<Vec<Vec<[f64; 2]>>>),
+}
+
+// The outline of one jurisdiction, as polygons of [longitude, latitude] rings.
+pub struct Boundary {
+    pub code: String,
+    polygons: Vec<Vec<Vec<[f64; 2]>>>,
+    bounds: [f64; 4], // West, south, east, north
+}
+
+impl Boundary {
+    fn contains(&self, longitude: f64, latitude: f64) -> bool {
+        let [west, south, east, north] = self.bounds;
+        if longitude < west || longitude > east || latitude < south || latitude > north {
+            return false;
+        }
+        self.polygons.iter().any(|rings| {
+            // Even-odd over all rings, so inner rings are holes.
+            rings
+                .iter()
+                .filter(|ring| crosses(ring, longitude, latitude))
+                .count()
+                % 2
+                == 1
+        })
+    }
+
+    // Approximate distance in miles from a point to the nearest edge.
+    fn distance_miles(&self, longitude: f64, latitude: f64) -> f64 {
+        let scale = latitude.to_radians().cos();
+        let mut nearest = f64::INFINITY;
+        for ring in self.polygons.iter().flatten() {
+            for edge in ring.windows(2) {
+                let (ax, ay) = ((edge[0][0] - longitude) * scale, edge[0][1] - latitude);
+                let (bx, by) = ((edge[1][0] - longitude) * scale, edge[1][1] - latitude);
+                let (dx, dy) = (bx - ax, by - ay);
+                let length = dx * dx + dy * dy;
+                let t = if length > 0.0 {
+                    (-(ax * dx + ay * dy) / length).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let (x, y) = (ax + t * dx, ay + t * dy);
+                nearest = nearest.min((x * x + y * y).sqrt());
+            }
+        }
+        nearest * MILES_PER_DEGREE
+    }
+}
+
+// Whether a ray from the point towards the east crosses the ring an odd number
+// of times.
+fn crosses(ring: &[[f64; 2]], longitude: f64, latitude: f64) -> bool {
+    let mut inside = false;
+    for edge in ring.windows(2) {
+        let ([x1, y1], [x2, y2]) = (edge[0], edge[1]);
+        if (y1 > latitude) != (y2 > latitude) {
+            let x = x1 + (latitude - y1) * (x2 - x1) / (y2 - y1);
+            if longitude < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// The bundled jurisdiction outlines, parsed on first use.
+pub fn boundaries() -> &'static [Boundary] {
+    static PARSED: OnceLock<Vec<Boundary>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        let collection: FeatureCollection =
+            serde_json::from_str(BOUNDARIES).expect("bundled boundaries are valid GeoJSON");
+        collection
+            .features
+            .into_iter()
+            .map(|feature| {
+                let polygons = match feature.geometry {
+                    Geometry::Polygon(rings) => vec![rings],
+                    Geometry::MultiPolygon(polygons) => polygons,
+                };
+                let mut bounds = [
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                ];
+                for [longitude, latitude] in polygons.iter().flatten().flatten() {
+                    bounds[0] = bounds[0].min(*longitude);
+                    bounds[1] = bounds[1].min(*latitude);
+                    bounds[2] = bounds[2].max(*longitude);
+                    bounds[3] = bounds[3].max(*latitude);
+                }
+                Boundary {
+                    code: feature.properties.code,
+                    polygons,
+                    bounds,
+                }
+            })
+            .collect()
+    })
+}
+
+/// The jurisdiction code of a position, falling back to the nearest outline
+/// within `BORDER_TOLERANCE_MILES`. None at sea or outside the member
+/// jurisdictions.
+pub fn locate(latitude: f64, longitude: f64) -> Option<&'static str> {
+    let boundaries = boundaries();
+    if let Some(boundary) = boundaries
+        .iter()
+        .find(|boundary| boundary.contains(longitude, latitude))
+    {
+        return Some(&boundary.code);
+    }
+    boundaries
+        .iter()
+        .map(|boundary| (boundary, boundary.distance_miles(longitude, latitude)))
+        .filter(|(_, distance)| *distance <= BORDER_TOLERANCE_MILES)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(boundary, _)| boundary.code.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kansas_city_is_split_at_the_state_line() {
+        assert_eq!(locate(39.0997, -94.5786), Some("MO"));
+        assert_eq!(locate(39.1141, -94.6275), Some("KS"));
+    }
+
+    #[test]
+    fn memphis_is_split_at_the_river() {
+        assert_eq!(locate(35.1495, -90.0490), Some("TN"));
+        assert_eq!(locate(35.1465, -90.1845), Some("AR"));
+    }
+
+    #[test]
+    fn wheeling_is_in_the_panhandle() {
+        assert_eq!(locate(40.0806, -80.9001), Some("OH"));
+        assert_eq!(locate(40.0640, -80.7209), Some("WV"));
+        assert_eq!(locate(40.1740, -80.2462), Some("PA"));
+    }
+
+    #[test]
+    fn open_ocean_is_nowhere() {
+        assert_eq!(locate(30.0, -60.0), None);
+    }
+}
@@ -0,0 +1,126 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use bson::doc;
+use dioxus::prelude::*;
+
+use crate::server::auth::controller::auth;
+use crate::server::common::response::SuccessResponse;
+use crate::server::ifta::mileage::{jurisdiction_miles, scale_miles};
+use crate::server::ifta::report::{
+    file_name, ifta_report, odometer_miles, quarter_range, render_csv, UNASSIGNED,
+};
+use crate::server::ifta::request::GetIftaReportRequest;
+use crate::server::ifta::response::IftaReportResponse;
+use crate::server::routing::geometry::LatLng;
+use crate::server::trip::model::{FuelingStop, Trip, TripStatus};
+use crate::server::trip::printout::data_url;
+
+use bson::oid::ObjectId;
+use futures_util::TryStreamExt;
+use std::collections::BTreeMap;
+#[cfg(feature = "server")]
+use {crate::db::get_client, crate::server::telematics::controller::driver_readings};
+
+/// The driver's IFTA jurisdiction table for a calendar quarter, with the CSV to
+/// file. Miles come from the ELD odometer, or from the planned distance of the
+/// quarter's completed trips when the ELD did not report, and are split by
+/// jurisdiction along the ELD's GPS track. Miles without a track are unassigned.
+#[server]
+pub async fn get_ifta_report(
+    req: GetIftaReportRequest,
+) -> Result<SuccessResponse<IftaReportResponse>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let (start, end) = quarter_range(req.year, req.quarter)
+        .ok_or(ServerFnError::new("The quarter must be between 1 and 4"))?;
+    let range = doc! {
+        "$gte": bson::DateTime::from_chrono(start),
+        "$lt": bson::DateTime::from_chrono(end),
+    };
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
+    let fueling_collection = db.collection::<FuelingStop>("fueling_stops");
+
+    let trips: Vec<Trip> = trip_collection
+        .find(doc! { "driverId": user.id })
+        .await?
+        .try_collect()
+        .await?;
+    let trip_ids: Vec<ObjectId> = trips.iter().map(|trip| trip.id).collect();
+
+    let mut gallons = BTreeMap::new();
+    let stops: Vec<FuelingStop> = fueling_collection
+        .find(doc! { "tripId": { "$in": trip_ids }, "purchasedAt": range.clone() })
+        .await?
+        .try_collect()
+        .await?;
+    for stop in stops {
+        let jurisdiction = if stop.jurisdiction.is_empty() {
+            UNASSIGNED.to_string()
+        } else {
+            stop.jurisdiction
+        };
+        *gallons.entry(jurisdiction).or_insert(0.0) += stop.fuel_amount;
+    }
+
+    // Each session's track is split on its own; joining them would credit the
+    // gap between one truck's last fix and the next truck's first.
+    let mut driven = 0.0;
+    let mut split = BTreeMap::new();
+    for readings in driver_readings(user.id, start, end).await? {
+        driven += odometer_miles(readings.iter().filter_map(|reading| reading.odometer));
+        let track: Vec<LatLng> = readings
+            .iter()
+            .filter_map(|reading| match (reading.latitude, reading.longitude) {
+                (Some(latitude), Some(longitude)) => Some(LatLng {
+                    latitude,
+                    longitude,
+                }),
+                _ => None,
+            })
+            .collect();
+        for (jurisdiction, miles) in jurisdiction_miles(&track) {
+            *split.entry(jurisdiction).or_insert(0.0) += miles;
+        }
+    }
+    if driven == 0.0 {
+        driven = trips
+            .iter()
+            .filter(|trip| {
+                trip.status == TripStatus::Completed
+                    && trip.created_at >= start
+                    && trip.created_at < end
+            })
+            .filter_map(|trip| trip.distance_miles)
+            .sum();
+    }
+    // The track gives each jurisdiction's share; the odometer or the trips give
+    // the total.
+    let mut miles = BTreeMap::new();
+    if split.is_empty() {
+        if driven > 0.0 {
+            miles.insert(UNASSIGNED.to_string(), driven);
+        }
+    } else if driven > 0.0 {
+        miles = scale_miles(&split, driven);
+    } else {
+        miles = split;
+    }
+
+    let report = ifta_report(req.year, req.quarter, &miles, &gallons);
+    let content = render_csv(&report);
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: IftaReportResponse {
+            file_name: file_name(&report),
+            data_url: data_url("text/csv", content.as_bytes()),
+            report,
+        },
+    })
+}
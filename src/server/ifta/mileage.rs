@@ -0,0 +1,116 @@
+use crate::server::ifta::boundary::locate;
+use crate::server::ifta::report::UNASSIGNED;
+use crate::server::routing::geometry::{distance_miles, LatLng};
+use std::collections::BTreeMap;
+
+// Segments are walked in steps of at most this length, so short crossings such
+// as the West Virginia panhandle are not skipped between two distant fixes.
+pub const STEP_MILES: f64 = 0.5;
+
+/// Splits the distance along a route or a sequence of GPS fixes by
+/// jurisdiction. Each segment is cut into steps of at most `STEP_MILES` and
+/// every step is credited to the jurisdiction of its midpoint; steps outside
+/// every jurisdiction go to `UNASSIGNED`.
+pub fn jurisdiction_miles(points: &[LatLng]) -> BTreeMap<String, f64> {
+    let mut miles = BTreeMap::new();
+    for segment in points.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let length = distance_miles(from, to);
+        if length == 0.0 {
+            continue;
+        }
+        let steps = (length / STEP_MILES).ceil().max(1.0);
+        for step in 0..steps as usize {
+            let t = (step as f64 + 0.5) / steps;
+            let jurisdiction = locate(
+                from.latitude + (to.latitude - from.latitude) * t,
+                from.longitude + (to.longitude - from.longitude) * t,
+            )
+            .unwrap_or(UNASSIGNED);
+            *miles.entry(jurisdiction.to_string()).or_insert(0.0) += length / steps;
+        }
+    }
+    miles
+}
+
+/// Scales a jurisdiction split to a measured total, such as the odometer miles,
+/// keeping each jurisdiction's share. Unchanged when the split is empty.
+pub fn scale_miles(miles: &BTreeMap<String, f64>, total: f64) -> BTreeMap<String, f64> {
+    let sum: f64 = miles.values().sum();
+    if sum <= 0.0 {
+        return miles.clone();
+    }
+    miles
+        .iter()
+        .map(|(jurisdiction, value)| (jurisdiction.clone(), value * total / sum))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::ifta::report::odometer_miles;
+
+    fn at(latitude: f64, longitude: f64) -> LatLng {
+        LatLng {
+            latitude,
+            longitude,
+        }
+    }
+
+    #[test]
+    fn kansas_city_crossing_is_split() {
+        // Kansas City, Kansas to downtown Kansas City, Missouri.
+        let miles = jurisdiction_miles(&[at(39.1141, -94.6275), at(39.0997, -94.5786)]);
+        assert_eq!(miles.keys().collect::<Vec<_>>(), ["KS", "MO"]);
+        assert!(miles["KS"] > 1.0 && miles["MO"] > 1.0);
+    }
+
+    #[test]
+    fn memphis_crossing_is_split() {
+        // West Memphis to Memphis over the Mississippi.
+        let miles = jurisdiction_miles(&[at(35.1465, -90.1845), at(35.1495, -90.0490)]);
+        assert_eq!(miles.keys().collect::<Vec<_>>(), ["AR", "TN"]);
+        assert!(miles["AR"] > miles["TN"]);
+    }
+
+    #[test]
+    fn panhandle_is_not_skipped() {
+        // St. Clairsville, Ohio to Washington, Pennsylvania through Wheeling in a
+        // single segment.
+        let miles = jurisdiction_miles(&[at(40.0806, -80.9001), at(40.1740, -80.2462)]);
+        assert_eq!(miles.keys().collect::<Vec<_>>(), ["OH", "PA", "WV"]);
+        assert!(miles["WV"] > 5.0);
+        let total: f64 = miles.values().sum();
+        let direct = distance_miles(at(40.0806, -80.9001), at(40.1740, -80.2462));
+        assert!((total - direct).abs() < 0.01);
+    }
+
+    #[test]
+    fn repeated_fixes_add_nothing() {
+        let fix = at(39.0997, -94.5786);
+        assert!(jurisdiction_miles(&[fix, fix]).is_empty());
+    }
+
+    #[test]
+    fn scaled_to_the_odometer() {
+        let miles = BTreeMap::from([("KS".to_string(), 30.0), ("MO".to_string(), 10.0)]);
+        let scaled = scale_miles(&miles, 100.0);
+        assert_eq!(scaled["KS"], 75.0);
+        assert_eq!(scaled["MO"], 25.0);
+    }
+
+    #[test]
+    fn nothing_to_scale() {
+        let miles = BTreeMap::from([("KS".to_string(), 0.0)]);
+        assert_eq!(scale_miles(&miles, 100.0), miles);
+        assert!(scale_miles(&BTreeMap::new(), 100.0).is_empty());
+    }
+
+    #[test]
+    fn odometer_resets_are_skipped() {
+        assert_eq!(odometer_miles([100.0, 150.0, 20.0, 70.0]), 100.0);
+        assert_eq!(odometer_miles([100.0]), 0.0);
+        assert_eq!(odometer_miles([]), 0.0);
+    }
+}
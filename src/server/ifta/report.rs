@@ -0,0 +1,237 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Miles or fuel that could not be placed in a member jurisdiction.
+pub const UNASSIGNED: &str = "Unassigned";
+
+// One line of the jurisdiction summary of a quarterly IFTA return.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IftaRow {
+    pub jurisdiction: String,
+    pub total_miles: f64,
+    pub taxable_miles: f64,
+    pub taxable_gallons: f64,     // Fuel burned there at the fleet average
+    pub tax_paid_gallons: f64,    // Fuel bought there
+    pub net_taxable_gallons: f64, // Negative when more fuel was bought than burned
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IftaReport {
+    pub year: i32,
+    pub quarter: u32,
+    pub total_miles: f64,
+    pub total_gallons: f64,
+    pub mpg: f64, // Zero when no fuel was bought
+    pub rows: Vec<IftaRow>,
+}
+
+/// Start and end of a calendar quarter (1 to 4), in UTC.
+pub fn quarter_range(year: i32, quarter: u32) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    if !(1..=4).contains(&quarter) {
+        return None;
+    }
+    let start = Utc
+        .with_ymd_and_hms(year, (quarter - 1) * 3 + 1, 1, 0, 0, 0)
+        .single()?;
+    let end = if quarter == 4 {
+        Utc.with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0).single()?
+    } else {
+        Utc.with_ymd_and_hms(year, quarter * 3 + 1, 1, 0, 0, 0)
+            .single()?
+    };
+    Some((start, end))
+}
+
+/// Miles covered by a sequence of odometer readings, skipping resets and
+/// corrections that run the odometer backwards.
+pub fn odometer_miles(readings: impl IntoIterator<Item = f64>) -> f64 {
+    let mut readings = readings.into_iter();
+    let Some(mut last) = readings.next() else {
+        return 0.0;
+    };
+    let mut miles = 0.0;
+    for reading in readings {
+        if reading > last {
+            miles += reading - last;
+        }
+        last = reading;
+    }
+    miles
+}
+
+/// Builds the quarter's jurisdiction table from the miles driven and the gallons
+/// bought in each jurisdiction. Fuel burned everywhere is taken at the fleet
+/// average of all miles over all gallons; unassigned miles count towards the
+/// average but are not taxable anywhere.
+pub fn ifta_report(
+    year: i32,
+    quarter: u32,
+    miles: &BTreeMap<String, f64>,
+    gallons: &BTreeMap<String, f64>,
+) -> IftaReport {
+    let total_miles: f64 = miles.values().sum();
+    let total_gallons: f64 = gallons.values().sum();
+    let mpg = if total_gallons > 0.0 {
+        total_miles / total_gallons
+    } else {
+        0.0
+    };
+
+    let mut jurisdictions: Vec<&String> = miles.keys().chain(gallons.keys()).collect();
+    jurisdictions.sort_by_key(|jurisdiction| (jurisdiction.as_str() == UNASSIGNED, *jurisdiction));
+    jurisdictions.dedup();
+
+    let rows = jurisdictions
+        .into_iter()
+        .map(|jurisdiction| {
+            let total = miles.get(jurisdiction).copied().unwrap_or(0.0);
+            let taxable_miles = if jurisdiction == UNASSIGNED {
+                0.0
+            } else {
+                total
+            };
+            let taxable_gallons = if mpg > 0.0 { taxable_miles / mpg } else { 0.0 };
+            let tax_paid_gallons = gallons.get(jurisdiction).copied().unwrap_or(0.0);
+            IftaRow {
+                jurisdiction: jurisdiction.clone(),
+                total_miles: total,
+                taxable_miles,
+                taxable_gallons,
+                tax_paid_gallons,
+                net_taxable_gallons: taxable_gallons - tax_paid_gallons,
+            }
+        })
+        .collect();
+
+    IftaReport {
+        year,
+        quarter,
+        total_miles,
+        total_gallons,
+        mpg,
+        rows,
+    }
+}
+
+/// The jurisdiction table as CSV, with a closing totals line.
+pub fn render_csv(report: &IftaReport) -> String {
+    let mut csv = String::from(
+        "Jurisdiction,Total Miles,Taxable Miles,Taxable Gallons,Tax-Paid Gallons,Net Taxable Gallons\r\n",
+    );
+    let mut totals = [0.0; 5];
+    for row in &report.rows {
+        let values = [
+            row.total_miles,
+            row.taxable_miles,
+            row.taxable_gallons,
+            row.tax_paid_gallons,
+            row.net_taxable_gallons,
+        ];
+        for (total, value) in totals.iter_mut().zip(values) {
+            *total += value;
+        }
+        csv.push_str(&format!(
+            "{},{:.0},{:.0},{:.2},{:.2},{:.2}\r\n",
+            row.jurisdiction, values[0], values[1], values[2], values[3], values[4]
+        ));
+    }
+    csv.push_str(&format!(
+        "Total,{:.0},{:.0},{:.2},{:.2},{:.2}\r\n",
+        totals[0], totals[1], totals[2], totals[3], totals[4]
+    ));
+    csv.push_str(&format!("Average MPG,{:.2}\r\n", report.mpg));
+    csv
+}
+
+pub fn file_name(report: &IftaReport) -> String {
+    format!("IFTA-{}-Q{}.csv", report.year, report.quarter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(entries: &[(&str, f64)]) -> BTreeMap<String, f64> {
+        entries
+            .iter()
+            .map(|(jurisdiction, value)| (jurisdiction.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn gallons_are_taxed_at_the_fleet_average() {
+        // 1200 miles on 200 gallons is 6 mpg.
+        let report = ifta_report(
+            2026,
+            1,
+            &table(&[("TX", 900.0), ("OK", 300.0)]),
+            &table(&[("TX", 200.0)]),
+        );
+        assert_eq!(report.mpg, 6.0);
+        let ok = &report.rows[0];
+        assert_eq!(ok.jurisdiction, "OK");
+        assert_eq!(ok.taxable_gallons, 50.0);
+        assert_eq!(ok.net_taxable_gallons, 50.0);
+        let tx = &report.rows[1];
+        assert_eq!(tx.taxable_gallons, 150.0);
+        assert_eq!(tx.net_taxable_gallons, -50.0);
+    }
+
+    #[test]
+    fn unassigned_miles_are_not_taxable() {
+        let report = ifta_report(
+            2026,
+            1,
+            &table(&[(UNASSIGNED, 200.0), ("TX", 1000.0)]),
+            &table(&[("TX", 200.0)]),
+        );
+        // They still count towards the average, and are listed last.
+        assert_eq!(report.mpg, 6.0);
+        let unassigned = &report.rows[1];
+        assert_eq!(unassigned.jurisdiction, UNASSIGNED);
+        assert_eq!(unassigned.total_miles, 200.0);
+        assert_eq!(unassigned.taxable_miles, 0.0);
+        assert_eq!(unassigned.taxable_gallons, 0.0);
+    }
+
+    #[test]
+    fn csv_closes_with_totals_and_average() {
+        let report = ifta_report(
+            2026,
+            1,
+            &table(&[("TX", 900.0), ("OK", 300.0)]),
+            &table(&[("TX", 200.0)]),
+        );
+        assert_eq!(
+            render_csv(&report),
+            "Jurisdiction,Total Miles,Taxable Miles,Taxable Gallons,Tax-Paid Gallons,Net Taxable Gallons\r\n\
+             OK,300,300,50.00,0.00,50.00\r\n\
+             TX,900,900,150.00,200.00,-50.00\r\n\
+             Total,1200,1200,200.00,200.00,0.00\r\n\
+             Average MPG,6.00\r\n"
+        );
+    }
+
+    #[test]
+    fn odometer_resets_are_skipped() {
+        assert_eq!(odometer_miles([1000.0, 1100.0, 5.0, 45.0]), 140.0);
+        assert_eq!(odometer_miles([1000.0]), 0.0);
+        assert_eq!(odometer_miles([]), 0.0);
+    }
+
+    #[test]
+    fn fourth_quarter_ends_next_year() {
+        let (start, end) = quarter_range(2026, 4).unwrap();
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(
+            quarter_range(2026, 2),
+            Some((
+                Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap(),
+            ))
+        );
+        assert_eq!(quarter_range(2026, 5), None);
+    }
+}
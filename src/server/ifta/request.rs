@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetIftaReportRequest {
+    pub token: String,
+    pub year: i32,
+    pub quarter: u32,
+}
@@ -0,0 +1,9 @@
+use crate::server::ifta::report::IftaReport;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IftaReportResponse {
+    pub report: IftaReport,
+    pub file_name: String,
+    pub data_url: String,
+}
@@ -0,0 +1,190 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use bson::doc;
+use dioxus::prelude::*;
+
+use crate::server::auth::controller::auth;
+use crate::server::auth::model::User;
+use crate::server::common::response::SuccessResponse;
+use crate::server::hos::stream::odometer_miles_between;
+use crate::server::inspection::model::InspectionClaims;
+use crate::server::inspection::request::{CreateInspectionLinkRequest, GetInspectionRequest};
+use crate::server::inspection::response::{
+    InspectionDay, InspectionLinkResponse, InspectionResponse,
+};
+use crate::server::trip::certification::{
+    certification_status, recorded_entries, CertificationStatus,
+};
+use crate::server::trip::model::{DailyLog, LogEntry};
+use crate::server::trip::printout::{
+    category_totals, data_url, recorded_spans, render_svg, row_totals, SheetHeader,
+};
+use crate::server::trip::response::DailyLogSheet;
+
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use chrono::Duration;
+use futures_util::TryStreamExt;
+use std::env;
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::server::hos::controller::load_duty_events,
+    crate::server::telematics::controller::driver_device,
+    jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation},
+};
+
+// The current day plus the previous seven (49 CFR 395.8(k)(2)).
+pub const INSPECTION_DAYS: i64 = 8;
+pub const MAX_LINK_MINUTES: i64 = 240;
+
+#[cfg(feature = "server")]
+fn inspection_secret() -> String {
+    format!(
+        "{}:inspection",
+        env::var("JWT_SECRET").expect("JWT_SECRET must be set")
+    )
+}
+
+/// Issues a link that shows the driver's last eight days to an officer without
+/// a login. It expires after `minutes`, capped at `MAX_LINK_MINUTES`.
+#[server]
+pub async fn create_inspection_link(
+    req: CreateInspectionLinkRequest,
+) -> Result<SuccessResponse<InspectionLinkResponse>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let now = Utc::now();
+    let expires_at = now + Duration::minutes(req.minutes.clamp(5, MAX_LINK_MINUTES));
+    let claims = InspectionClaims {
+        sub: user.id.to_hex(),
+        iat: now.timestamp() as usize,
+        exp: expires_at.timestamp() as usize,
+    };
+    let link = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(inspection_secret().as_ref()),
+    )?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: InspectionLinkResponse { link, expires_at },
+    })
+}
+
+#[server]
+pub async fn get_inspection(
+    req: GetInspectionRequest,
+) -> Result<SuccessResponse<InspectionResponse>, ServerFnError> {
+    let claims = decode::<InspectionClaims>(
+        &req.link,
+        &DecodingKey::from_secret(inspection_secret().as_ref()),
+        &Validation::default(),
+    )
+    .map_err(|_| ServerFnError::new("This inspection link is invalid or has expired"))?
+    .claims;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let user_collection = db.collection::<User>("users");
+    let daily_collection = db.collection::<DailyLog>("daily_logs");
+    let entry_collection = db.collection::<LogEntry>("log_entries");
+
+    let driver_id = ObjectId::parse_str(&claims.sub)?;
+    let driver = user_collection
+        .find_one(doc! { "_id": driver_id })
+        .await?
+        .ok_or(ServerFnError::new("Driver not found"))?;
+
+    let now = Utc::now();
+    let today = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .ok_or(ServerFnError::new("Invalid date"))?
+        .and_utc();
+    let from = today - Duration::days(INSPECTION_DAYS - 1);
+
+    let events = load_duty_events(driver_id, from).await?;
+
+    let daily_logs: Vec<DailyLog> = daily_collection
+        .find(doc! { "driverId": driver_id })
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut days = Vec::with_capacity(INSPECTION_DAYS as usize);
+    for offset in 0..INSPECTION_DAYS {
+        let date = today - Duration::days(offset);
+
+        // The daily log the driver signed for the day, if any.
+        let stored = daily_logs
+            .iter()
+            .filter(|log| log.log_date == date)
+            .max_by_key(|log| (log.certified_at.is_some(), log.signature.is_some()))
+            .cloned();
+        let log = stored.clone().unwrap_or_else(|| DailyLog {
+            id: ObjectId::new(),
+            driver_id,
+            trip_id: ObjectId::new(),
+            log_date: date,
+            signature: None,
+            certified_at: None,
+            entries_hash: None,
+            created_at: now,
+            updated_at: now,
+        });
+
+        // Recorded duty status wins; the daily log covers days without any. The
+        // certification is checked against exactly these entries.
+        let mut entries = recorded_entries(&events, &log, now);
+        if entries.is_empty() && stored.is_some() {
+            entries = entry_collection
+                .find(doc! { "logId": log.id })
+                .sort(doc! { "time": 1 })
+                .await?
+                .try_collect()
+                .await?;
+        }
+        let sheet = DailyLogSheet { log, entries };
+        let certification = certification_status(&sheet);
+
+        let miles_driven =
+            odometer_miles_between(&events, date, date + Duration::days(1)).unwrap_or_default();
+
+        let header = SheetHeader {
+            driver_name: driver.name.clone(),
+            carrier_name: driver.carrier_name.clone().unwrap_or_default(),
+            truck_number: driver.truck_number.clone().unwrap_or_default(),
+            miles_driven,
+            miles_estimated: false,
+            recorded_until: (date == today).then_some(now),
+        };
+        let spans = recorded_spans(&header, &sheet);
+        days.push(InspectionDay {
+            date,
+            recorded: !sheet.entries.is_empty(),
+            certification,
+            totals: row_totals(&spans),
+            categories: category_totals(&spans),
+            sheet: data_url("image/svg+xml", render_svg(&header, &sheet).as_bytes()),
+        });
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: InspectionResponse {
+            driver_name: driver.name,
+            carrier_name: driver.carrier_name.unwrap_or_default(),
+            truck_number: driver.truck_number.unwrap_or_default(),
+            license_number: driver.license_number.unwrap_or_default(),
+            eld_identifier: driver_device(driver.id).await?.unwrap_or_default(),
+            days,
+            generated_at: now,
+            expires_at: DateTime::from_timestamp(claims.exp as i64, 0).unwrap_or(now),
+        },
+    })
+}
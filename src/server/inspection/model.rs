@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+// Claims of a roadside inspection link. They are signed with a key of their own,
+// so a link can never be used as a login token.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InspectionClaims {
+    pub sub: String, // Driver ID
+    pub iat: usize,
+    pub exp: usize,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateInspectionLinkRequest {
+    pub token: String,
+    pub minutes: i64, // How long the link stays valid
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetInspectionRequest {
+    pub link: String, // Signed inspection token from the link
+}
@@ -0,0 +1,32 @@
+use crate::server::hos::model::{CategoryTotals, StatusTotals};
+use crate::server::trip::certification::CertificationStatus;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InspectionLinkResponse {
+    pub link: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InspectionDay {
+    pub date: DateTime<Utc>,
+    pub recorded: bool, // False when the driver has no records for the day
+    pub certification: CertificationStatus,
+    pub totals: StatusTotals,
+    pub categories: CategoryTotals,
+    pub sheet: String, // Data URL of the day's log sheet as SVG
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InspectionResponse {
+    pub driver_name: String,
+    pub carrier_name: String,
+    pub truck_number: String,
+    pub license_number: String,
+    pub eld_identifier: String,
+    pub days: Vec<InspectionDay>, // Today first
+    pub generated_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
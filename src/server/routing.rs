@@ -0,0 +1,12 @@
+pub(crate) mod cache;
+#[cfg(feature = "server")]
+pub(crate) mod client;
+pub(crate) mod controller;
+pub(crate) mod fake;
+pub(crate) mod geometry;
+#[cfg(feature = "server")]
+pub(crate) mod google;
+#[cfg(feature = "server")]
+pub(crate) mod osrm;
+pub(crate) mod provider;
+pub(crate) mod request;
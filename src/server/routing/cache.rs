@@ -0,0 +1,159 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Provider answers kept for `ttl`, dropping the oldest entries beyond
+/// `capacity`.
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    capacity: usize,
+    entries: HashMap<K, (Instant, V)>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, key: &K, now: Instant) -> Option<V> {
+        match self.entries.get(key) {
+            Some((stored, value)) if now.duration_since(*stored) < self.ttl => Some(value.clone()),
+            Some(_) => {
+                self.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V, now: Instant) {
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            let ttl = self.ttl;
+            self.entries
+                .retain(|_, (stored, _)| now.duration_since(*stored) < ttl);
+            if self.entries.len() >= self.capacity {
+                if let Some(oldest) = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, (stored, _))| *stored)
+                    .map(|(key, _)| key.clone())
+                {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.entries.insert(key, (now, value));
+    }
+}
+
+/// Allows each user at most `limit` calls in any `window`.
+pub struct RateLimiter {
+    window: Duration,
+    limit: usize,
+    calls: HashMap<String, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(window: Duration, limit: usize) -> Self {
+        Self {
+            window,
+            limit,
+            calls: HashMap::new(),
+        }
+    }
+
+    /// Records `count` calls by `user` and whether they all fit within the limit.
+    /// Refused calls are not recorded.
+    pub fn allow(&mut self, user: &str, count: usize, now: Instant) -> bool {
+        let window = self.window;
+        self.calls.retain(|_, calls| {
+            calls
+                .back()
+                .is_some_and(|last| now.duration_since(*last) < window)
+        });
+        let calls = self.calls.entry(user.to_string()).or_default();
+        while calls
+            .front()
+            .is_some_and(|first| now.duration_since(*first) >= window)
+        {
+            calls.pop_front();
+        }
+        if calls.len() + count > self.limit {
+            return false;
+        }
+        calls.extend(std::iter::repeat_n(now, count));
+        true
+    }
+}
+
+/// Cache key of a free-form location, ignoring case and spacing.
+pub fn location_key(location: &str) -> String {
+    location
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(start: Instant, seconds: u64) -> Instant {
+        start + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn entries_expire_at_the_ttl() {
+        let start = Instant::now();
+        let mut cache = TtlCache::new(Duration::from_secs(60), 10);
+        cache.insert("dallas", 1, start);
+        assert_eq!(cache.get(&"dallas", after(start, 59)), Some(1));
+        assert_eq!(cache.get(&"dallas", after(start, 60)), None);
+    }
+
+    #[test]
+    fn oldest_entry_is_evicted_at_capacity() {
+        let start = Instant::now();
+        let mut cache = TtlCache::new(Duration::from_secs(60), 2);
+        cache.insert("dallas", 1, start);
+        cache.insert("waco", 2, after(start, 1));
+        cache.insert("austin", 3, after(start, 2));
+        assert_eq!(cache.get(&"dallas", after(start, 3)), None);
+        assert_eq!(cache.get(&"waco", after(start, 3)), Some(2));
+        assert_eq!(cache.get(&"austin", after(start, 3)), Some(3));
+
+        // Replacing a cached key evicts nothing.
+        cache.insert("waco", 4, after(start, 4));
+        assert_eq!(cache.get(&"austin", after(start, 5)), Some(3));
+    }
+
+    #[test]
+    fn refused_calls_are_not_recorded() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(Duration::from_secs(60), 5);
+        assert!(limiter.allow("driver", 3, start));
+        assert!(!limiter.allow("driver", 3, start));
+        // The refused three left room for two more.
+        assert!(limiter.allow("driver", 2, start));
+        assert!(!limiter.allow("driver", 1, start));
+        assert!(limiter.allow("another driver", 5, start));
+    }
+
+    #[test]
+    fn window_slides() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(Duration::from_secs(60), 2);
+        assert!(limiter.allow("driver", 1, start));
+        assert!(limiter.allow("driver", 1, after(start, 30)));
+        assert!(!limiter.allow("driver", 1, after(start, 59)));
+        // The first call has left the window; the second has not.
+        assert!(limiter.allow("driver", 1, after(start, 60)));
+        assert!(!limiter.allow("driver", 1, after(start, 89)));
+        assert!(limiter.allow("driver", 1, after(start, 90)));
+    }
+}
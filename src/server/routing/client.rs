@@ -0,0 +1,198 @@
+use crate::server::routing::cache::{location_key, RateLimiter, TtlCache};
+use crate::server::routing::fake::FakeRouting;
+use crate::server::routing::geometry::LatLng;
+use crate::server::routing::google::GoogleRouting;
+use crate::server::routing::osrm::OsrmRouting;
+use crate::server::routing::provider::{DistanceDuration, Matrix, Place, Route, RoutingProvider};
+use dioxus::prelude::*;
+use std::env;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+const OSRM_URL: &str = "https://router.project-osrm.org";
+const NOMINATIM_URL: &str = "https://nominatim.openstreetmap.org";
+
+// Provider requests each driver may cause per minute. Answers from the cache
+// are free.
+pub const CALLS_PER_MINUTE: usize = 30;
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+const CACHE_CAPACITY: usize = 2000;
+
+static ROUTING: OnceLock<Routing> = OnceLock::new();
+static ROUTES: OnceLock<Mutex<TtlCache<Vec<String>, Route>>> = OnceLock::new();
+static MATRICES: OnceLock<Mutex<TtlCache<Vec<String>, Matrix>>> = OnceLock::new();
+static PLACES: OnceLock<Mutex<TtlCache<String, Vec<Place>>>> = OnceLock::new();
+static LIMITER: OnceLock<Mutex<RateLimiter>> = OnceLock::new();
+
+/// The provider chosen by `ROUTING_PROVIDER`.
+pub enum Routing {
+    Google(GoogleRouting),
+    Osrm(OsrmRouting),
+    Fake(FakeRouting),
+}
+
+fn url_or(name: &str, default: &str) -> String {
+    env::var(name)
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or(default.to_string())
+}
+
+fn init_routing() -> Result<Routing, ServerFnError> {
+    match env::var("ROUTING_PROVIDER")
+        .unwrap_or_default()
+        .to_lowercase()
+        .as_str()
+    {
+        "osrm" => Ok(Routing::Osrm(OsrmRouting::new(
+            url_or("OSRM_URL", OSRM_URL),
+            url_or("NOMINATIM_URL", NOMINATIM_URL),
+        ))),
+        "fake" => Ok(Routing::Fake(FakeRouting::default())),
+        "" | "google" => env::var("GOOGLE_MAPS_API_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+            .map(|key| Routing::Google(GoogleRouting::new(key)))
+            .ok_or(ServerFnError::new(
+                "Maps are not configured: GOOGLE_MAPS_API_KEY is not set",
+            )),
+        other => Err(ServerFnError::new(format!(
+            "Maps are not configured: unknown ROUTING_PROVIDER {}",
+            other
+        ))),
+    }
+}
+
+/// The provider chosen by `ROUTING_PROVIDER`. A missing or unknown setting fails
+/// the request that needed a map instead of the server, and is read again on the
+/// next request.
+pub fn get_routing() -> Result<&'static Routing, ServerFnError> {
+    if let Some(routing) = ROUTING.get() {
+        return Ok(routing);
+    }
+    let routing = init_routing()?;
+    Ok(ROUTING.get_or_init(|| routing))
+}
+
+impl Routing {
+    // Requests to the provider's servers for a route or matrix over `places`.
+    // OSRM geocodes every place with Nominatim first; the fake provider answers
+    // locally but is charged like a single request.
+    fn upstream_calls(&self, places: usize) -> usize {
+        match self {
+            Routing::Osrm(_) => places + 1,
+            Routing::Google(_) | Routing::Fake(_) => 1,
+        }
+    }
+}
+
+// The caches and the limiter stay usable after a panic while they were held:
+// the worst left behind is an entry too many or too few.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn allow(user: &str, calls: usize) -> Result<(), ServerFnError> {
+    let limiter = LIMITER
+        .get_or_init(|| Mutex::new(RateLimiter::new(Duration::from_secs(60), CALLS_PER_MINUTE)));
+    let mut limiter = lock(limiter);
+    if limiter.allow(user, calls, Instant::now()) {
+        Ok(())
+    } else {
+        Err(ServerFnError::new(
+            "Too many map requests, try again in a minute",
+        ))
+    }
+}
+
+/// A route through `stops` for `user`, from the cache when the same stops were
+/// asked for within the hour.
+pub async fn cached_route(user: &str, stops: &[String]) -> Result<Route, ServerFnError> {
+    let cache = ROUTES.get_or_init(|| Mutex::new(TtlCache::new(CACHE_TTL, CACHE_CAPACITY)));
+    let key: Vec<String> = stops.iter().map(|stop| location_key(stop)).collect();
+    if let Some(route) = lock(cache).get(&key, Instant::now()) {
+        return Ok(route);
+    }
+    let routing = get_routing()?;
+    allow(user, routing.upstream_calls(stops.len()))?;
+    let route = routing.route(stops).await?;
+    lock(cache).insert(key, route.clone(), Instant::now());
+    Ok(route)
+}
+
+/// Place suggestions for `user`, cached like routes.
+pub async fn cached_autocomplete(user: &str, input: &str) -> Result<Vec<Place>, ServerFnError> {
+    let cache = PLACES.get_or_init(|| Mutex::new(TtlCache::new(CACHE_TTL, CACHE_CAPACITY)));
+    let key = location_key(input);
+    if let Some(places) = lock(cache).get(&key, Instant::now()) {
+        return Ok(places);
+    }
+    let routing = get_routing()?;
+    allow(user, 1)?;
+    let places = routing.autocomplete(input).await?;
+    lock(cache).insert(key, places.clone(), Instant::now());
+    Ok(places)
+}
+
+/// Travel between every pair of `locations` for `user`, cached like routes.
+pub async fn cached_matrix(user: &str, locations: &[String]) -> Result<Matrix, ServerFnError> {
+    let cache = MATRICES.get_or_init(|| Mutex::new(TtlCache::new(CACHE_TTL, CACHE_CAPACITY)));
+    let key: Vec<String> = locations
+        .iter()
+        .map(|location| location_key(location))
+        .collect();
+    if let Some(matrix) = lock(cache).get(&key, Instant::now()) {
+        return Ok(matrix);
+    }
+    let routing = get_routing()?;
+    allow(user, routing.upstream_calls(locations.len()))?;
+    let matrix = routing.matrix(locations).await?;
+    lock(cache).insert(key, matrix.clone(), Instant::now());
+    Ok(matrix)
+}
+
+impl RoutingProvider for Routing {
+    async fn autocomplete(&self, input: &str) -> Result<Vec<Place>, ServerFnError> {
+        match self {
+            Routing::Google(provider) => provider.autocomplete(input).await,
+            Routing::Osrm(provider) => provider.autocomplete(input).await,
+            Routing::Fake(provider) => provider.autocomplete(input).await,
+        }
+    }
+
+    async fn geocode(&self, address: &str) -> Result<LatLng, ServerFnError> {
+        match self {
+            Routing::Google(provider) => provider.geocode(address).await,
+            Routing::Osrm(provider) => provider.geocode(address).await,
+            Routing::Fake(provider) => provider.geocode(address).await,
+        }
+    }
+
+    async fn route(&self, stops: &[String]) -> Result<Route, ServerFnError> {
+        match self {
+            Routing::Google(provider) => provider.route(stops).await,
+            Routing::Osrm(provider) => provider.route(stops).await,
+            Routing::Fake(provider) => provider.route(stops).await,
+        }
+    }
+
+    async fn distance_duration(
+        &self,
+        origin: &str,
+        destination: &str,
+    ) -> Result<DistanceDuration, ServerFnError> {
+        match self {
+            Routing::Google(provider) => provider.distance_duration(origin, destination).await,
+            Routing::Osrm(provider) => provider.distance_duration(origin, destination).await,
+            Routing::Fake(provider) => provider.distance_duration(origin, destination).await,
+        }
+    }
+
+    async fn matrix(&self, locations: &[String]) -> Result<Matrix, ServerFnError> {
+        match self {
+            Routing::Google(provider) => provider.matrix(locations).await,
+            Routing::Osrm(provider) => provider.matrix(locations).await,
+            Routing::Fake(provider) => provider.matrix(locations).await,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use dioxus::prelude::*;
+
+use crate::server::auth::controller::auth;
+use crate::server::common::response::SuccessResponse;
+use crate::server::routing::provider::{check_stops, Place, Route};
+use crate::server::routing::request::{GetRouteRequest, PlacesAutocompleteRequest};
+use crate::server::trip::optimizer::MAX_TRIP_STOPS;
+
+#[cfg(feature = "server")]
+use crate::server::routing::client::{cached_autocomplete, cached_route};
+
+/// Place suggestions for a partly typed location.
+#[server]
+pub async fn fetch_places_autocomplete(
+    req: PlacesAutocompleteRequest,
+) -> Result<SuccessResponse<Vec<Place>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let places = if req.input.trim().is_empty() {
+        vec![]
+    } else {
+        cached_autocomplete(&user.id.to_hex(), &req.input).await?
+    };
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: places,
+    })
+}
+
+/// The driving route from the origin through the waypoints to the destination.
+#[server]
+pub async fn get_route(req: GetRouteRequest) -> Result<SuccessResponse<Route>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    // The same limit as a trip: its start and at most `MAX_TRIP_STOPS` stops.
+    if req.waypoints.len() + 1 > MAX_TRIP_STOPS {
+        return Err(ServerFnError::new(format!(
+            "A route can have at most {} waypoints",
+            MAX_TRIP_STOPS - 1
+        )));
+    }
+    let mut stops = vec![req.origin];
+    stops.extend(req.waypoints);
+    stops.push(req.destination);
+    check_stops(&stops)?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: cached_route(&user.id.to_hex(), &stops).await?,
+    })
+}
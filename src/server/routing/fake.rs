@@ -0,0 +1,257 @@
+use crate::server::routing::geometry::{distance_miles, LatLng};
+use crate::server::routing::provider::{
+    check_stops, DistanceDuration, Place, Route, RoutingProvider,
+};
+use dioxus::prelude::*;
+
+// Straight-line distance times this approximates the distance by road.
+pub const ROAD_FACTOR: f64 = 1.2;
+pub const AVERAGE_SPEED_MPH: f64 = 55.0;
+
+const PLACES: [(&str, f64, f64); 12] = [
+    ("Atlanta, GA, USA", 33.749, -84.388),
+    ("Chicago, IL, USA", 41.8781, -87.6298),
+    ("Dallas, TX, USA", 32.7767, -96.797),
+    ("Denver, CO, USA", 39.7392, -104.9903),
+    ("Kansas City, MO, USA", 39.0997, -94.5786),
+    ("Los Angeles, CA, USA", 34.0522, -118.2437),
+    ("Memphis, TN, USA", 35.1495, -90.049),
+    ("Nashville, TN, USA", 36.1627, -86.7816),
+    ("Oklahoma City, OK, USA", 35.4676, -97.5164),
+    ("Phoenix, AZ, USA", 33.4484, -112.074),
+    ("St. Louis, MO, USA", 38.627, -90.1994),
+    ("Toronto, ON, Canada", 43.6532, -79.3832),
+];
+
+/// A deterministic, offline provider for development and tests. Places come
+/// from a fixed list, "latitude, longitude" strings are taken literally and any
+/// other address is hashed to a stable point in the contiguous US. Routes are
+/// straight lines between the stops, at `ROAD_FACTOR` and `AVERAGE_SPEED_MPH`.
+pub struct FakeRouting {
+    places: Vec<(String, LatLng)>,
+}
+
+impl Default for FakeRouting {
+    fn default() -> Self {
+        Self {
+            places: PLACES
+                .iter()
+                .map(|(name, latitude, longitude)| {
+                    (
+                        name.to_string(),
+                        LatLng {
+                            latitude: *latitude,
+                            longitude: *longitude,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl FakeRouting {
+    /// Adds or moves a named place.
+    pub fn with_place(mut self, name: &str, position: LatLng) -> Self {
+        self.places
+            .retain(|(known, _)| !known.eq_ignore_ascii_case(name));
+        self.places.push((name.to_string(), position));
+        self
+    }
+
+    fn locate(&self, address: &str) -> Option<LatLng> {
+        let address = address.trim();
+        if address.is_empty() {
+            return None;
+        }
+        if let Some((_, position)) = self
+            .places
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(address))
+        {
+            return Some(*position);
+        }
+        if let Some((latitude, longitude)) = address.split_once(',').and_then(|(lat, lng)| {
+            Some((
+                lat.trim().parse::<f64>().ok()?,
+                lng.trim().parse::<f64>().ok()?,
+            ))
+        }) {
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) {
+                return Some(LatLng {
+                    latitude,
+                    longitude,
+                });
+            }
+        }
+
+        // FNV-1a, so the point does not change between runs or builds.
+        let hash = address
+            .to_lowercase()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        Some(LatLng {
+            latitude: 30.0 + (hash & 0xffff) as f64 / 65535.0 * 17.0,
+            longitude: -120.0 + (hash >> 16 & 0xffff) as f64 / 65535.0 * 45.0,
+        })
+    }
+}
+
+impl RoutingProvider for FakeRouting {
+    async fn autocomplete(&self, input: &str) -> Result<Vec<Place>, ServerFnError> {
+        let input = input.trim().to_lowercase();
+        Ok(self
+            .places
+            .iter()
+            .enumerate()
+            .filter(|(_, (name, _))| !input.is_empty() && name.to_lowercase().contains(&input))
+            .take(5)
+            .map(|(index, (name, _))| Place {
+                description: name.clone(),
+                place_id: format!("fake-{}", index),
+            })
+            .collect())
+    }
+
+    async fn geocode(&self, address: &str) -> Result<LatLng, ServerFnError> {
+        self.locate(address).ok_or(ServerFnError::new(format!(
+            "Location not found: {}",
+            address
+        )))
+    }
+
+    async fn route(&self, stops: &[String]) -> Result<Route, ServerFnError> {
+        check_stops(stops)?;
+        let mut polyline = Vec::with_capacity(stops.len());
+        for stop in stops {
+            polyline.push(self.geocode(stop).await?);
+        }
+        let legs = polyline
+            .windows(2)
+            .map(|pair| {
+                let miles = distance_miles(pair[0], pair[1]) * ROAD_FACTOR;
+                DistanceDuration {
+                    distance_miles: miles,
+                    duration_seconds: (miles / AVERAGE_SPEED_MPH * 3600.0).round() as i64,
+                }
+            })
+            .collect();
+        Ok(Route { legs, polyline })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::hos::model::CycleRule;
+    use crate::server::trip::model::{StopType, TripStop};
+    use crate::server::trip::planner::{plan_itinerary, stop_legs};
+    use chrono::prelude::*;
+    use chrono::Duration;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    // The fake provider never waits, so its futures are done on the first poll.
+    fn ready<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the fake provider does not wait"),
+        }
+    }
+
+    fn places(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn stop(location: &str, stop_type: StopType) -> TripStop {
+        TripStop {
+            location: location.to_string(),
+            stop_type,
+            window_start: None,
+            window_end: None,
+        }
+    }
+
+    #[test]
+    fn distance_by_road_factor_and_speed() {
+        let fake = FakeRouting::default();
+        let straight = distance_miles(
+            fake.locate("Dallas, TX, USA").unwrap(),
+            fake.locate("Oklahoma City, OK, USA").unwrap(),
+        );
+        let travel =
+            ready(fake.distance_duration("dallas, tx, usa", "Oklahoma City, OK, USA")).unwrap();
+
+        assert!((180.0..200.0).contains(&straight));
+        assert!((travel.distance_miles - straight * ROAD_FACTOR).abs() < 1e-9);
+        assert_eq!(
+            travel.duration_seconds,
+            (straight * ROAD_FACTOR / AVERAGE_SPEED_MPH * 3600.0).round() as i64
+        );
+    }
+
+    #[test]
+    fn matrix_agrees_with_routes() {
+        let fake = FakeRouting::default();
+        let stops = places(&[
+            "Dallas, TX, USA",
+            "Oklahoma City, OK, USA",
+            "Kansas City, MO, USA",
+        ]);
+        let route = ready(fake.route(&stops)).unwrap();
+        let matrix = ready(fake.matrix(&stops)).unwrap();
+
+        assert_eq!(route.legs.len(), 2);
+        assert_eq!(matrix[0][1], route.legs[0]);
+        assert_eq!(matrix[1][2], route.legs[1]);
+        assert_eq!(matrix[2][0], matrix[0][2]);
+        assert_eq!(matrix[1][1], DistanceDuration::default());
+        assert!(ready(fake.route(&stops[..1])).is_err());
+    }
+
+    #[test]
+    fn plans_a_trip_over_a_fake_route() {
+        let fake = FakeRouting::default();
+        let departure = Utc.with_ymd_and_hms(2026, 3, 2, 6, 0, 0).unwrap();
+        let stops = vec![
+            stop("St. Louis, MO, USA", StopType::Pickup),
+            stop("Dallas, TX, USA", StopType::Dropoff),
+        ];
+        let route = ready(fake.route(&places(&[
+            "Chicago, IL, USA",
+            "St. Louis, MO, USA",
+            "Dallas, TX, USA",
+        ])))
+        .unwrap();
+        let itinerary = plan_itinerary(
+            &stop_legs("Chicago, IL, USA", &stops, &route.legs),
+            CycleRule::SeventyEight,
+            Duration::zero(),
+            departure,
+        );
+
+        let total = route.total();
+        assert!((itinerary.total_distance_miles - total.distance_miles).abs() < 1.0);
+        assert_eq!(
+            itinerary.driving_minutes,
+            route
+                .legs
+                .iter()
+                .map(|leg| leg.duration_seconds / 60)
+                .sum::<i64>()
+        );
+        // Over 11 hours of driving needs a 10-hour rest on the way; the hour at
+        // the pickup already counts as the 30-minute break.
+        assert!(itinerary.driving_minutes > 11 * 60);
+        let stop_types: Vec<StopType> = itinerary.stops.iter().map(|stop| stop.stop_type).collect();
+        assert_eq!(stop_types[0], StopType::Pickup);
+        assert!(!stop_types.contains(&StopType::Break));
+        assert!(stop_types.contains(&StopType::Rest));
+        assert_eq!(stop_types.last(), Some(&StopType::Dropoff));
+        assert!(itinerary.arrival > departure + Duration::minutes(itinerary.driving_minutes + 600));
+        assert!(itinerary.missed_windows.is_empty());
+    }
+}
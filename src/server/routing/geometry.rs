@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+const EARTH_RADIUS_MILES: f64 = 3958.8;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct LatLng {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Great-circle distance between two positions.
+pub fn distance_miles(from: LatLng, to: LatLng) -> f64 {
+    let (lat1, lat2) = (from.latitude.to_radians(), to.latitude.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (to.longitude - from.longitude).to_radians();
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_MILES * a.sqrt().min(1.0).asin()
+}
+
+/// Decodes a route in Google's encoded polyline format (five decimal places),
+/// as returned by the Directions API and by OSRM. None when the string is
+/// malformed.
+pub fn decode_polyline(encoded: &str) -> Option<Vec<LatLng>> {
+    let mut bytes = encoded.bytes();
+    let mut next_value = || -> Option<Option<i64>> {
+        let mut result = 0i64;
+        let mut shift = 0;
+        loop {
+            let Some(byte) = bytes.next() else {
+                return if shift == 0 { Some(None) } else { None };
+            };
+            if !(63..=126).contains(&byte) || shift > 30 {
+                return None;
+            }
+            let chunk = (byte - 63) as i64;
+            result |= (chunk & 0x1f) << shift;
+            shift += 5;
+            if chunk < 0x20 {
+                break;
+            }
+        }
+        Some(Some(if result & 1 == 1 {
+            !(result >> 1)
+        } else {
+            result >> 1
+        }))
+    };
+
+    let mut points = Vec::new();
+    let (mut latitude, mut longitude) = (0i64, 0i64);
+    while let Some(dlat) = next_value()? {
+        let dlon = next_value()??;
+        latitude += dlat;
+        longitude += dlon;
+        points.push(LatLng {
+            latitude: latitude as f64 / 1e5,
+            longitude: longitude as f64 / 1e5,
+        });
+    }
+    Some(points)
+}
@@ -0,0 +1,274 @@
+use crate::server::routing::geometry::{decode_polyline, LatLng};
+use crate::server::routing::provider::{
+    check_stops, DistanceDuration, Matrix, Place, Route, RoutingProvider, METERS_PER_MILE,
+};
+use dioxus::prelude::*;
+use reqwest::Client as ReqClient;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+const API_URL: &str = "https://maps.googleapis.com/maps/api";
+// Distance Matrix allows 100 elements per request.
+const MATRIX_LOCATIONS: usize = 10;
+
+#[derive(Deserialize)]
+struct Status {
+    status: String,
+    error_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AutocompleteResponse {
+    predictions: Vec<Prediction>,
+}
+
+#[derive(Deserialize)]
+struct Prediction {
+    description: String,
+    place_id: String,
+}
+
+#[derive(Deserialize)]
+struct GeocodeResponse {
+    results: Vec<GeocodeResult>,
+}
+
+#[derive(Deserialize)]
+struct GeocodeResult {
+    geometry: Geometry,
+}
+
+#[derive(Deserialize)]
+struct Geometry {
+    location: Location,
+}
+
+#[derive(Deserialize)]
+struct Location {
+    lat: f64,
+    lng: f64,
+}
+
+#[derive(Deserialize)]
+struct DistanceMatrixResponse {
+    rows: Vec<Row>,
+}
+
+#[derive(Deserialize)]
+struct Row {
+    elements: Vec<Element>,
+}
+
+#[derive(Deserialize)]
+struct Element {
+    status: String,
+    distance: Option<Value>,
+    duration: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct Value {
+    value: f64, // Meters or seconds
+}
+
+#[derive(Deserialize)]
+struct DirectionsResponse {
+    routes: Vec<DirectionsRoute>,
+}
+
+#[derive(Deserialize)]
+struct DirectionsRoute {
+    legs: Vec<DirectionsLeg>,
+    overview_polyline: OverviewPolyline,
+}
+
+#[derive(Deserialize)]
+struct DirectionsLeg {
+    distance: Value,
+    duration: Value,
+}
+
+#[derive(Deserialize)]
+struct OverviewPolyline {
+    points: String,
+}
+
+fn distance_duration(distance: &Value, duration: &Value) -> DistanceDuration {
+    DistanceDuration {
+        distance_miles: distance.value / METERS_PER_MILE,
+        duration_seconds: duration.value as i64,
+    }
+}
+
+/// Google Maps Places, Geocoding, Distance Matrix and Directions APIs.
+pub struct GoogleRouting {
+    client: ReqClient,
+    api_key: String,
+}
+
+impl GoogleRouting {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: ReqClient::new(),
+            api_key,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, ServerFnError> {
+        let body = self
+            .client
+            .get(format!("{}/{}/json", API_URL, endpoint))
+            .query(query)
+            .query(&[("key", self.api_key.as_str())])
+            .send()
+            .await
+            .map_err(|_| ServerFnError::new("Error fetching data from Google API"))?
+            .text()
+            .await
+            .map_err(|_| ServerFnError::new("Error reading response from Google API"))?;
+
+        let status: Status = serde_json::from_str(&body)
+            .map_err(|_| ServerFnError::new("Error parsing response from Google API"))?;
+        match status.status.as_str() {
+            "OK" | "ZERO_RESULTS" => {}
+            other => {
+                return Err(ServerFnError::new(format!(
+                    "Google API error: {}",
+                    status.error_message.as_deref().unwrap_or(other)
+                )))
+            }
+        }
+        serde_json::from_str(&body)
+            .map_err(|_| ServerFnError::new("Error parsing response from Google API"))
+    }
+}
+
+impl RoutingProvider for GoogleRouting {
+    async fn autocomplete(&self, input: &str) -> Result<Vec<Place>, ServerFnError> {
+        let response: AutocompleteResponse =
+            self.get("place/autocomplete", &[("input", input)]).await?;
+        Ok(response
+            .predictions
+            .into_iter()
+            .map(|prediction| Place {
+                description: prediction.description,
+                place_id: prediction.place_id,
+            })
+            .collect())
+    }
+
+    async fn geocode(&self, address: &str) -> Result<LatLng, ServerFnError> {
+        let response: GeocodeResponse = self.get("geocode", &[("address", address)]).await?;
+        let location = response
+            .results
+            .first()
+            .map(|result| &result.geometry.location)
+            .ok_or(ServerFnError::new(format!(
+                "Location not found: {}",
+                address
+            )))?;
+        Ok(LatLng {
+            latitude: location.lat,
+            longitude: location.lng,
+        })
+    }
+
+    async fn distance_duration(
+        &self,
+        origin: &str,
+        destination: &str,
+    ) -> Result<DistanceDuration, ServerFnError> {
+        let response: DistanceMatrixResponse = self
+            .get(
+                "distancematrix",
+                &[("origins", origin), ("destinations", destination)],
+            )
+            .await?;
+        match response.rows.first().and_then(|row| row.elements.first()) {
+            Some(Element {
+                status,
+                distance: Some(distance),
+                duration: Some(duration),
+            }) if status == "OK" => Ok(distance_duration(distance, duration)),
+            _ => Err(ServerFnError::new(format!(
+                "No route from {} to {}",
+                origin, destination
+            ))),
+        }
+    }
+
+    async fn matrix(&self, locations: &[String]) -> Result<Matrix, ServerFnError> {
+        if locations.len() > MATRIX_LOCATIONS {
+            return Err(ServerFnError::new(format!(
+                "At most {} places can be compared at once",
+                MATRIX_LOCATIONS
+            )));
+        }
+        let places = locations.join("|");
+        let response: DistanceMatrixResponse = self
+            .get(
+                "distancematrix",
+                &[
+                    ("origins", places.as_str()),
+                    ("destinations", places.as_str()),
+                ],
+            )
+            .await?;
+        if response.rows.len() != locations.len() {
+            return Err(ServerFnError::new("Incomplete response from Google API"));
+        }
+        let mut matrix = vec![vec![DistanceDuration::default(); locations.len()]; locations.len()];
+        for (i, row) in response.rows.iter().enumerate() {
+            for (j, element) in row.elements.iter().enumerate().take(locations.len()) {
+                if i == j {
+                    continue;
+                }
+                matrix[i][j] = match element {
+                    Element {
+                        status,
+                        distance: Some(distance),
+                        duration: Some(duration),
+                    } if status == "OK" => distance_duration(distance, duration),
+                    _ => {
+                        return Err(ServerFnError::new(format!(
+                            "No route from {} to {}",
+                            locations[i], locations[j]
+                        )))
+                    }
+                };
+            }
+        }
+        Ok(matrix)
+    }
+
+    async fn route(&self, stops: &[String]) -> Result<Route, ServerFnError> {
+        check_stops(stops)?;
+        let waypoints = stops[1..stops.len() - 1].join("|");
+        let mut query = vec![
+            ("origin", stops[0].as_str()),
+            ("destination", stops[stops.len() - 1].as_str()),
+        ];
+        if !waypoints.is_empty() {
+            query.push(("waypoints", waypoints.as_str()));
+        }
+        let response: DirectionsResponse = self.get("directions", &query).await?;
+        let route = response
+            .routes
+            .into_iter()
+            .next()
+            .ok_or(ServerFnError::new("No route between the trip stops"))?;
+
+        Ok(Route {
+            legs: route
+                .legs
+                .iter()
+                .map(|leg| distance_duration(&leg.distance, &leg.duration))
+                .collect(),
+            polyline: decode_polyline(&route.overview_polyline.points).unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,231 @@
+use crate::server::routing::geometry::{decode_polyline, LatLng};
+use crate::server::routing::provider::{
+    check_stops, DistanceDuration, Matrix, Place, Route, RoutingProvider, METERS_PER_MILE,
+};
+use dioxus::prelude::*;
+use reqwest::Client as ReqClient;
+use serde::Deserialize;
+
+// Nominatim's usage policy asks every client to identify itself.
+const USER_AGENT: &str = "eldflow";
+const SUGGESTIONS: &str = "5";
+
+#[derive(Deserialize)]
+struct SearchResult {
+    place_id: u64,
+    display_name: String,
+    lat: String,
+    lon: String,
+}
+
+#[derive(Deserialize)]
+struct RouteResponse {
+    code: String,
+    message: Option<String>,
+    #[serde(default)]
+    routes: Vec<OsrmRoute>,
+}
+
+#[derive(Deserialize)]
+struct TableResponse {
+    code: String,
+    message: Option<String>,
+    #[serde(default)]
+    distances: Vec<Vec<Option<f64>>>, // Meters; null when unreachable
+    #[serde(default)]
+    durations: Vec<Vec<Option<f64>>>, // Seconds
+}
+
+#[derive(Deserialize)]
+struct OsrmRoute {
+    legs: Vec<OsrmLeg>,
+    geometry: String,
+}
+
+#[derive(Deserialize)]
+struct OsrmLeg {
+    distance: f64, // Meters
+    duration: f64, // Seconds
+}
+
+/// An OSRM routing server with a Nominatim geocoder, as self-hosted or run by
+/// the OpenStreetMap community.
+pub struct OsrmRouting {
+    client: ReqClient,
+    osrm_url: String,
+    nominatim_url: String,
+}
+
+impl OsrmRouting {
+    pub fn new(osrm_url: String, nominatim_url: String) -> Self {
+        Self {
+            client: ReqClient::new(),
+            osrm_url: osrm_url.trim_end_matches('/').to_string(),
+            nominatim_url: nominatim_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    // OSRM's "lon,lat;lon,lat" list of the geocoded places.
+    async fn coordinates(&self, places: &[String]) -> Result<String, ServerFnError> {
+        let mut coordinates = Vec::with_capacity(places.len());
+        for place in places {
+            let position = self.geocode(place).await?;
+            coordinates.push(format!("{},{}", position.longitude, position.latitude));
+        }
+        Ok(coordinates.join(";"))
+    }
+
+    async fn fetch(
+        &self,
+        service: &str,
+        places: &[String],
+        query: &[(&str, &str)],
+    ) -> Result<String, ServerFnError> {
+        self.client
+            .get(format!(
+                "{}/{}/v1/driving/{}",
+                self.osrm_url,
+                service,
+                self.coordinates(places).await?
+            ))
+            .query(query)
+            .send()
+            .await
+            .map_err(|_| ServerFnError::new("Error fetching data from OSRM"))?
+            .text()
+            .await
+            .map_err(|_| ServerFnError::new("Error reading response from OSRM"))
+    }
+
+    async fn search(&self, query: &str, limit: &str) -> Result<Vec<SearchResult>, ServerFnError> {
+        self.client
+            .get(format!("{}/search", self.nominatim_url))
+            .header("User-Agent", USER_AGENT)
+            .query(&[("q", query), ("format", "jsonv2"), ("limit", limit)])
+            .send()
+            .await
+            .map_err(|_| ServerFnError::new("Error fetching data from Nominatim"))?
+            .text()
+            .await
+            .map_err(|_| ServerFnError::new("Error reading response from Nominatim"))
+            .and_then(|body| {
+                serde_json::from_str(&body)
+                    .map_err(|_| ServerFnError::new("Error parsing response from Nominatim"))
+            })
+    }
+}
+
+impl RoutingProvider for OsrmRouting {
+    async fn autocomplete(&self, input: &str) -> Result<Vec<Place>, ServerFnError> {
+        Ok(self
+            .search(input, SUGGESTIONS)
+            .await?
+            .into_iter()
+            .map(|result| Place {
+                description: result.display_name,
+                place_id: result.place_id.to_string(),
+            })
+            .collect())
+    }
+
+    async fn geocode(&self, address: &str) -> Result<LatLng, ServerFnError> {
+        self.search(address, "1")
+            .await?
+            .first()
+            .and_then(|result| {
+                Some(LatLng {
+                    latitude: result.lat.parse().ok()?,
+                    longitude: result.lon.parse().ok()?,
+                })
+            })
+            .ok_or(ServerFnError::new(format!(
+                "Location not found: {}",
+                address
+            )))
+    }
+
+    async fn matrix(&self, locations: &[String]) -> Result<Matrix, ServerFnError> {
+        if locations.len() < 2 {
+            return Ok(vec![
+                vec![DistanceDuration::default(); locations.len()];
+                locations.len()
+            ]);
+        }
+        let body = self
+            .fetch("table", locations, &[("annotations", "distance,duration")])
+            .await?;
+        let response: TableResponse = serde_json::from_str(&body)
+            .map_err(|_| ServerFnError::new("Error parsing response from OSRM"))?;
+        if response.code != "Ok" {
+            return Err(ServerFnError::new(format!(
+                "OSRM error: {}",
+                response.message.unwrap_or(response.code)
+            )));
+        }
+        let mut matrix = vec![vec![DistanceDuration::default(); locations.len()]; locations.len()];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let distance = response
+                    .distances
+                    .get(i)
+                    .and_then(|row| row.get(j).copied().flatten());
+                let duration = response
+                    .durations
+                    .get(i)
+                    .and_then(|row| row.get(j).copied().flatten());
+                match (distance, duration) {
+                    (Some(distance), Some(duration)) => {
+                        *cell = DistanceDuration {
+                            distance_miles: distance / METERS_PER_MILE,
+                            duration_seconds: duration.round() as i64,
+                        }
+                    }
+                    _ if i == j => {}
+                    _ => {
+                        return Err(ServerFnError::new(format!(
+                            "No route from {} to {}",
+                            locations[i], locations[j]
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(matrix)
+    }
+
+    async fn route(&self, stops: &[String]) -> Result<Route, ServerFnError> {
+        check_stops(stops)?;
+        let body = self
+            .fetch(
+                "route",
+                stops,
+                &[("overview", "full"), ("geometries", "polyline")],
+            )
+            .await?;
+        let response: RouteResponse = serde_json::from_str(&body)
+            .map_err(|_| ServerFnError::new("Error parsing response from OSRM"))?;
+        if response.code != "Ok" {
+            return Err(ServerFnError::new(format!(
+                "OSRM error: {}",
+                response.message.unwrap_or(response.code)
+            )));
+        }
+        let route = response
+            .routes
+            .into_iter()
+            .next()
+            .ok_or(ServerFnError::new("No route between the trip stops"))?;
+
+        Ok(Route {
+            legs: route
+                .legs
+                .iter()
+                .map(|leg| DistanceDuration {
+                    distance_miles: leg.distance / METERS_PER_MILE,
+                    duration_seconds: leg.duration.round() as i64,
+                })
+                .collect(),
+            polyline: decode_polyline(&route.geometry).unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use crate::server::routing::geometry::LatLng;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const METERS_PER_MILE: f64 = 1609.34;
+
+// An autocomplete suggestion.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Place {
+    pub description: String,
+    #[serde(rename = "placeId")]
+    pub place_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct DistanceDuration {
+    #[serde(rename = "distanceMiles")]
+    pub distance_miles: f64,
+    #[serde(rename = "durationSeconds")]
+    pub duration_seconds: i64,
+}
+
+// Travel between every pair of a list of places; `matrix[i][j]` is from `i` to `j`.
+pub type Matrix = Vec<Vec<DistanceDuration>>;
+
+// A driving route through an ordered list of stops.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Route {
+    pub legs: Vec<DistanceDuration>, // One per pair of consecutive stops
+    pub polyline: Vec<LatLng>,
+}
+
+impl Route {
+    pub fn total(&self) -> DistanceDuration {
+        DistanceDuration {
+            distance_miles: self.legs.iter().map(|leg| leg.distance_miles).sum(),
+            duration_seconds: self.legs.iter().map(|leg| leg.duration_seconds).sum(),
+        }
+    }
+}
+
+/// Place search and driving directions. Locations are free-form addresses as
+/// typed by the driver.
+pub trait RoutingProvider {
+    async fn autocomplete(&self, input: &str) -> Result<Vec<Place>, ServerFnError>;
+
+    async fn geocode(&self, address: &str) -> Result<LatLng, ServerFnError>;
+
+    /// A route visiting `stops` in order, from the first to the last.
+    async fn route(&self, stops: &[String]) -> Result<Route, ServerFnError>;
+
+    async fn distance_duration(
+        &self,
+        origin: &str,
+        destination: &str,
+    ) -> Result<DistanceDuration, ServerFnError> {
+        Ok(self
+            .route(&[origin.to_string(), destination.to_string()])
+            .await?
+            .total())
+    }
+
+    /// Travel between every pair of `locations`; `matrix[i][j]` is from `i` to `j`.
+    async fn matrix(&self, locations: &[String]) -> Result<Matrix, ServerFnError> {
+        let mut matrix = vec![vec![DistanceDuration::default(); locations.len()]; locations.len()];
+        for (i, origin) in locations.iter().enumerate() {
+            for (j, destination) in locations.iter().enumerate() {
+                if i != j {
+                    matrix[i][j] = self.distance_duration(origin, destination).await?;
+                }
+            }
+        }
+        Ok(matrix)
+    }
+}
+
+pub fn check_stops(stops: &[String]) -> Result<(), ServerFnError> {
+    if stops.len() < 2 {
+        return Err(ServerFnError::new("A route needs at least two stops"));
+    }
+    if stops.iter().any(|stop| stop.trim().is_empty()) {
+        return Err(ServerFnError::new("Every route stop needs a location"));
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlacesAutocompleteRequest {
+    pub token: String,
+    pub input: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetRouteRequest {
+    pub token: String,
+    pub origin: String,
+    pub destination: String,
+    pub waypoints: Vec<String>, // Visited in order between origin and destination
+}
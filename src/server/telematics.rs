@@ -0,0 +1,5 @@
+pub(crate) mod controller;
+pub(crate) mod model;
+pub(crate) mod motion;
+pub(crate) mod request;
+pub(crate) mod response;
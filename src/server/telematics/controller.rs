@@ -0,0 +1,806 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use bson::doc;
+use dioxus::prelude::*;
+
+use crate::server::auth::controller::auth;
+use crate::server::auth::model::User;
+use crate::server::common::response::{ErrorResponse, SuccessResponse};
+use crate::server::hos::model::{DutyEvent, DutyEventOrigin, DutyState};
+use crate::server::hos::stream::{status_at, DutyChange};
+use crate::server::telematics::model::*;
+use crate::server::telematics::motion::{
+    detect, Drive, MotionChange, MotionEvent, PROMPT_MINUTES, STATIONARY_MINUTES,
+};
+use crate::server::telematics::request::{
+    AnswerDutyPromptRequest, DeviceSessionRequest, GetDevicesRequest, GetDutyPromptRequest,
+    IngestTelematicsRequest, IssueDeviceTokenRequest, RegisterDeviceRequest,
+    StartDeviceSessionRequest,
+};
+use crate::server::telematics::response::{DeviceTokenResponse, IngestTelematicsResponse};
+
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use chrono::Duration;
+use futures_util::TryStreamExt;
+use std::env;
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::server::hos::controller::{append_duty_events, load_duty_events},
+    crate::server::trip::controller::uncertify_days,
+    crate::server::unidentified::controller::record_unidentified_driving,
+    axum::extract::{Json, Path},
+    axum::http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation},
+};
+
+pub const DEVICE_TOKEN_DAYS: i64 = 365;
+pub const MAX_BATCH_READINGS: usize = 1000;
+pub const MAX_DEVICE_ID_CHARS: usize = 64;
+
+// Devices buffer readings while out of coverage, but never report from the future.
+pub const MAX_CLOCK_SKEW_MINUTES: i64 = 5;
+
+#[cfg(feature = "server")]
+fn device_secret() -> String {
+    format!(
+        "{}:device",
+        env::var("JWT_SECRET").expect("JWT_SECRET must be set")
+    )
+}
+
+// Device IDs end up in the ingest URL, so they are kept to letters, digits, `-` and `_`.
+pub fn valid_device_id(device_id: &str) -> bool {
+    !device_id.is_empty()
+        && device_id.len() <= MAX_DEVICE_ID_CHARS
+        && device_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(feature = "server")]
+pub async fn registered_device(device_id: &str) -> Result<Option<EldDevice>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<EldDevice>("eld_devices");
+
+    Ok(collection.find_one(doc! { "_id": device_id }).await?)
+}
+
+// IDs of the devices a carrier registered.
+#[cfg(feature = "server")]
+pub async fn carrier_device_ids(carrier_id: ObjectId) -> Result<Vec<String>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<EldDevice>("eld_devices");
+
+    let devices: Vec<EldDevice> = collection
+        .find(doc! { "carrierId": carrier_id })
+        .await?
+        .try_collect()
+        .await?;
+    Ok(devices.into_iter().map(|device| device.id).collect())
+}
+
+// Sessions that were open at some point between `from` and `to`, on one device or
+// of one driver.
+#[cfg(feature = "server")]
+async fn sessions_between(
+    mut filter: bson::Document,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<DeviceSession>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DeviceSession>("device_sessions");
+
+    filter.insert(
+        "startedAt",
+        doc! { "$lte": bson::DateTime::from_chrono(to) },
+    );
+    filter.insert(
+        "$or",
+        vec![
+            doc! { "endedAt": null },
+            doc! { "endedAt": { "$gt": bson::DateTime::from_chrono(from) } },
+        ],
+    );
+    Ok(collection
+        .find(filter)
+        .sort(doc! { "startedAt": 1 })
+        .await?
+        .try_collect()
+        .await?)
+}
+
+/// Readings of the devices the driver was logged in to between `from` and `to`,
+/// one list per session in time order. Odometers only compare within a session.
+#[cfg(feature = "server")]
+pub async fn driver_readings(
+    driver_id: ObjectId,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Vec<TelematicsRecord>>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<TelematicsRecord>("telematics");
+
+    let mut readings = Vec::new();
+    for session in sessions_between(doc! { "driverId": driver_id }, from, to).await? {
+        let start = session.started_at.max(from);
+        let end = session.ended_at.map_or(to, |ended_at| ended_at.min(to));
+        let found: Vec<TelematicsRecord> = collection
+            .find(doc! {
+                "eldDeviceId": &session.eld_device_id,
+                "at": {
+                    "$gte": bson::DateTime::from_chrono(start),
+                    "$lt": bson::DateTime::from_chrono(end),
+                },
+            })
+            .sort(doc! { "at": 1 })
+            .await?
+            .try_collect()
+            .await?;
+        readings.push(found);
+    }
+    Ok(readings)
+}
+
+// Device of the driver's latest session, which identifies the ELD on their reports.
+#[cfg(feature = "server")]
+pub async fn driver_device(driver_id: ObjectId) -> Result<Option<String>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DeviceSession>("device_sessions");
+
+    Ok(collection
+        .find_one(doc! { "driverId": driver_id })
+        .sort(doc! { "startedAt": -1 })
+        .await?
+        .map(|session| session.eld_device_id))
+}
+
+/// A carrier user registers one of the carrier's ELD devices. A device ID can
+/// only be registered once, by one carrier.
+#[server]
+pub async fn register_device(
+    req: RegisterDeviceRequest,
+) -> Result<SuccessResponse<EldDevice>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let carrier_id = user.managed_carrier().ok_or(ServerFnError::new(
+        "Only carrier users can register ELD devices",
+    ))?;
+
+    let device_id = req.device_id.trim().to_string();
+    if !valid_device_id(&device_id) {
+        return Err(ServerFnError::new(
+            "Device IDs are letters, digits, '-' and '_'",
+        ));
+    }
+    if registered_device(&device_id).await?.is_some() {
+        return Err(ServerFnError::new("This device is already registered"));
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<EldDevice>("eld_devices");
+
+    let now = Utc::now();
+    let device = EldDevice {
+        id: device_id,
+        carrier_id,
+        truck_number: req.truck_number.trim().to_string(),
+        registered_by: user.id,
+        created_at: now,
+        updated_at: now,
+    };
+    collection.insert_one(device.clone()).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: device,
+    })
+}
+
+/// ELD devices of the user's carrier.
+#[server]
+pub async fn get_devices(
+    req: GetDevicesRequest,
+) -> Result<SuccessResponse<Vec<EldDevice>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let Some(carrier_id) = user.carrier_id else {
+        return Ok(SuccessResponse {
+            status: "success".into(),
+            data: Vec::new(),
+        });
+    };
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<EldDevice>("eld_devices");
+
+    let devices: Vec<EldDevice> = collection
+        .find(doc! { "carrierId": carrier_id })
+        .sort(doc! { "_id": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: devices,
+    })
+}
+
+/// Issues the token a registered ELD device signs its readings with. Only carrier
+/// users of the carrier that registered the device can issue it.
+#[server]
+pub async fn issue_device_token(
+    req: IssueDeviceTokenRequest,
+) -> Result<SuccessResponse<DeviceTokenResponse>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let carrier_id = user.managed_carrier().ok_or(ServerFnError::new(
+        "Only carrier users can issue device tokens",
+    ))?;
+
+    let device_id = registered_device(req.device_id.trim())
+        .await?
+        .filter(|device| device.carrier_id == carrier_id)
+        .ok_or(ServerFnError::new("Device not found"))?
+        .id;
+
+    let now = Utc::now();
+    let expires_at = now + Duration::days(DEVICE_TOKEN_DAYS);
+    let claims = DeviceClaims {
+        sub: device_id.clone(),
+        iat: now.timestamp() as usize,
+        exp: expires_at.timestamp() as usize,
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(device_secret().as_ref()),
+    )?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: DeviceTokenResponse {
+            device_id,
+            token,
+            expires_at,
+        },
+    })
+}
+
+/// Logs the driver in to one of their carrier's devices. It ends the driver's
+/// session on any other device and anyone else's session on this one.
+#[server]
+pub async fn start_device_session(
+    req: StartDeviceSessionRequest,
+) -> Result<SuccessResponse<DeviceSession>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let device = registered_device(req.device_id.trim())
+        .await?
+        .filter(|device| user.carrier_id == Some(device.carrier_id))
+        .ok_or(ServerFnError::new("Device not found"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DeviceSession>("device_sessions");
+
+    let now = Utc::now();
+    collection
+        .update_many(
+            doc! {
+                "$or": [{ "driverId": user.id }, { "eldDeviceId": &device.id }],
+                "endedAt": null,
+            },
+            doc! { "$set": { "endedAt": bson::DateTime::from_chrono(now) } },
+        )
+        .await?;
+    let session = DeviceSession {
+        id: ObjectId::new(),
+        eld_device_id: device.id,
+        driver_id: user.id,
+        started_at: now,
+        ended_at: None,
+    };
+    collection.insert_one(session.clone()).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: session,
+    })
+}
+
+/// Logs the driver out of their device.
+#[server]
+pub async fn end_device_session(
+    req: DeviceSessionRequest,
+) -> Result<SuccessResponse<()>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DeviceSession>("device_sessions");
+
+    collection
+        .update_many(
+            doc! { "driverId": user.id, "endedAt": null },
+            doc! { "$set": { "endedAt": bson::DateTime::from_chrono(Utc::now()) } },
+        )
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: (),
+    })
+}
+
+/// The device the driver is logged in to, if any.
+#[server]
+pub async fn get_device_session(
+    req: DeviceSessionRequest,
+) -> Result<SuccessResponse<Option<DeviceSession>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DeviceSession>("device_sessions");
+
+    let session = collection
+        .find_one(doc! { "driverId": user.id, "endedAt": null })
+        .sort(doc! { "startedAt": -1 })
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: session,
+    })
+}
+
+#[cfg(feature = "server")]
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+#[cfg(feature = "server")]
+fn api_error(status: StatusCode, message: impl Into<String>) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            status: "fail".into(),
+            message: message.into(),
+        }),
+    )
+}
+
+// Device ID of the bearer token, if it is valid.
+#[cfg(feature = "server")]
+fn token_device(headers: &HeaderMap) -> Option<String> {
+    let token = headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    decode::<DeviceClaims>(
+        token.trim(),
+        &DecodingKey::from_secret(device_secret().as_ref()),
+        &Validation::default(),
+    )
+    .ok()
+    .map(|data| data.claims.sub)
+}
+
+/// `POST /api/telematics/:device_id`: stores a batch of engine readings from an
+/// ELD device and turns the vehicle's starts and stops into duty-status events.
+#[cfg(feature = "server")]
+pub async fn ingest_telematics(
+    Path(device_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<IngestTelematicsRequest>,
+) -> Result<Json<SuccessResponse<IngestTelematicsResponse>>, ApiError> {
+    if token_device(&headers).as_deref() != Some(device_id.as_str()) {
+        return Err(api_error(StatusCode::UNAUTHORIZED, "Invalid device token"));
+    }
+    if req.readings.len() > MAX_BATCH_READINGS {
+        return Err(api_error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Send at most {} readings per batch", MAX_BATCH_READINGS),
+        ));
+    }
+    let latest = Utc::now() + Duration::minutes(MAX_CLOCK_SKEW_MINUTES);
+    if let Some(reading) = req
+        .readings
+        .iter()
+        .find(|reading| reading.at > latest || !reading.speed.is_finite() || reading.speed < 0.0)
+    {
+        return Err(api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Invalid reading at {}", reading.at.to_rfc3339()),
+        ));
+    }
+
+    let device = registered_device(&device_id)
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or(api_error(StatusCode::UNAUTHORIZED, "Unknown device"))?;
+
+    let data = store_readings(&device, &req.readings)
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(SuccessResponse {
+        status: "success".into(),
+        data,
+    }))
+}
+
+#[cfg(feature = "server")]
+async fn store_readings(
+    device: &EldDevice,
+    readings: &[EngineReading],
+) -> Result<IngestTelematicsResponse, ServerFnError> {
+    let device_id = device.id.as_str();
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let reading_collection = db.collection::<TelematicsRecord>("telematics");
+    let state_collection = db.collection::<MotionState>("device_states");
+
+    let mut state = state_collection
+        .find_one(doc! { "_id": device_id })
+        .await?
+        .unwrap_or_else(|| MotionState::new(device_id));
+
+    let now = Utc::now();
+    let records: Vec<TelematicsRecord> = readings
+        .iter()
+        .filter(|reading| state.last_at.is_none_or(|last_at| reading.at > last_at))
+        .map(|reading| TelematicsRecord {
+            id: ObjectId::new(),
+            eld_device_id: device_id.to_string(),
+            at: reading.at,
+            ignition: reading.ignition,
+            speed: reading.speed,
+            odometer: reading.odometer,
+            engine_hours: reading.engine_hours,
+            latitude: reading.latitude,
+            longitude: reading.longitude,
+            received_at: now,
+        })
+        .collect();
+    if records.is_empty() {
+        return Ok(IngestTelematicsResponse {
+            stored: 0,
+            transitions: 0,
+        });
+    }
+    reading_collection.insert_many(records.clone()).await?;
+
+    let motion = detect(&mut state, readings);
+    state_collection
+        .replace_one(doc! { "_id": device_id }, state)
+        .upsert(true)
+        .await?;
+
+    // Each change goes to the driver logged in when it happened; drives that start
+    // with nobody logged in are unidentified.
+    let times: Vec<DateTime<Utc>> = motion
+        .changes
+        .iter()
+        .map(|change| change.at)
+        .chain(motion.drives.iter().map(|drive| drive.start))
+        .collect();
+    if let (Some(&from), Some(&to)) = (times.iter().min(), times.iter().max()) {
+        let sessions = sessions_between(doc! { "eldDeviceId": device_id }, from, to).await?;
+        let mut by_driver: Vec<(ObjectId, Vec<MotionChange>)> = Vec::new();
+        for change in &motion.changes {
+            let Some(session) = session_at(&sessions, change.at) else {
+                continue;
+            };
+            match by_driver
+                .iter_mut()
+                .find(|(driver_id, _)| *driver_id == session.driver_id)
+            {
+                Some((_, changes)) => changes.push(change.clone()),
+                None => by_driver.push((session.driver_id, vec![change.clone()])),
+            }
+        }
+        for (driver_id, changes) in by_driver {
+            record_duty_changes(driver_id, device_id, &changes).await?;
+        }
+        let unidentified: Vec<Drive> = motion
+            .drives
+            .iter()
+            .filter(|drive| session_at(&sessions, drive.start).is_none())
+            .cloned()
+            .collect();
+        record_unidentified(device_id, &unidentified).await?;
+    }
+
+    Ok(IngestTelematicsResponse {
+        stored: records.len(),
+        transitions: motion.changes.len(),
+    })
+}
+
+// Motion puts the driver in Driving, unless they are moving the vehicle in the
+// yard or for personal conveyance. A stop that lasts asks them whether they are
+// still driving.
+#[cfg(feature = "server")]
+async fn record_duty_changes(
+    driver_id: ObjectId,
+    device_id: &str,
+    changes: &[MotionChange],
+) -> Result<(), ServerFnError> {
+    let Some(first) = changes.first() else {
+        return Ok(());
+    };
+
+    let mut events = load_duty_events(driver_id, first.at).await?;
+    let mut changed_from = None;
+    for change in changes {
+        let status = status_at(&events, change.at);
+        let appended = match change.event {
+            MotionEvent::Started => match status {
+                DutyState::Driving | DutyState::YardMove | DutyState::PersonalConveyance => {
+                    continue
+                }
+                _ => {
+                    append_duty_events(
+                        driver_id,
+                        None,
+                        &[DutyChange {
+                            status: DutyState::Driving,
+                            at: change.at,
+                            location: change.location.clone(),
+                            note: String::new(),
+                            odometer: change.odometer,
+                            source: None,
+                        }],
+                        DutyEventOrigin::Auto,
+                    )
+                    .await?
+                }
+            },
+            MotionEvent::Stopped if status == DutyState::Driving => {
+                open_prompt(driver_id, device_id, change).await?;
+                continue;
+            }
+            MotionEvent::Stopped => continue,
+            MotionEvent::TimedOut => match open_prompt_of(driver_id, device_id).await? {
+                Some(prompt) => expire_prompt(&prompt).await?,
+                None => continue,
+            },
+        };
+        changed_from.get_or_insert(change.at);
+        events.extend(appended);
+    }
+
+    if let Some(from) = changed_from {
+        uncertify_days(driver_id, from, Utc::now()).await?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+async fn open_prompt(
+    driver_id: ObjectId,
+    device_id: &str,
+    change: &MotionChange,
+) -> Result<DutyPrompt, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyPrompt>("duty_prompts");
+
+    let now = Utc::now();
+    let prompt = DutyPrompt {
+        id: ObjectId::new(),
+        driver_id,
+        eld_device_id: device_id.to_string(),
+        stopped_at: change.at,
+        respond_by: change.at + Duration::minutes(STATIONARY_MINUTES + PROMPT_MINUTES),
+        location: change.location.clone(),
+        odometer: change.odometer,
+        status: DutyPromptStatus::Open,
+        answer: None,
+        created_at: now,
+        updated_at: now,
+    };
+    collection.insert_one(prompt.clone()).await?;
+    Ok(prompt)
+}
+
+#[cfg(feature = "server")]
+async fn open_prompt_of(
+    driver_id: ObjectId,
+    device_id: &str,
+) -> Result<Option<DutyPrompt>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyPrompt>("duty_prompts");
+
+    Ok(collection
+        .find_one(doc! { "driverId": driver_id, "eldDeviceId": device_id, "status": "Open" })
+        .sort(doc! { "stoppedAt": -1 })
+        .await?)
+}
+
+// Closes an unanswered prompt and switches a driver who is still driving to on
+// duty. Whichever of the device and the driver's dashboard gets here first does
+// it; returns the events appended.
+#[cfg(feature = "server")]
+async fn expire_prompt(prompt: &DutyPrompt) -> Result<Vec<DutyEvent>, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyPrompt>("duty_prompts");
+
+    let expired = collection
+        .update_one(
+            doc! { "_id": prompt.id, "status": "Open" },
+            doc! { "$set": {
+                "status": "Expired",
+                "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+            }},
+        )
+        .await?;
+    if expired.modified_count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let events = load_duty_events(prompt.driver_id, prompt.respond_by).await?;
+    if status_at(&events, prompt.respond_by) != DutyState::Driving {
+        return Ok(Vec::new());
+    }
+    append_duty_events(
+        prompt.driver_id,
+        None,
+        &[DutyChange {
+            status: DutyState::OnDuty,
+            at: prompt.respond_by,
+            location: prompt.location.clone(),
+            note: "No answer to the stationary vehicle prompt".to_string(),
+            odometer: prompt.odometer,
+            source: None,
+        }],
+        DutyEventOrigin::Auto,
+    )
+    .await
+}
+
+/// The open prompt of a driver whose vehicle has stopped, if any. A prompt past
+/// its deadline is expired here, in case the device went quiet.
+#[server]
+pub async fn get_duty_prompt(
+    req: GetDutyPromptRequest,
+) -> Result<SuccessResponse<Option<DutyPrompt>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyPrompt>("duty_prompts");
+
+    let prompts: Vec<DutyPrompt> = collection
+        .find(doc! { "driverId": user.id, "status": "Open" })
+        .sort(doc! { "stoppedAt": -1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut open = None;
+    for prompt in prompts {
+        if prompt.respond_by <= Utc::now() {
+            if !expire_prompt(&prompt).await?.is_empty() {
+                uncertify_days(user.id, prompt.respond_by, Utc::now()).await?;
+            }
+        } else if open.is_none() {
+            open = Some(prompt);
+        }
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: open,
+    })
+}
+
+/// Answers a stationary vehicle prompt. Driving keeps the driver in Driving; any
+/// other status applies from the moment the vehicle stopped.
+#[server]
+pub async fn answer_duty_prompt(
+    req: AnswerDutyPromptRequest,
+) -> Result<SuccessResponse<DutyPrompt>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DutyPrompt>("duty_prompts");
+
+    let prompt_id = ObjectId::parse_str(&req.prompt_id)?;
+    let mut prompt = collection
+        .find_one(doc! { "_id": prompt_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Prompt not found"))?;
+    if prompt.status != DutyPromptStatus::Open || prompt.respond_by <= Utc::now() {
+        return Err(ServerFnError::new(
+            "The prompt has expired; edit your log to change the status",
+        ));
+    }
+
+    let answered = collection
+        .update_one(
+            doc! { "_id": prompt.id, "status": "Open" },
+            doc! { "$set": {
+                "status": "Answered",
+                "answer": bson::to_bson(&req.status)?,
+                "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+            }},
+        )
+        .await?;
+    if answered.modified_count == 0 {
+        return Err(ServerFnError::new(
+            "The prompt has expired; edit your log to change the status",
+        ));
+    }
+    prompt.status = DutyPromptStatus::Answered;
+    prompt.answer = Some(req.status);
+    prompt.updated_at = Utc::now();
+
+    if req.status != DutyState::Driving {
+        append_duty_events(
+            user.id,
+            None,
+            &[DutyChange {
+                status: req.status,
+                at: prompt.stopped_at,
+                location: prompt.location.clone(),
+                note: String::new(),
+                odometer: prompt.odometer,
+                source: None,
+            }],
+            DutyEventOrigin::Driver,
+        )
+        .await?;
+        uncertify_days(user.id, prompt.stopped_at, Utc::now()).await?;
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: prompt,
+    })
+}
+
+// Completed drives nobody was logged in for are filed as unidentified driving for
+// the carrier to assign.
+#[cfg(feature = "server")]
+async fn record_unidentified(device_id: &str, drives: &[Drive]) -> Result<(), ServerFnError> {
+    for drive in drives {
+        record_unidentified_driving(
+            device_id,
+            drive.start,
+            drive.end,
+            &drive.start_location,
+            &drive.end_location,
+            drive.start_odometer,
+            drive.end_odometer,
+        )
+        .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+#![allow(non_snake_case)]
+
+use crate::server::hos::model::DutyState;
+use bson::{
+    oid::ObjectId,
+    serde_helpers::{chrono_datetime_as_bson_datetime, chrono_datetime_as_bson_datetime_optional},
+};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// One reading of a device's engine bus and GPS, as the device sends it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct EngineReading {
+    pub at: DateTime<Utc>,
+    pub ignition: bool,
+    pub speed: f64, // Miles per hour
+    pub odometer: Option<f64>,
+    #[serde(rename = "engineHours")]
+    pub engine_hours: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TelematicsRecord {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "eldDeviceId")]
+    pub eld_device_id: String,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub at: DateTime<Utc>,
+    pub ignition: bool,
+    pub speed: f64,
+    pub odometer: Option<f64>,
+    #[serde(rename = "engineHours")]
+    pub engine_hours: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "receivedAt")]
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StopStage {
+    Stationary, // Stopped for less than five minutes
+    Prompted,   // The driver has been asked whether they are still driving
+    #[default]
+    Settled, // The prompt window has passed
+}
+
+// Where a device's vehicle was left by the readings processed so far.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct MotionState {
+    #[serde(rename = "_id")]
+    pub eld_device_id: String,
+    pub moving: bool,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub since: DateTime<Utc>, // Start of the current motion or stop
+    #[serde(rename = "stopStage", default)]
+    pub stop_stage: StopStage,
+    #[serde(rename = "driveStart", default)]
+    pub drive_start: Option<DateTime<Utc>>, // Drive not yet ended by a long stop
+    #[serde(rename = "driveLocation", default)]
+    pub drive_location: String,
+    #[serde(rename = "driveOdometer", default)]
+    pub drive_odometer: Option<f64>,
+    pub location: String, // Last known position
+    pub odometer: Option<f64>,
+    #[serde(rename = "lastAt", default)]
+    pub last_at: Option<DateTime<Utc>>, // Time of the last reading processed
+}
+
+impl MotionState {
+    pub fn new(eld_device_id: &str) -> Self {
+        Self {
+            eld_device_id: eld_device_id.to_string(),
+            moving: false,
+            since: DateTime::<Utc>::UNIX_EPOCH,
+            stop_stage: StopStage::Settled,
+            drive_start: None,
+            drive_location: String::new(),
+            drive_odometer: None,
+            location: String::new(),
+            odometer: None,
+            last_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum DutyPromptStatus {
+    Open,
+    Answered, // The driver picked a status, or confirmed they are still driving
+    Expired,  // No answer in time; the driver was switched to on duty
+}
+
+// Asks a driver whose vehicle has stopped whether they are still driving.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DutyPrompt {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "driverId")]
+    pub driver_id: ObjectId,
+    #[serde(rename = "eldDeviceId")]
+    pub eld_device_id: String,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "stoppedAt")]
+    pub stopped_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "respondBy")]
+    pub respond_by: DateTime<Utc>,
+    pub location: String,
+    pub odometer: Option<f64>,
+    pub status: DutyPromptStatus,
+    pub answer: Option<DutyState>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// An ELD device a carrier registered. The device ID is the `_id`, so a device
+// belongs to a single carrier and only its carrier users can issue its token.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct EldDevice {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "carrierId")]
+    pub carrier_id: ObjectId,
+    #[serde(rename = "truckNumber", default)]
+    pub truck_number: String,
+    #[serde(rename = "registeredBy")]
+    pub registered_by: ObjectId,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// A driver logged in to an ELD device. What the device records while the session
+// is open goes to the driver's log; driving outside every session is unidentified.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DeviceSession {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "eldDeviceId")]
+    pub eld_device_id: String,
+    #[serde(rename = "driverId")]
+    pub driver_id: ObjectId,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "startedAt")]
+    pub started_at: DateTime<Utc>,
+    #[serde(
+        with = "chrono_datetime_as_bson_datetime_optional",
+        rename = "endedAt",
+        default
+    )]
+    pub ended_at: Option<DateTime<Utc>>, // None while the driver is logged in
+}
+
+impl DeviceSession {
+    pub fn covers(&self, at: DateTime<Utc>) -> bool {
+        self.started_at <= at && self.ended_at.is_none_or(|ended_at| at < ended_at)
+    }
+}
+
+// The session open on the device at `at`; the latest one if several overlap.
+pub fn session_at(sessions: &[DeviceSession], at: DateTime<Utc>) -> Option<&DeviceSession> {
+    sessions
+        .iter()
+        .filter(|session| session.covers(at))
+        .max_by_key(|session| session.started_at)
+}
+
+// Claims of a device token. They are signed with a key of their own, so a device
+// can only post readings and never act as the driver who set it up.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeviceClaims {
+    pub sub: String, // ELD device ID
+    pub iat: usize,
+    pub exp: usize,
+}
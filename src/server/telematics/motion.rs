@@ -0,0 +1,246 @@
+use crate::server::telematics::model::{EngineReading, MotionState, StopStage};
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+// The vehicle is in motion above this speed (49 CFR 395, Appendix A, 4.3.1.2).
+pub const DRIVING_SPEED_MPH: f64 = 5.0;
+// Out of motion this long, the driver is asked whether they are still driving,
+pub const STATIONARY_MINUTES: i64 = 5;
+// and switched to on duty if they do not answer within this.
+pub const PROMPT_MINUTES: i64 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MotionEvent {
+    Started,  // The vehicle went above the driving speed
+    Stopped,  // It has been out of motion for five minutes since `at`
+    TimedOut, // The prompt that followed went unanswered
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MotionChange {
+    pub event: MotionEvent,
+    pub at: DateTime<Utc>,
+    pub location: String,
+    pub odometer: Option<f64>,
+}
+
+// From the first motion after a long stop to the next long stop.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Drive {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub start_location: String,
+    pub end_location: String,
+    pub start_odometer: Option<f64>,
+    pub end_odometer: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Motion {
+    pub changes: Vec<MotionChange>,
+    pub drives: Vec<Drive>, // Completed in this batch
+}
+
+/// Coordinates of a reading as "latitude, longitude", or empty without a GPS fix.
+pub fn location(reading: &EngineReading) -> String {
+    match (reading.latitude, reading.longitude) {
+        (Some(latitude), Some(longitude)) => format!("{:.4}, {:.4}", latitude, longitude),
+        _ => String::new(),
+    }
+}
+
+pub fn is_moving(reading: &EngineReading) -> bool {
+    reading.ignition && reading.speed > DRIVING_SPEED_MPH
+}
+
+/// Replays readings in time order over a device's motion state. Every start of
+/// motion is reported; a stop only once it has lasted `STATIONARY_MINUTES`, and
+/// again when the prompt window that follows has run out. Readings at or before
+/// the last one processed are skipped, so a batch that is sent twice changes
+/// nothing.
+pub fn detect(state: &mut MotionState, readings: &[EngineReading]) -> Motion {
+    let mut readings: Vec<&EngineReading> = readings
+        .iter()
+        .filter(|reading| state.last_at.is_none_or(|last_at| reading.at > last_at))
+        .collect();
+    readings.sort_by_key(|reading| reading.at);
+
+    let mut motion = Motion::default();
+    for reading in readings {
+        let position = location(reading);
+        if !position.is_empty() {
+            state.location = position;
+        }
+        state.odometer = reading.odometer.or(state.odometer);
+
+        let moving = is_moving(reading);
+        if moving != state.moving {
+            state.moving = moving;
+            state.since = reading.at;
+            if moving {
+                motion.changes.push(MotionChange {
+                    event: MotionEvent::Started,
+                    at: reading.at,
+                    location: state.location.clone(),
+                    odometer: state.odometer,
+                });
+                if state.drive_start.is_none() {
+                    state.drive_start = Some(reading.at);
+                    state.drive_location = state.location.clone();
+                    state.drive_odometer = state.odometer;
+                }
+            } else {
+                state.stop_stage = StopStage::Stationary;
+            }
+        }
+
+        // The vehicle does not move while stopped, so the current position is
+        // where it stopped.
+        let stationary = reading.at - state.since;
+        if !state.moving
+            && state.stop_stage == StopStage::Stationary
+            && stationary >= Duration::minutes(STATIONARY_MINUTES)
+        {
+            motion.changes.push(MotionChange {
+                event: MotionEvent::Stopped,
+                at: state.since,
+                location: state.location.clone(),
+                odometer: state.odometer,
+            });
+            if let Some(start) = state.drive_start.take() {
+                motion.drives.push(Drive {
+                    start,
+                    end: state.since,
+                    start_location: state.drive_location.clone(),
+                    end_location: state.location.clone(),
+                    start_odometer: state.drive_odometer,
+                    end_odometer: state.odometer,
+                });
+            }
+            state.stop_stage = StopStage::Prompted;
+        }
+        if !state.moving
+            && state.stop_stage == StopStage::Prompted
+            && stationary >= Duration::minutes(STATIONARY_MINUTES + PROMPT_MINUTES)
+        {
+            motion.changes.push(MotionChange {
+                event: MotionEvent::TimedOut,
+                at: state.since + Duration::minutes(STATIONARY_MINUTES + PROMPT_MINUTES),
+                location: state.location.clone(),
+                odometer: state.odometer,
+            });
+            state.stop_stage = StopStage::Settled;
+        }
+        state.last_at = Some(reading.at);
+    }
+    motion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(minute: i64, speed: f64, odometer: f64) -> EngineReading {
+        EngineReading {
+            at: Utc.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap() + Duration::minutes(minute),
+            ignition: true,
+            speed,
+            odometer: Some(odometer),
+            engine_hours: None,
+            latitude: None,
+            longitude: None,
+        }
+    }
+
+    // Idles, pulls away at 8:02, drives to 8:20 and then stands until 8:30.
+    fn trace() -> Vec<EngineReading> {
+        let mut readings = vec![reading(0, 0.0, 1000.0), reading(1, 5.0, 1000.0)];
+        readings.extend((2..20).map(|minute| reading(minute, 50.0, 1000.0 + (minute - 1) as f64)));
+        readings.extend((20..=30).map(|minute| reading(minute, 0.0, 1019.0)));
+        readings
+    }
+
+    fn events(motion: &Motion) -> Vec<(MotionEvent, i64)> {
+        let start = reading(0, 0.0, 0.0).at;
+        motion
+            .changes
+            .iter()
+            .map(|change| (change.event, (change.at - start).num_minutes()))
+            .collect()
+    }
+
+    #[test]
+    fn starts_above_five_mph() {
+        let mut state = MotionState::new("EF0001");
+        let motion = detect(&mut state, &trace()[..3]);
+        assert_eq!(events(&motion), [(MotionEvent::Started, 2)]);
+        assert_eq!(motion.changes[0].odometer, Some(1001.0));
+    }
+
+    #[test]
+    fn stops_after_five_stationary_minutes() {
+        let mut state = MotionState::new("EF0001");
+        let motion = detect(&mut state, &trace()[..25]);
+        assert_eq!(events(&motion), [(MotionEvent::Started, 2)]);
+
+        // The reading at 8:25 completes five minutes; the stop dates from 8:20.
+        let motion = detect(&mut state, &trace()[25..26]);
+        assert_eq!(events(&motion), [(MotionEvent::Stopped, 20)]);
+    }
+
+    #[test]
+    fn prompt_times_out_a_minute_later() {
+        let mut state = MotionState::new("EF0001");
+        let motion = detect(&mut state, &trace());
+        assert_eq!(
+            events(&motion),
+            [
+                (MotionEvent::Started, 2),
+                (MotionEvent::Stopped, 20),
+                (MotionEvent::TimedOut, 26),
+            ]
+        );
+        assert_eq!(state.stop_stage, StopStage::Settled);
+    }
+
+    #[test]
+    fn completed_drive_carries_its_odometer() {
+        let mut state = MotionState::new("EF0001");
+        let motion = detect(&mut state, &trace());
+        let start = reading(0, 0.0, 0.0).at;
+        assert_eq!(
+            motion.drives,
+            [Drive {
+                start: start + Duration::minutes(2),
+                end: start + Duration::minutes(20),
+                start_location: String::new(),
+                end_location: String::new(),
+                start_odometer: Some(1001.0),
+                end_odometer: Some(1019.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn short_stop_does_not_end_the_drive() {
+        let mut readings = trace()[..23].to_vec();
+        readings.extend((23..40).map(|minute| reading(minute, 50.0, 1000.0 + minute as f64)));
+        let mut state = MotionState::new("EF0001");
+        let motion = detect(&mut state, &readings);
+        assert_eq!(
+            events(&motion),
+            [(MotionEvent::Started, 2), (MotionEvent::Started, 23)]
+        );
+        assert!(motion.drives.is_empty());
+    }
+
+    #[test]
+    fn replayed_batch_changes_nothing() {
+        let mut state = MotionState::new("EF0001");
+        detect(&mut state, &trace());
+        let settled = state.clone();
+        assert_eq!(detect(&mut state, &trace()), Motion::default());
+        assert_eq!(state, settled);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::server::hos::model::DutyState;
+use crate::server::telematics::model::EngineReading;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegisterDeviceRequest {
+    pub token: String,
+    pub device_id: String,
+    pub truck_number: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetDevicesRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IssueDeviceTokenRequest {
+    pub token: String,
+    pub device_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StartDeviceSessionRequest {
+    pub token: String,
+    pub device_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeviceSessionRequest {
+    pub token: String,
+}
+
+// Body of `POST /api/telematics/:device_id`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IngestTelematicsRequest {
+    pub readings: Vec<EngineReading>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetDutyPromptRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnswerDutyPromptRequest {
+    pub token: String,
+    pub prompt_id: String,
+    pub status: DutyState, // Driving to confirm the driver is still driving
+}
@@ -0,0 +1,15 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeviceTokenResponse {
+    pub device_id: String,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IngestTelematicsResponse {
+    pub stored: usize,      // Readings newer than the last batch
+    pub transitions: usize, // Starts and stops of the vehicle they contained
+}
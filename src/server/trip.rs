@@ -1,4 +1,9 @@
+pub(crate) mod certification;
 pub(crate) mod controller;
+pub(crate) mod daily;
 pub(crate) mod model;
+pub(crate) mod optimizer;
+pub(crate) mod planner;
+pub(crate) mod printout;
 pub(crate) mod request;
 pub(crate) mod response;
@@ -0,0 +1,200 @@
+use crate::server::hos::model::{DutyEvent, DutyPeriod};
+use crate::server::hos::stream::periods_from_events;
+use crate::server::trip::daily::daily_sheets;
+use crate::server::trip::model::{DailyLog, LogEntry};
+use crate::server::trip::response::DailyLogSheet;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CertificationStatus {
+    Uncertified, // Never signed
+    Certified,
+    Recertify, // Signed, but the day changed since
+}
+
+impl CertificationStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CertificationStatus::Uncertified => "Uncertified",
+            CertificationStatus::Certified => "Certified",
+            CertificationStatus::Recertify => "Recertify",
+        }
+    }
+}
+
+/// The duty status the driver recorded on `log`'s day, as entries of `log`; empty
+/// when nothing was recorded. `events` must include the event in effect at midnight.
+pub fn recorded_entries(events: &[DutyEvent], log: &DailyLog, now: DateTime<Utc>) -> Vec<LogEntry> {
+    let day = log.log_date;
+    let next_day = day + Duration::days(1);
+    let periods: Vec<DutyPeriod> = periods_from_events(events, now)
+        .into_iter()
+        .filter(|period| period.end > day && period.start < next_day)
+        .map(|mut period| {
+            period.start = period.start.max(day);
+            period.end = period.end.min(next_day);
+            period
+        })
+        .collect();
+
+    daily_sheets(&periods)
+        .into_iter()
+        .find(|sheet| sheet.date == day)
+        .map(|sheet| {
+            sheet
+                .entries
+                .into_iter()
+                .map(|entry| LogEntry {
+                    id: ObjectId::new(),
+                    log_id: log.id,
+                    time: entry.time,
+                    status: entry.status,
+                    location: entry.location,
+                    remarks: entry.remarks,
+                    created_at: now,
+                    updated_at: now,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fingerprint of a day's entries: each entry's time, status, location and remarks
+/// in time order, so that reordering the stored documents does not change it.
+pub fn entries_hash(entries: &[LogEntry]) -> String {
+    let mut entries: Vec<&LogEntry> = entries.iter().collect();
+    entries.sort_by_key(|entry| entry.time);
+
+    let canonical: String = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                entry.time.timestamp(),
+                entry.status.label(),
+                entry.location,
+                entry.remarks.as_deref().unwrap_or_default()
+            )
+        })
+        .collect();
+    format!("{:x}", Sha256::digest(canonical.as_bytes()))
+}
+
+// A day stays certified only while its entries still match the certified hash.
+pub fn certification_status(sheet: &DailyLogSheet) -> CertificationStatus {
+    let log = &sheet.log;
+    if log.signature.as_deref().unwrap_or_default().is_empty() {
+        return CertificationStatus::Uncertified;
+    }
+    match (&log.certified_at, &log.entries_hash) {
+        (Some(_), Some(hash)) if *hash == entries_hash(&sheet.entries) => {
+            CertificationStatus::Certified
+        }
+        _ => CertificationStatus::Recertify,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::hos::model::{DutyEventOrigin, DutyEventType, DutyState};
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, 0, 0).unwrap()
+    }
+
+    fn event(hour: u32, status: DutyState) -> DutyEvent {
+        DutyEvent {
+            id: ObjectId::new(),
+            driver_id: ObjectId::new(),
+            trip_id: None,
+            sequence: hour as i64,
+            event_type: DutyEventType::DutyStatus,
+            origin: DutyEventOrigin::Driver,
+            status,
+            at: at(hour),
+            location: "Dallas, TX".to_string(),
+            note: String::new(),
+            odometer_reading: None,
+            active: true,
+            source_id: None,
+            created_at: at(hour),
+            updated_at: at(hour),
+        }
+    }
+
+    fn log() -> DailyLog {
+        DailyLog {
+            id: ObjectId::new(),
+            driver_id: ObjectId::new(),
+            trip_id: ObjectId::new(),
+            log_date: at(0),
+            signature: None,
+            certified_at: None,
+            entries_hash: None,
+            created_at: at(0),
+            updated_at: at(0),
+        }
+    }
+
+    fn stream() -> Vec<DutyEvent> {
+        vec![
+            event(0, DutyState::OffDuty),
+            event(8, DutyState::OnDuty),
+            event(9, DutyState::Driving),
+            event(17, DutyState::OffDuty),
+        ]
+    }
+
+    // The day's sheet, signed over the entries recorded from `events`.
+    fn signed(events: &[DutyEvent]) -> DailyLogSheet {
+        let mut log = log();
+        let entries = recorded_entries(events, &log, at(23));
+        log.signature = Some("data:image/svg+xml;base64,".to_string());
+        log.certified_at = Some(at(23));
+        log.entries_hash = Some(entries_hash(&entries));
+        DailyLogSheet { log, entries }
+    }
+
+    #[test]
+    fn unsigned_day_is_uncertified() {
+        let log = log();
+        let entries = recorded_entries(&stream(), &log, at(23));
+        assert_eq!(
+            certification_status(&DailyLogSheet { log, entries }),
+            CertificationStatus::Uncertified
+        );
+    }
+
+    #[test]
+    fn certified_day_needs_recertifying_after_an_edit() {
+        let mut sheet = signed(&stream());
+        assert_eq!(certification_status(&sheet), CertificationStatus::Certified);
+
+        // The driving that started at 9:00 is edited to start at 10:00.
+        let mut events = stream();
+        events[2].active = false;
+        events.push(event(10, DutyState::Driving));
+        sheet.entries = recorded_entries(&events, &sheet.log, at(23));
+        assert_eq!(certification_status(&sheet), CertificationStatus::Recertify);
+    }
+
+    #[test]
+    fn hash_ignores_the_stored_order() {
+        let sheet = signed(&stream());
+        let mut entries = sheet.entries.clone();
+        entries.reverse();
+        assert_eq!(entries_hash(&entries), entries_hash(&sheet.entries));
+        assert_eq!(
+            certification_status(&DailyLogSheet {
+                log: sheet.log,
+                entries
+            }),
+            CertificationStatus::Certified
+        );
+    }
+}
@@ -7,13 +7,26 @@ use dioxus_logger::tracing;
 
 use crate::server::auth::controller::auth;
 use crate::server::common::response::SuccessResponse;
+use crate::server::conversation::model::{Conversation, Message};
+use crate::server::hos::engine::{cycle_used, evaluate, normalize};
+use crate::server::hos::model::{DutyEvent, DutyEventOrigin, DutyState};
+use crate::server::hos::stream::{odometer_miles_between, record_range};
+use crate::server::ifta::jurisdiction::jurisdiction_code;
+use crate::server::trip::certification::{entries_hash, recorded_entries};
+use crate::server::trip::daily::{daily_sheets, itinerary_from_route, itinerary_periods};
 use crate::server::trip::model::*;
+use crate::server::trip::optimizer::{optimize_stops, MAX_TRIP_STOPS};
+use crate::server::trip::planner::{plan_itinerary, stop_legs};
+use crate::server::trip::printout::{
+    data_url, render_pdf, render_svg, row_totals, status_spans, SheetFormat, SheetHeader,
+};
 use crate::server::trip::request::*;
 use crate::server::trip::response::*;
 use std::env;
 
 use bson::oid::ObjectId;
 use chrono::prelude::*;
+use chrono::Duration;
 use futures_util::StreamExt;
 use futures_util::TryStreamExt;
 use regex::Regex;
@@ -21,8 +34,14 @@ use regex::Regex;
 use {
     crate::ai::get_ai,
     crate::db::get_client,
+    crate::server::hos::controller::{
+        append_duty_events, category_warnings_for, cycle_rule_for, deactivate_duty_events,
+        load_duty_events, load_duty_history, record_restarts, HISTORY_DAYS,
+    },
+    crate::server::routing::client::{cached_matrix, cached_route},
     crate::unsplash::get_unsplash_client,
     http_api_isahc_client::{Client as _, IsahcClient},
+    mongodb::options::ReturnDocument,
     rand::thread_rng,
     rand::Rng,
     unsplash_api::endpoints::common::EndpointRet,
@@ -32,53 +51,8 @@ use {
     unsplash_api::objects::rate_limiting::RateLimiting,
 };
 
-#[cfg(feature = "server")]
-use reqwest::Client as ReqClient;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
-pub struct GooglePlacesResponse {
-    pub predictions: Vec<Prediction>,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct GoogleDurationResponse {
-    pub distance: f64,
-    pub duration: i64,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct Prediction {
-    pub description: String,
-    pub place_id: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct DistanceMatrixResponse {
-    rows: Vec<Row>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Row {
-    elements: Vec<Element>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Element {
-    distance: Distance,
-    duration: DurationValue,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Distance {
-    value: f64, // Distance in meters
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct DurationValue {
-    value: i64, // Duration in seconds
-}
-
 #[server]
 pub async fn store_trip(
     req: StoreTripRequest,
@@ -89,7 +63,32 @@ pub async fn store_trip(
     let client = get_client().await;
     let db = client.database(&std::env::var("MONGODB_DB_NAME").unwrap());
     let trip_collection = db.collection::<Trip>("trips");
-    let distance_duration = fetch_distance_duration(req.route_url).await?;
+
+    if req.status != TripStatus::Pending {
+        return Err(ServerFnError::new("New trips start as Pending"));
+    }
+    if req.current_location.trim().is_empty()
+        || req.stops.iter().any(|stop| stop.location.trim().is_empty())
+    {
+        return Err(ServerFnError::new("Every trip location is required"));
+    }
+    check_trip_stops(&req.stops)?;
+    let mut places = vec![req.current_location.clone()];
+    places.extend(req.stops.iter().map(|stop| stop.location.clone()));
+    let route = cached_route(&user.id.to_hex(), &places).await?.total();
+
+    let now = Utc::now();
+    let periods = normalize(&load_duty_history(user.id, now - Duration::days(HISTORY_DAYS)).await?);
+    let cycle_rule = cycle_rule_for(&user).await?;
+    let used = cycle_used(&periods, cycle_rule, now);
+    let remaining = cycle_rule.limit() - used;
+    if remaining <= Duration::zero() {
+        return Err(ServerFnError::new(format!(
+            "No hours left in the {} cycle",
+            cycle_rule.label()
+        )));
+    }
+    let cycle_exceeded = Duration::seconds(route.duration_seconds) > remaining;
 
     let photo_url = fetch_cover(req.current_location.clone()).await?;
 
@@ -97,13 +96,16 @@ pub async fn store_trip(
         id: ObjectId::new(),
         driver_id: user.id,
         current_location: req.current_location,
-        pickup_location: req.pickup_location,
+        pickup_location: req.stops[0].location.clone(),
         picture: photo_url.unwrap_or_default(),
-        dropoff_location: req.dropoff_location,
-        cycle_used_hours: req.cycle_used_hours,
-        status: req.status,
-        distance_miles: Some(distance_duration.distance),
-        estimated_duration: Some(distance_duration.duration as u64),
+        dropoff_location: req.stops[req.stops.len() - 1].location.clone(),
+        stops: req.stops,
+        cycle_used_hours: used.num_minutes() as f64 / 60.0,
+        cycle_rule,
+        cycle_exceeded,
+        status: TripStatus::Pending,
+        distance_miles: Some(route.distance_miles),
+        estimated_duration: Some((route.duration_seconds / 60) as u64),
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -112,10 +114,47 @@ pub async fn store_trip(
 
     Ok(SuccessResponse {
         status: "success".into(),
-        data: TripResponse { id: new_trip.id },
+        data: TripResponse {
+            id: new_trip.id,
+            cycle_used_hours: new_trip.cycle_used_hours,
+            cycle_remaining_hours: remaining.num_minutes() as f64 / 60.0,
+            cycle_exceeded,
+        },
     })
 }
 
+// A trip starts with a pickup, ends with a drop-off, and has nothing but pickups
+// and drop-offs between.
+fn check_trip_stops(stops: &[TripStop]) -> Result<(), ServerFnError> {
+    if stops.len() < 2 || stops.len() > MAX_TRIP_STOPS {
+        return Err(ServerFnError::new(format!(
+            "A trip has between 2 and {} stops",
+            MAX_TRIP_STOPS
+        )));
+    }
+    if stops[0].stop_type != StopType::Pickup
+        || stops[stops.len() - 1].stop_type != StopType::Dropoff
+    {
+        return Err(ServerFnError::new(
+            "A trip starts with a pickup and ends with a drop-off",
+        ));
+    }
+    for stop in stops {
+        if !matches!(stop.stop_type, StopType::Pickup | StopType::Dropoff) {
+            return Err(ServerFnError::new("Trip stops are pickups or drop-offs"));
+        }
+        if let (Some(start), Some(end)) = (stop.window_start, stop.window_end) {
+            if end < start {
+                return Err(ServerFnError::new(format!(
+                    "The window at {} ends before it starts",
+                    stop.location
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[server]
 pub async fn fetch_cover(topic: String) -> Result<Option<String>, ServerFnError> {
     let client = get_unsplash_client().await.lock().await;
@@ -176,6 +215,185 @@ pub async fn get_trips_for_user(
     })
 }
 
+/// Moves the trip along its status lifecycle and/or updates where the driver is.
+#[server]
+pub async fn update_trip(req: UpdateTripRequest) -> Result<SuccessResponse<Trip>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
+
+    let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    let trip = trip_collection
+        .find_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Trip not found"))?;
+
+    let mut update = doc! { "updatedAt": bson::DateTime::from_chrono(Utc::now()) };
+    if let Some(next) = req.status.filter(|next| *next != trip.status) {
+        if !trip.status.can_become(next) {
+            return Err(ServerFnError::new(format!(
+                "A {} trip cannot become {}",
+                trip.status.label(),
+                next.label()
+            )));
+        }
+        update.insert("status", bson::to_bson(&next)?);
+    }
+    if let Some(location) = req.current_location {
+        let location = location.trim();
+        if location.is_empty() {
+            return Err(ServerFnError::new("The current location is empty"));
+        }
+        update.insert("currentLocation", location);
+    }
+
+    // The status is part of the filter so concurrent transitions cannot both apply.
+    let updated = trip_collection
+        .find_one_and_update(
+            doc! { "_id": trip_id, "driverId": user.id, "status": bson::to_bson(&trip.status)? },
+            doc! { "$set": update },
+        )
+        .return_document(ReturnDocument::After)
+        .await?
+        .ok_or(ServerFnError::new(
+            "The trip changed in the meantime; reload and try again",
+        ))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: updated,
+    })
+}
+
+/// Deletes a trip that is not under way, with its routes and stops, fueling stops,
+/// logs and conversations. Duty-status events are records of duty status and are
+/// kept; they are only detached from the trip.
+#[server]
+pub async fn delete_trip(req: DeleteTripRequest) -> Result<SuccessResponse<()>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
+    let route_collection = db.collection::<Route>("routes");
+    let daily_collection = db.collection::<DailyLog>("daily_logs");
+    let conversation_collection = db.collection::<Conversation>("conversations");
+
+    let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    let trip = trip_collection
+        .find_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Trip not found"))?;
+    if trip.status == TripStatus::Ongoing {
+        return Err(ServerFnError::new(
+            "Complete or cancel the trip before deleting it",
+        ));
+    }
+
+    // Children first, so a failure leaves the trip in place to delete again.
+    let route_ids: Vec<ObjectId> = route_collection
+        .find(doc! { "tripId": trip_id })
+        .await?
+        .try_collect::<Vec<Route>>()
+        .await?
+        .iter()
+        .map(|route| route.id)
+        .collect();
+    db.collection::<RouteStop>("route_stops")
+        .delete_many(doc! { "routeId": { "$in": route_ids } })
+        .await?;
+    route_collection
+        .delete_many(doc! { "tripId": trip_id })
+        .await?;
+
+    db.collection::<FuelingStop>("fueling_stops")
+        .delete_many(doc! { "tripId": trip_id })
+        .await?;
+    db.collection::<EldLog>("eld_logs")
+        .delete_many(doc! { "tripId": trip_id })
+        .await?;
+
+    let log_ids: Vec<ObjectId> = daily_collection
+        .find(doc! { "tripId": trip_id })
+        .await?
+        .try_collect::<Vec<DailyLog>>()
+        .await?
+        .iter()
+        .map(|log| log.id)
+        .collect();
+    db.collection::<LogEntry>("log_entries")
+        .delete_many(doc! { "logId": { "$in": log_ids } })
+        .await?;
+    daily_collection
+        .delete_many(doc! { "tripId": trip_id })
+        .await?;
+
+    let conversation_ids: Vec<ObjectId> = conversation_collection
+        .find(doc! { "trip": trip_id, "user": user.id })
+        .await?
+        .try_collect::<Vec<Conversation>>()
+        .await?
+        .iter()
+        .map(|conversation| conversation.id)
+        .collect();
+    db.collection::<Message>("messages")
+        .delete_many(doc! { "conversation": { "$in": conversation_ids } })
+        .await?;
+    conversation_collection
+        .delete_many(doc! { "trip": trip_id, "user": user.id })
+        .await?;
+
+    db.collection::<bson::Document>("duty_events")
+        .update_many(
+            doc! { "driverId": user.id, "tripId": trip_id },
+            doc! { "$set": { "tripId": null } },
+        )
+        .await?;
+
+    trip_collection
+        .delete_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: (),
+    })
+}
+
+// Checks a fuel purchase and returns the IFTA code of its jurisdiction.
+fn fuel_purchase_jurisdiction(
+    location: &str,
+    jurisdiction: &str,
+    fuel_amount: f64,
+    price_per_gallon: Option<f64>,
+    odometer: Option<f64>,
+) -> Result<&'static str, ServerFnError> {
+    if location.trim().is_empty() {
+        return Err(ServerFnError::new("The fueling location is empty"));
+    }
+    if !fuel_amount.is_finite() || fuel_amount <= 0.0 {
+        return Err(ServerFnError::new("The fuel amount must be positive"));
+    }
+    if price_per_gallon.is_some_and(|price| !price.is_finite() || price < 0.0) {
+        return Err(ServerFnError::new("The fuel price cannot be negative"));
+    }
+    if odometer.is_some_and(|odometer| !odometer.is_finite() || odometer < 0.0) {
+        return Err(ServerFnError::new(
+            "The odometer reading cannot be negative",
+        ));
+    }
+    jurisdiction_code(jurisdiction).ok_or(ServerFnError::new(format!(
+        "{:?} is not an IFTA jurisdiction",
+        jurisdiction
+    )))
+}
+
 #[server]
 pub async fn store_fueling_stop(
     req: StoreFuelingStopRequest,
@@ -186,14 +404,35 @@ pub async fn store_fueling_stop(
 
     let client = get_client().await;
     let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
     let collection = db.collection::<FuelingStop>("fueling_stops");
 
+    let jurisdiction = fuel_purchase_jurisdiction(
+        &req.location,
+        &req.jurisdiction,
+        req.fuel_amount,
+        req.price_per_gallon,
+        req.odometer,
+    )?;
     let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    trip_collection
+        .find_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Trip not found"))?;
+
     let new_fueling_stop = FuelingStop {
         id: ObjectId::new(),
         trip_id,
-        location: req.location,
+        location: req.location.trim().to_string(),
         fuel_amount: req.fuel_amount,
+        jurisdiction: jurisdiction.to_string(),
+        price_per_gallon: req.price_per_gallon,
+        odometer: req.odometer,
+        receipt_reference: req
+            .receipt_reference
+            .map(|reference| reference.trim().to_string())
+            .filter(|reference| !reference.is_empty()),
+        purchased_at: req.purchased_at.unwrap_or_else(Utc::now),
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -208,6 +447,106 @@ pub async fn store_fueling_stop(
     })
 }
 
+/// Fuel purchases of one of the driver's trips, or of all of them, newest first.
+#[server]
+pub async fn list_fueling_stops(
+    req: ListFuelingStopsRequest,
+) -> Result<SuccessResponse<Vec<FuelingStop>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
+    let collection = db.collection::<FuelingStop>("fueling_stops");
+
+    let mut filter = doc! { "driverId": user.id };
+    if let Some(trip_id) = req.trip_id {
+        filter.insert("_id", ObjectId::parse_str(&trip_id)?);
+    }
+    let trip_ids: Vec<ObjectId> = trip_collection
+        .find(filter)
+        .await?
+        .try_collect::<Vec<Trip>>()
+        .await?
+        .iter()
+        .map(|trip| trip.id)
+        .collect();
+
+    let stops = collection
+        .find(doc! { "tripId": { "$in": trip_ids } })
+        .sort(doc! { "purchasedAt": -1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: stops,
+    })
+}
+
+#[server]
+pub async fn update_fueling_stop(
+    req: UpdateFuelingStopRequest,
+) -> Result<SuccessResponse<FuelingStop>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
+    let collection = db.collection::<FuelingStop>("fueling_stops");
+
+    let jurisdiction = fuel_purchase_jurisdiction(
+        &req.location,
+        &req.jurisdiction,
+        req.fuel_amount,
+        req.price_per_gallon,
+        req.odometer,
+    )?;
+    let stop_id = ObjectId::parse_str(&req.fueling_stop_id)?;
+    let stop = collection
+        .find_one(doc! { "_id": stop_id })
+        .await?
+        .ok_or(ServerFnError::new("Fueling stop not found"))?;
+    trip_collection
+        .find_one(doc! { "_id": stop.trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Fueling stop not found"))?;
+
+    let receipt_reference = req
+        .receipt_reference
+        .map(|reference| reference.trim().to_string())
+        .filter(|reference| !reference.is_empty());
+    let updated = collection
+        .find_one_and_update(
+            doc! { "_id": stop_id },
+            doc! {
+                "$set": {
+                    "location": req.location.trim(),
+                    "jurisdiction": jurisdiction,
+                    "fuelAmount": req.fuel_amount,
+                    "pricePerGallon": req.price_per_gallon,
+                    "odometer": req.odometer,
+                    "receiptReference": receipt_reference,
+                    "purchasedAt": bson::DateTime::from_chrono(req.purchased_at),
+                    "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+                }
+            },
+        )
+        .return_document(ReturnDocument::After)
+        .await?
+        .ok_or(ServerFnError::new("Fueling stop not found"))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: updated,
+    })
+}
+
 #[server]
 pub async fn store_eld_log(
     req: StoreEldLogRequest,
@@ -218,59 +557,95 @@ pub async fn store_eld_log(
 
     let client = get_client().await;
     let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
-    let collection = db.collection::<EldLog>("eld_logs");
+    let trip_collection = db.collection::<Trip>("trips");
 
-    let new_log = EldLog {
-        id: ObjectId::new(),
-        driver_id: user.id,
-        start_hour: req.start_hour,
-        end_hour: req.end_hour,
-        status: req.status,
-        trip_id: ObjectId::parse_str(&req.trip_id)?,
-        driving_hours: req.driving_hours,
-        on_duty_hours: req.on_duty_hours,
-        off_duty_hours: req.off_duty_hours,
-        sleeper_berth_hours: req.sleeper_berth_hours,
-        location: req.location,
-        note: req.note,
-        odometer_reading: req.odometer_reading,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
+    let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    let trip = trip_collection
+        .find_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Trip not found"))?;
+
+    let status = req.status;
+    let (start, end) = (req.start, req.end);
+    if end <= start {
+        return Err(ServerFnError::new("Invalid time range"));
+    }
 
-    collection.insert_one(new_log.clone()).await?;
+    let events = load_duty_events(user.id, start).await?;
+    let (changes, superseded) = record_range(
+        &events,
+        status,
+        start,
+        end,
+        &req.location,
+        &req.note,
+        req.odometer_reading,
+    );
+    deactivate_duty_events(&superseded).await?;
+    let recorded =
+        append_duty_events(user.id, Some(trip_id), &changes, DutyEventOrigin::Driver).await?;
+    let uncertified_days = uncertify_days(user.id, start, end).await?;
+
+    let now = Utc::now();
+    let periods = load_duty_history(user.id, now - Duration::days(HISTORY_DAYS)).await?;
+    let cycle_rule = cycle_rule_for(&user).await?;
+    let report = evaluate(&periods, cycle_rule, now);
+    let used = cycle_used(&normalize(&periods), cycle_rule, now);
+    let restarts = record_restarts(user.id, &report.restarts).await?;
+    let warnings = category_warnings_for(&user, start, end).await?;
+
+    trip_collection
+        .update_one(
+            doc! { "_id": trip_id },
+            doc! { "$set": {
+                "cycleUsedHours": used.num_minutes() as f64 / 60.0,
+                "updatedAt": bson::DateTime::from_chrono(now),
+            }},
+        )
+        .await?;
 
     Ok(SuccessResponse {
         status: "success".into(),
-        data: EldLogResponse { id: new_log.id },
+        data: EldLogResponse {
+            id: recorded[0].id,
+            violations: report.violations,
+            restarts,
+            uncertified_days,
+            warnings,
+        },
     })
 }
 
 #[server]
 pub async fn get_elds_for_user(
     req: GetEldsForUserRequest,
-) -> Result<SuccessResponse<Vec<EldLog>>, ServerFnError> {
+) -> Result<SuccessResponse<Vec<DutyEvent>>, ServerFnError> {
     let user = auth(req.token)
         .await
         .map_err(|_| ServerFnError::new("Not Authenticated"))?;
 
     let client = get_client().await;
     let db = client.database(&std::env::var("MONGODB_DB_NAME").unwrap());
-    let collection = db.collection::<EldLog>("eld_logs");
+    let collection = db.collection::<DutyEvent>("duty_events");
 
-    let filter = doc! {"driverId": user.id, "tripId":  ObjectId::parse_str(&req.trip_id)?};
+    let filter = doc! {
+        "driverId": user.id,
+        "tripId": ObjectId::parse_str(&req.trip_id)?,
+        "active": true,
+    };
     let cursor = collection
         .find(filter)
+        .sort(doc! { "at": 1, "sequence": 1 })
         .await
         .map_err(|e| ServerFnError::new(&e.to_string()))?;
 
-    let elds: Vec<EldLog> = cursor
+    let events: Vec<DutyEvent> = cursor
         .try_collect()
         .await
         .map_err(|e| ServerFnError::new(&e.to_string()))?;
     Ok(SuccessResponse {
         status: "success".into(),
-        data: elds,
+        data: events,
     })
 }
 
@@ -286,9 +661,24 @@ pub async fn store_route(
     let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
     let collection = db.collection::<Route>("routes");
 
+    if req.start_location.trim().is_empty()
+        || req.end_location.trim().is_empty()
+        || req
+            .waypoints
+            .iter()
+            .any(|waypoint| waypoint.location.trim().is_empty())
+    {
+        return Err(ServerFnError::new("Every route location is required"));
+    }
+    let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    db.collection::<Trip>("trips")
+        .find_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Trip not found"))?;
+
     let new_route = Route {
         id: ObjectId::new(),
-        trip_id: ObjectId::parse_str(&req.trip_id)?,
+        trip_id,
         start_location: req.start_location,
         end_location: req.end_location,
         waypoints: req.waypoints,
@@ -318,10 +708,17 @@ pub async fn store_waypoint(
     let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
     let collection = db.collection::<Waypoint>("waypoints");
 
+    if req.location.trim().is_empty() {
+        return Err(ServerFnError::new("The waypoint location is required"));
+    }
     let new_waypoint = Waypoint {
         id: ObjectId::new(),
         location: req.location,
         eta: req.eta,
+        stop_type: None,
+        window_start: None,
+        window_end: None,
+        missed_window: false,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -347,12 +744,27 @@ pub async fn store_route_stop(
     let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
     let collection = db.collection::<RouteStop>("route_stops");
 
+    if req.location.trim().is_empty() {
+        return Err(ServerFnError::new("The stop location is required"));
+    }
+    // The route must belong to one of the driver's trips.
+    let route = db
+        .collection::<Route>("routes")
+        .find_one(doc! { "_id": ObjectId::parse_str(&req.route_id)? })
+        .await?
+        .ok_or(ServerFnError::new("Route not found"))?;
+    db.collection::<Trip>("trips")
+        .find_one(doc! { "_id": route.trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Route not found"))?;
+
     let new_stop = RouteStop {
         id: ObjectId::new(),
-        route_id: ObjectId::parse_str(&req.route_id)?,
+        route_id: route.id,
         location: req.location,
         stop_type: req.stop_type,
         duration_minutes: req.duration_minutes,
+        scheduled_at: req.scheduled_at,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -364,6 +776,155 @@ pub async fn store_route_stop(
         data: RouteStopResponse { id: new_stop.id },
     })
 }
+#[server]
+pub async fn plan_trip(
+    req: PlanTripRequest,
+) -> Result<SuccessResponse<PlanTripResponse>, ServerFnError> {
+    let user = auth(req.token.clone())
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
+
+    let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    let trip = trip_collection
+        .find_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Trip not found"))?;
+
+    let cycle_used = Duration::minutes((trip.cycle_used_hours * 60.0) as i64);
+    let cycle_rule = cycle_rule_for(&user).await?;
+    let departure = Utc::now();
+    let mut stops = trip.ordered_stops();
+    if req.optimize && stops.len() > 3 {
+        let mut places = vec![trip.current_location.clone()];
+        places.extend(stops.iter().map(|stop| stop.location.clone()));
+        let matrix = cached_matrix(&user.id.to_hex(), &places).await?;
+        let order = optimize_stops(
+            &trip.current_location,
+            &stops,
+            &matrix,
+            cycle_rule,
+            cycle_used,
+            departure,
+        );
+        stops = order.into_iter().map(|i| stops[i].clone()).collect();
+        trip_collection
+            .update_one(
+                doc! { "_id": trip_id },
+                doc! { "$set": {
+                    "stops": bson::to_bson(&stops)?,
+                    "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+                }},
+            )
+            .await?;
+    }
+
+    let mut places = vec![trip.current_location.clone()];
+    places.extend(stops.iter().map(|stop| stop.location.clone()));
+    let route = cached_route(&user.id.to_hex(), &places).await?;
+    if route.legs.len() != stops.len() {
+        return Err(ServerFnError::new("No route between the trip stops"));
+    }
+    let itinerary = plan_itinerary(
+        &stop_legs(&trip.current_location, &stops, &route.legs),
+        cycle_rule,
+        cycle_used,
+        departure,
+    );
+
+    // Service at each stop, in order; waits for a window come before it.
+    let mut service = itinerary
+        .stops
+        .iter()
+        .filter(|stop| matches!(stop.stop_type, StopType::Pickup | StopType::Dropoff));
+    let waypoints = stops
+        .iter()
+        .enumerate()
+        .map(|(index, stop)| Waypoint {
+            id: ObjectId::new(),
+            location: stop.location.clone(),
+            eta: service.next().map(|planned| planned.arrival),
+            stop_type: Some(stop.stop_type),
+            window_start: stop.window_start,
+            window_end: stop.window_end,
+            missed_window: itinerary.missed_windows.contains(&index), // Leg `index` ends here
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+        .collect();
+    let route = store_route(StoreRouteRequest {
+        token: req.token.clone(),
+        trip_id: req.trip_id.clone(),
+        start_location: trip.current_location.clone(),
+        end_location: stops[stops.len() - 1].location.clone(),
+        waypoints,
+        total_distance_miles: itinerary.total_distance_miles,
+        estimated_time_minutes: (itinerary.arrival - itinerary.departure).num_minutes() as u64,
+    })
+    .await?;
+
+    for stop in &itinerary.stops {
+        store_route_stop(StoreRouteStopRequest {
+            token: req.token.clone(),
+            route_id: route.data.id.to_hex(),
+            location: stop.location.clone(),
+            stop_type: stop.stop_type,
+            duration_minutes: stop.duration_minutes,
+            scheduled_at: Some(stop.arrival),
+        })
+        .await?;
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: PlanTripResponse {
+            route_id: route.data.id,
+            itinerary,
+        },
+    })
+}
+
+// Clears the certification of the driver's daily logs that overlap [start, end).
+// The signature stays, so those days show as needing recertification.
+#[cfg(feature = "server")]
+pub async fn uncertify_days(
+    driver_id: ObjectId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<usize, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<DailyLog>("daily_logs");
+
+    let certified: Vec<DailyLog> = collection
+        .find(doc! { "driverId": driver_id, "certifiedAt": { "$ne": null } })
+        .await?
+        .try_collect()
+        .await?;
+    let ids: Vec<ObjectId> = certified
+        .iter()
+        .filter(|log| log.log_date < end && log.log_date + Duration::days(1) > start)
+        .map(|log| log.id)
+        .collect();
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    collection
+        .update_many(
+            doc! { "_id": { "$in": ids.clone() } },
+            doc! { "$set": {
+                "certifiedAt": null,
+                "updatedAt": bson::DateTime::from_chrono(Utc::now()),
+            }},
+        )
+        .await?;
+    Ok(ids.len())
+}
+
 #[server]
 pub async fn store_daily_log(
     req: StoreDailyLogRequest,
@@ -376,12 +937,20 @@ pub async fn store_daily_log(
     let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
     let collection = db.collection::<DailyLog>("daily_logs");
 
+    let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    db.collection::<Trip>("trips")
+        .find_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Trip not found"))?;
+
     let new_log = DailyLog {
         id: ObjectId::new(),
         driver_id: user.id,
-        trip_id: ObjectId::parse_str(&req.trip_id)?,
+        trip_id,
         log_date: req.log_date,
         signature: req.signature,
+        certified_at: None,
+        entries_hash: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -405,9 +974,15 @@ pub async fn store_log_entry(
     let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
     let collection = db.collection::<LogEntry>("log_entries");
 
+    let log = db
+        .collection::<DailyLog>("daily_logs")
+        .find_one(doc! { "_id": ObjectId::parse_str(&req.log_id)?, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Daily log not found"))?;
+
     let new_entry = LogEntry {
         id: ObjectId::new(),
-        log_id: ObjectId::parse_str(&req.log_id)?,
+        log_id: log.id,
         time: req.time,
         status: req.status,
         location: req.location,
@@ -425,70 +1000,252 @@ pub async fn store_log_entry(
 }
 
 #[server]
-pub async fn fetch_google_places_autocomplete(
-    input: String,
-    api_key: String,
-) -> Result<GooglePlacesResponse, ServerFnError> {
-    let url = format!(
-        "https://maps.googleapis.com/maps/api/place/autocomplete/json?input={}&key={}",
-        input, api_key
-    );
+pub async fn generate_daily_logs(
+    req: GenerateDailyLogsRequest,
+) -> Result<SuccessResponse<Vec<DailyLogSheet>>, ServerFnError> {
+    let user = auth(req.token.clone())
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
 
-    let client = ReqClient::new();
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let trip_collection = db.collection::<Trip>("trips");
+    let route_collection = db.collection::<Route>("routes");
+    let stop_collection = db.collection::<RouteStop>("route_stops");
+    let daily_collection = db.collection::<DailyLog>("daily_logs");
+    let entry_collection = db.collection::<LogEntry>("log_entries");
+
+    let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    let trip = trip_collection
+        .find_one(doc! { "_id": trip_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Trip not found"))?;
+    let route = route_collection
+        .find_one(doc! { "tripId": trip_id })
+        .sort(doc! { "createdAt": -1 })
+        .await?
+        .ok_or(ServerFnError::new("Plan the trip before generating logs"))?;
+    let stops: Vec<RouteStop> = stop_collection
+        .find(doc! { "routeId": route.id })
+        .await?
+        .try_collect()
+        .await?;
+
+    let itinerary = itinerary_from_route(&route, &stops);
+    let sheets = daily_sheets(&itinerary_periods(&itinerary, &trip.current_location));
+
+    // Regenerating replaces earlier sheets the driver has not signed yet.
+    let unsigned = doc! { "tripId": trip_id, "driverId": user.id, "signature": null };
+    let stale: Vec<DailyLog> = daily_collection
+        .find(unsigned.clone())
+        .await?
+        .try_collect()
+        .await?;
+    for log in &stale {
+        entry_collection
+            .delete_many(doc! { "logId": log.id })
+            .await?;
+    }
+    daily_collection.delete_many(unsigned).await?;
+
+    for sheet in sheets {
+        let log = store_daily_log(StoreDailyLogRequest {
+            token: req.token.clone(),
+            trip_id: req.trip_id.clone(),
+            log_date: sheet.date,
+            signature: None,
+        })
+        .await?;
+
+        for entry in sheet.entries {
+            store_log_entry(StoreLogEntryRequest {
+                token: req.token.clone(),
+                log_id: log.data.id.to_hex(),
+                time: entry.time,
+                status: entry.status,
+                location: entry.location,
+                remarks: entry.remarks,
+            })
+            .await?;
+        }
+    }
+
+    get_daily_logs(GetDailyLogsRequest {
+        token: req.token,
+        trip_id: req.trip_id,
+    })
+    .await
+}
 
-    let response = client
-        .get(&url)
-        .send()
+// A day as its sheet shows it and as certifying it signs it: the duty status
+// the driver recorded that day, or the log's planned entries if there is none.
+#[cfg(feature = "server")]
+pub async fn day_sheet(log: DailyLog, now: DateTime<Utc>) -> Result<DailyLogSheet, ServerFnError> {
+    let events = load_duty_events(log.driver_id, log.log_date).await?;
+    let mut entries = recorded_entries(&events, &log, now);
+    if entries.is_empty() {
+        let client = get_client().await;
+        let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+        entries = db
+            .collection::<LogEntry>("log_entries")
+            .find(doc! { "logId": log.id })
+            .sort(doc! { "time": 1 })
+            .await?
+            .try_collect()
+            .await?;
+    }
+    Ok(DailyLogSheet { log, entries })
+}
+
+#[server]
+pub async fn get_daily_logs(
+    req: GetDailyLogsRequest,
+) -> Result<SuccessResponse<Vec<DailyLogSheet>>, ServerFnError> {
+    let user = auth(req.token)
         .await
-        .map_err(|_| ServerFnError::new("Error fetching autocomplete data from Google API"))?;
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
 
-    let google_response = response
-        .json::<GooglePlacesResponse>()
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let daily_collection = db.collection::<DailyLog>("daily_logs");
+
+    let trip_id = ObjectId::parse_str(&req.trip_id)?;
+    let logs: Vec<DailyLog> = daily_collection
+        .find(doc! { "tripId": trip_id, "driverId": user.id })
+        .sort(doc! { "logDate": 1 })
         .await
-        .map_err(|_| ServerFnError::new("Error parsing response from Google API"))?;
+        .map_err(|e| ServerFnError::new(&e.to_string()))?
+        .try_collect()
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?;
 
-    Ok(google_response)
+    let mut sheets = Vec::with_capacity(logs.len());
+    for log in logs {
+        sheets.push(day_sheet(log, Utc::now()).await?);
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: sheets,
+    })
 }
 
+/// Certifies a day with the driver's drawn signature and a hash of the day's
+/// entries; the day reads as certified only while the entries still match it.
 #[server]
-pub async fn fetch_distance_duration(
-    route_url: String,
-) -> Result<GoogleDurationResponse, ServerFnError> {
-    let client = ReqClient::new();
-
-    use dioxus_logger::tracing;
-    let response = client
-        .get(&route_url)
-        .send()
+pub async fn certify_daily_log(
+    req: CertifyDailyLogRequest,
+) -> Result<SuccessResponse<DailyLogSheet>, ServerFnError> {
+    let user = auth(req.token)
         .await
-        .map_err(|_| ServerFnError::new("Error fetching autocomplete data from Google API"))?;
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    if !req.signature.starts_with("data:image/") {
+        return Err(ServerFnError::new("Sign the log to certify it"));
+    }
 
-    let google_response = response
-        .json::<DistanceMatrixResponse>()
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let daily_collection = db.collection::<DailyLog>("daily_logs");
+
+    let log_id = ObjectId::parse_str(&req.log_id)?;
+    let log = daily_collection
+        .find_one(doc! { "_id": log_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Daily log not found"))?;
+    if log.log_date > Utc::now() {
+        return Err(ServerFnError::new(
+            "A day can only be certified once it has started",
+        ));
+    }
+    let now = Utc::now();
+    let mut sheet = day_sheet(log, now).await?;
+    sheet.log.signature = Some(req.signature);
+    sheet.log.certified_at = Some(now);
+    sheet.log.entries_hash = Some(entries_hash(&sheet.entries));
+    sheet.log.updated_at = now;
+    daily_collection
+        .replace_one(doc! { "_id": sheet.log.id }, sheet.log.clone())
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: sheet,
+    })
+}
+
+#[server]
+pub async fn export_daily_log(
+    req: ExportDailyLogRequest,
+) -> Result<SuccessResponse<DailyLogExportResponse>, ServerFnError> {
+    let user = auth(req.token)
         .await
-        .map_err(|_| ServerFnError::new("Error parsing response from Google API"))?;
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
 
-    if let Some(distance) = google_response
-        .rows
-        .first()
-        .and_then(|row| row.elements.first())
-        .map(|element| element.distance.value)
-    {
-        if let Some(duration) = google_response
-            .rows
-            .first()
-            .and_then(|row| row.elements.first())
-            .map(|element| element.duration.value)
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let daily_collection = db.collection::<DailyLog>("daily_logs");
+    let route_collection = db.collection::<Route>("routes");
+    let stop_collection = db.collection::<RouteStop>("route_stops");
+
+    let log_id = ObjectId::parse_str(&req.log_id)?;
+    let log = daily_collection
+        .find_one(doc! { "_id": log_id, "driverId": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Daily log not found"))?;
+    let sheet = day_sheet(log, Utc::now()).await?;
+
+    // Miles come from the odometer readings recorded that day, as on the
+    // inspection view. Without any, they are estimated from the planned route's
+    // average driving speed.
+    let day = sheet.log.log_date;
+    let events = load_duty_events(user.id, day).await?;
+    let recorded = odometer_miles_between(&events, day, day + Duration::days(1));
+    let mut miles_driven = recorded.unwrap_or_default();
+    if recorded.is_none() {
+        if let Some(route) = route_collection
+            .find_one(doc! { "tripId": sheet.log.trip_id })
+            .sort(doc! { "createdAt": -1 })
+            .await?
         {
-            return Ok(GoogleDurationResponse {
-                distance: distance / 1609.34,
-                duration: duration,
-            });
+            let stops: Vec<RouteStop> = stop_collection
+                .find(doc! { "routeId": route.id })
+                .await?
+                .try_collect()
+                .await?;
+            let itinerary = itinerary_from_route(&route, &stops);
+            if itinerary.driving_minutes > 0 {
+                let driving_minutes = row_totals(&status_spans(&sheet)).driving;
+                miles_driven = itinerary.total_distance_miles * driving_minutes as f64
+                    / itinerary.driving_minutes as f64;
+            }
         }
     }
 
-    Ok(GoogleDurationResponse {
-        distance: 0.0,
-        duration: 0,
+    let header = SheetHeader {
+        driver_name: user.name,
+        carrier_name: user.carrier_name.unwrap_or_default(),
+        truck_number: user.truck_number.unwrap_or_default(),
+        miles_driven,
+        miles_estimated: recorded.is_none(),
+        recorded_until: None,
+    };
+    let bytes = match req.format {
+        SheetFormat::Svg => render_svg(&header, &sheet).into_bytes(),
+        SheetFormat::Pdf => render_pdf(&header, &sheet).ok_or(ServerFnError::new(
+            "This signature can't be embedded in a PDF; export the sheet as SVG",
+        ))?,
+    };
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: DailyLogExportResponse {
+            file_name: format!(
+                "daily_log_{}.{}",
+                sheet.log.log_date.format("%Y-%m-%d"),
+                req.format.extension()
+            ),
+            data_url: data_url(req.format.mime_type(), &bytes),
+        },
     })
 }
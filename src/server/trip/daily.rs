@@ -0,0 +1,285 @@
+use crate::server::hos::model::{DutyPeriod, DutyState};
+use crate::server::trip::model::{Route, RouteStop};
+use crate::server::trip::planner::{Itinerary, PlannedStop};
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SheetEntry {
+    pub time: DateTime<Utc>,
+    pub status: DutyState,
+    pub location: String,
+    pub remarks: Option<String>,
+}
+
+// One calendar day (UTC) of expected duty statuses, starting at midnight.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailySheet {
+    pub date: DateTime<Utc>,
+    pub entries: Vec<SheetEntry>,
+}
+
+fn midnight(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc()
+}
+
+/// Rebuilds an itinerary from a stored route; driving fills the gaps between stops.
+pub fn itinerary_from_route(route: &Route, stops: &[RouteStop]) -> Itinerary {
+    let mut stops: Vec<PlannedStop> = stops
+        .iter()
+        .filter_map(|stop| {
+            Some(PlannedStop {
+                location: stop.location.clone(),
+                stop_type: stop.stop_type,
+                arrival: stop.scheduled_at?,
+                duration_minutes: stop.duration_minutes,
+                mile_marker: 0.0,
+            })
+        })
+        .collect();
+    stops.sort_by_key(|stop| stop.arrival);
+
+    let arrival = stops
+        .last()
+        .map(|stop| stop.arrival + Duration::minutes(stop.duration_minutes as i64))
+        .unwrap_or(route.created_at);
+    let departure = arrival - Duration::minutes(route.estimated_time_minutes as i64);
+    let stopped: i64 = stops.iter().map(|stop| stop.duration_minutes as i64).sum();
+
+    Itinerary {
+        total_distance_miles: route.total_distance_miles,
+        driving_minutes: route.estimated_time_minutes as i64 - stopped,
+        departure,
+        arrival,
+        stops,
+        missed_windows: vec![],
+    }
+}
+
+/// Expands an itinerary into duty periods, from `start_location` through the last stop.
+pub fn itinerary_periods(itinerary: &Itinerary, start_location: &str) -> Vec<DutyPeriod> {
+    let mut periods = Vec::new();
+    let mut cursor = itinerary.departure;
+    let mut location = start_location.to_string();
+
+    for stop in &itinerary.stops {
+        if stop.arrival > cursor {
+            periods.push(DutyPeriod {
+                status: DutyState::Driving,
+                start: cursor,
+                end: stop.arrival,
+                location: location.clone(),
+            });
+        }
+        let end = stop.arrival + Duration::minutes(stop.duration_minutes as i64);
+        periods.push(DutyPeriod {
+            status: stop.stop_type.duty_status(),
+            start: stop.arrival,
+            end,
+            location: stop.location.clone(),
+        });
+        cursor = end;
+        location = stop.location.clone();
+    }
+    periods
+}
+
+/// Splits duty periods into per-day sheets covering all 24 hours; time before the
+/// first period and after the last one is logged off duty.
+pub fn daily_sheets(periods: &[DutyPeriod]) -> Vec<DailySheet> {
+    let (Some(first), Some(last)) = (periods.first(), periods.last()) else {
+        return Vec::new();
+    };
+
+    let mut sheets = Vec::new();
+    let mut day = midnight(first.start);
+    while day < last.end {
+        let next_day = day + Duration::days(1);
+        let mut entries: Vec<SheetEntry> = Vec::new();
+        let mut cursor = day;
+        let mut location = first.location.clone();
+
+        for period in periods {
+            let start = period.start.max(day);
+            let end = period.end.min(next_day);
+            if end <= start {
+                if period.end <= day {
+                    location = period.location.clone();
+                }
+                continue;
+            }
+            if start > cursor
+                && entries.last().map(|entry| entry.status) != Some(DutyState::OffDuty)
+            {
+                entries.push(SheetEntry {
+                    time: cursor,
+                    status: DutyState::OffDuty,
+                    location: location.clone(),
+                    remarks: None,
+                });
+            }
+            if entries.last().map(|entry| entry.status) != Some(period.status) {
+                entries.push(SheetEntry {
+                    time: start,
+                    status: period.status,
+                    location: period.location.clone(),
+                    remarks: (period.start == start).then(|| period.location.clone()),
+                });
+            }
+            cursor = end;
+            location = period.location.clone();
+        }
+        if cursor < next_day && entries.last().map(|entry| entry.status) != Some(DutyState::OffDuty)
+        {
+            entries.push(SheetEntry {
+                time: cursor,
+                status: DutyState::OffDuty,
+                location,
+                remarks: None,
+            });
+        }
+
+        sheets.push(DailySheet { date: day, entries });
+        day = next_day;
+    }
+    sheets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::trip::model::StopType;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn stop(
+        location: &str,
+        stop_type: StopType,
+        arrival: DateTime<Utc>,
+        hours: u64,
+    ) -> PlannedStop {
+        PlannedStop {
+            location: location.to_string(),
+            stop_type,
+            arrival,
+            duration_minutes: hours * 60,
+            mile_marker: 0.0,
+        }
+    }
+
+    // Leaves the yard at 20:00, loads in Dallas, sleeps in Waco and unloads in
+    // Austin the next morning.
+    fn itinerary() -> Itinerary {
+        Itinerary {
+            stops: vec![
+                stop("Dallas, TX", StopType::Pickup, at(2, 21), 1),
+                stop("Waco, TX", StopType::Rest, at(2, 23), 10),
+                stop("Austin, TX", StopType::Dropoff, at(3, 11), 1),
+            ],
+            total_distance_miles: 250.0,
+            driving_minutes: 240,
+            departure: at(2, 20),
+            arrival: at(3, 12),
+            missed_windows: vec![],
+        }
+    }
+
+    fn entries(sheet: &DailySheet) -> Vec<(u32, DutyState, &str)> {
+        sheet
+            .entries
+            .iter()
+            .map(|entry| (entry.time.hour(), entry.status, entry.location.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn driving_fills_the_gaps_between_stops() {
+        let periods = itinerary_periods(&itinerary(), "Yard");
+        let spans: Vec<(DutyState, DateTime<Utc>, &str)> = periods
+            .iter()
+            .map(|period| (period.status, period.start, period.location.as_str()))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (DutyState::Driving, at(2, 20), "Yard"),
+                (DutyState::OnDuty, at(2, 21), "Dallas, TX"),
+                (DutyState::Driving, at(2, 22), "Dallas, TX"),
+                (DutyState::Sleeper, at(2, 23), "Waco, TX"),
+                (DutyState::Driving, at(3, 9), "Waco, TX"),
+                (DutyState::OnDuty, at(3, 11), "Austin, TX"),
+            ]
+        );
+        assert_eq!(periods.last().unwrap().end, at(3, 12));
+    }
+
+    #[test]
+    fn sheets_split_at_midnight_with_off_duty_padding() {
+        let sheets = daily_sheets(&itinerary_periods(&itinerary(), "Yard"));
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(sheets[0].date, at(2, 0));
+        assert_eq!(
+            entries(&sheets[0]),
+            [
+                (0, DutyState::OffDuty, "Yard"),
+                (20, DutyState::Driving, "Yard"),
+                (21, DutyState::OnDuty, "Dallas, TX"),
+                (22, DutyState::Driving, "Dallas, TX"),
+                (23, DutyState::Sleeper, "Waco, TX"),
+            ]
+        );
+        assert_eq!(sheets[1].date, at(3, 0));
+        assert_eq!(
+            entries(&sheets[1]),
+            [
+                (0, DutyState::Sleeper, "Waco, TX"),
+                (9, DutyState::Driving, "Waco, TX"),
+                (11, DutyState::OnDuty, "Austin, TX"),
+                (12, DutyState::OffDuty, "Austin, TX"),
+            ]
+        );
+    }
+
+    #[test]
+    fn carried_over_status_has_no_remark() {
+        let sheets = daily_sheets(&itinerary_periods(&itinerary(), "Yard"));
+        assert_eq!(sheets[0].entries[4].remarks.as_deref(), Some("Waco, TX"));
+        assert_eq!(sheets[1].entries[0].remarks, None);
+    }
+
+    #[test]
+    fn off_duty_day_keeps_the_last_location() {
+        // Two days apart; the day between is logged off duty where the driver was.
+        let periods = [
+            DutyPeriod {
+                status: DutyState::Driving,
+                start: at(2, 8),
+                end: at(2, 10),
+                location: "Dallas, TX".to_string(),
+            },
+            DutyPeriod {
+                status: DutyState::Driving,
+                start: at(4, 8),
+                end: at(4, 10),
+                location: "Waco, TX".to_string(),
+            },
+        ];
+        let sheets = daily_sheets(&periods);
+        assert_eq!(sheets.len(), 3);
+        assert_eq!(entries(&sheets[1]), [(0, DutyState::OffDuty, "Dallas, TX")]);
+        assert_eq!(
+            entries(&sheets[2]),
+            [
+                (0, DutyState::OffDuty, "Dallas, TX"),
+                (8, DutyState::Driving, "Waco, TX"),
+                (10, DutyState::OffDuty, "Waco, TX"),
+            ]
+        );
+    }
+}
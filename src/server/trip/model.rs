@@ -1,5 +1,6 @@
 #![allow(non_snake_case)]
 
+use crate::server::hos::model::{CycleRule, DutyState};
 use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -17,10 +18,16 @@ pub struct Trip {
     pub pickup_location: String,
     #[serde(rename = "dropoffLocation")]
     pub dropoff_location: String,
+    #[serde(rename = "stops", default)]
+    pub stops: Vec<TripStop>, // In visiting order; empty on trips stored before multi-stop trips
     #[serde(rename = "cycleUsedHours")]
-    pub cycle_used_hours: f64,
+    pub cycle_used_hours: f64, // Computed from the driver's rolling ELD history
+    #[serde(rename = "cycleRule", default)]
+    pub cycle_rule: CycleRule,
+    #[serde(rename = "cycleExceeded", default)]
+    pub cycle_exceeded: bool, // Planned driving exceeds the remaining cycle
     #[serde(rename = "status")]
-    pub status: String, // "Pending", "Ongoing", "Completed"
+    pub status: TripStatus,
     #[serde(rename = "distanceMiles")]
     pub distance_miles: Option<f64>,
     #[serde(rename = "estimatedDuration")]
@@ -31,6 +38,97 @@ pub struct Trip {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Trip {
+    // The stops to visit, with the pickup and drop-off of older trips as stops.
+    pub fn ordered_stops(&self) -> Vec<TripStop> {
+        if !self.stops.is_empty() {
+            return self.stops.clone();
+        }
+        vec![
+            TripStop {
+                location: self.pickup_location.clone(),
+                stop_type: StopType::Pickup,
+                window_start: None,
+                window_end: None,
+            },
+            TripStop {
+                location: self.dropoff_location.clone(),
+                stop_type: StopType::Dropoff,
+                window_start: None,
+                window_end: None,
+            },
+        ]
+    }
+}
+
+// A pickup or drop-off on a trip, with the appointment window to start service in.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TripStop {
+    #[serde(rename = "location")]
+    pub location: String,
+    #[serde(rename = "stopType")]
+    pub stop_type: StopType, // Pickup or Dropoff
+    #[serde(rename = "windowStart", default)]
+    pub window_start: Option<DateTime<Utc>>, // Open when not given
+    #[serde(rename = "windowEnd", default)]
+    pub window_end: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TripStatus {
+    #[default]
+    Pending,
+    Ongoing,
+    Completed,
+    Cancelled,
+}
+
+impl TripStatus {
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_lowercase().as_str() {
+            "pending" => Some(TripStatus::Pending),
+            "ongoing" => Some(TripStatus::Ongoing),
+            "completed" => Some(TripStatus::Completed),
+            "cancelled" | "canceled" => Some(TripStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TripStatus::Pending => "Pending",
+            TripStatus::Ongoing => "Ongoing",
+            TripStatus::Completed => "Completed",
+            TripStatus::Cancelled => "Cancelled",
+        }
+    }
+
+    // Pending -> Ongoing -> Completed; trips that are not finished can be cancelled.
+    pub fn can_become(&self, next: TripStatus) -> bool {
+        matches!(
+            (self, next),
+            (TripStatus::Pending, TripStatus::Ongoing)
+                | (TripStatus::Ongoing, TripStatus::Completed)
+                | (
+                    TripStatus::Pending | TripStatus::Ongoing,
+                    TripStatus::Cancelled
+                )
+        )
+    }
+
+    // Statuses the trip can move to next.
+    pub fn next(&self) -> Vec<TripStatus> {
+        [
+            TripStatus::Ongoing,
+            TripStatus::Completed,
+            TripStatus::Cancelled,
+        ]
+        .into_iter()
+        .filter(|next| self.can_become(*next))
+        .collect()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct FuelingStop {
     #[serde(rename = "_id")]
@@ -41,6 +139,20 @@ pub struct FuelingStop {
     pub location: String,
     #[serde(rename = "fuelAmount")]
     pub fuel_amount: f64, // Gallons
+    #[serde(default)]
+    pub jurisdiction: String, // IFTA code such as "TX" or "ON"; empty when unknown
+    #[serde(rename = "pricePerGallon", default)]
+    pub price_per_gallon: Option<f64>,
+    #[serde(default)]
+    pub odometer: Option<f64>,
+    #[serde(rename = "receiptReference", default)]
+    pub receipt_reference: Option<String>,
+    #[serde(
+        with = "chrono_datetime_as_bson_datetime",
+        rename = "purchasedAt",
+        default
+    )]
+    pub purchased_at: DateTime<Utc>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
@@ -68,7 +180,7 @@ pub struct EldLog {
     #[serde(rename = "sleeperBerthHours")]
     pub sleeper_berth_hours: f64,
     pub location: String,
-    pub status: String,
+    pub status: DutyState,
     pub note: String,
     #[serde(rename = "odometerReading")]
     pub odometer_reading: Option<f64>,
@@ -108,12 +220,70 @@ pub struct Waypoint {
     pub location: String,
     #[serde(rename = "eta")]
     pub eta: Option<DateTime<Utc>>, // Estimated Time of Arrival
+    #[serde(rename = "stopType", default)]
+    pub stop_type: Option<StopType>,
+    #[serde(rename = "windowStart", default)]
+    pub window_start: Option<DateTime<Utc>>,
+    #[serde(rename = "windowEnd", default)]
+    pub window_end: Option<DateTime<Utc>>,
+    #[serde(rename = "missedWindow", default)]
+    pub missed_window: bool, // Planned to arrive after the window closes
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum StopType {
+    Rest,    // 10 hours in the sleeper berth
+    Break,   // 30 minutes off duty
+    Restart, // 34 hours off duty
+    Fueling,
+    Inspection,
+    Pickup,
+    Dropoff,
+    Wait, // Off duty until an appointment window opens
+}
+
+impl StopType {
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_lowercase().as_str() {
+            "rest" => Some(StopType::Rest),
+            "break" => Some(StopType::Break),
+            "restart" => Some(StopType::Restart),
+            "fueling" | "fuel" => Some(StopType::Fueling),
+            "inspection" => Some(StopType::Inspection),
+            "pickup" => Some(StopType::Pickup),
+            "dropoff" => Some(StopType::Dropoff),
+            "wait" => Some(StopType::Wait),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StopType::Rest => "Rest",
+            StopType::Break => "Break",
+            StopType::Restart => "Restart",
+            StopType::Fueling => "Fueling",
+            StopType::Inspection => "Inspection",
+            StopType::Pickup => "Pickup",
+            StopType::Dropoff => "Dropoff",
+            StopType::Wait => "Wait",
+        }
+    }
+
+    // Duty status while stopped.
+    pub fn duty_status(&self) -> DutyState {
+        match self {
+            StopType::Rest => DutyState::Sleeper,
+            StopType::Break | StopType::Restart | StopType::Wait => DutyState::OffDuty,
+            _ => DutyState::OnDuty,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RouteStop {
     #[serde(rename = "_id")]
@@ -123,9 +293,11 @@ pub struct RouteStop {
     #[serde(rename = "location")]
     pub location: String,
     #[serde(rename = "stopType")]
-    pub stop_type: String, // "Rest", "Fueling", "Inspection"
+    pub stop_type: StopType,
     #[serde(rename = "durationMinutes")]
     pub duration_minutes: u64,
+    #[serde(rename = "scheduledAt", default)]
+    pub scheduled_at: Option<DateTime<Utc>>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
@@ -144,6 +316,10 @@ pub struct DailyLog {
     pub log_date: DateTime<Utc>,
     #[serde(rename = "signature")]
     pub signature: Option<String>, // Base64 driver signature
+    #[serde(rename = "certifiedAt", default)]
+    pub certified_at: Option<DateTime<Utc>>, // Cleared when a later edit touches the day
+    #[serde(rename = "entriesHash", default)]
+    pub entries_hash: Option<String>, // SHA-256 of the entries as certified
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
@@ -159,7 +335,7 @@ pub struct LogEntry {
     #[serde(rename = "time")]
     pub time: DateTime<Utc>,
     #[serde(rename = "status")]
-    pub status: String, // "Driving", "On-Duty", "Off-Duty", "Sleeper Berth"
+    pub status: DutyState,
     #[serde(rename = "location")]
     pub location: String,
     #[serde(rename = "remarks")]
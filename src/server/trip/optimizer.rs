@@ -0,0 +1,235 @@
+use crate::server::hos::model::CycleRule;
+use crate::server::routing::provider::DistanceDuration;
+use crate::server::trip::model::{StopType, TripStop};
+use crate::server::trip::planner::{plan_itinerary, stop_legs};
+use chrono::prelude::*;
+use chrono::Duration;
+
+// Keeps the exhaustive search over the intermediate stops to at most 7! orders.
+pub const MAX_TRIP_STOPS: usize = 9;
+
+// Missed windows, then miles, then arrival; lower is better.
+type Score = (usize, f64, DateTime<Utc>);
+
+fn better(score: &Score, than: &Score) -> bool {
+    score.0 < than.0
+        || (score.0 == than.0 && score.1 < than.1)
+        || (score.0 == than.0 && score.1 == than.1 && score.2 < than.2)
+}
+
+struct Search<'a> {
+    start: &'a str,
+    stops: &'a [TripStop],
+    matrix: &'a [Vec<DistanceDuration>],
+    rule: CycleRule,
+    cycle_used: Duration,
+    departure: DateTime<Utc>,
+    best: Option<(Score, Vec<usize>)>,
+}
+
+impl Search<'_> {
+    // Travel between two stops, where 0 is the start and `i + 1` is `stops[i]`.
+    fn travel(&self, from: usize, to: usize) -> DistanceDuration {
+        self.matrix[from][to]
+    }
+
+    fn evaluate(&mut self, order: &[usize]) {
+        let stops: Vec<TripStop> = order.iter().map(|&i| self.stops[i].clone()).collect();
+        let mut from = 0;
+        let travel: Vec<DistanceDuration> = order
+            .iter()
+            .map(|&i| {
+                let leg = self.travel(from, i + 1);
+                from = i + 1;
+                leg
+            })
+            .collect();
+        let miles = travel.iter().map(|leg| leg.distance_miles).sum();
+        let itinerary = plan_itinerary(
+            &stop_legs(self.start, &stops, &travel),
+            self.rule,
+            self.cycle_used,
+            self.departure,
+        );
+        let score = (itinerary.missed_windows.len(), miles, itinerary.arrival);
+        if self
+            .best
+            .as_ref()
+            .is_none_or(|(best, _)| better(&score, best))
+        {
+            self.best = Some((score, order.to_vec()));
+        }
+    }
+
+    // A drop-off can't come before a pickup that preceded it on the trip as entered.
+    fn ready(&self, stop: usize, visited: &[bool]) -> bool {
+        self.stops[stop].stop_type != StopType::Dropoff
+            || (1..stop).all(|earlier| {
+                self.stops[earlier].stop_type != StopType::Pickup || visited[earlier]
+            })
+    }
+
+    fn visit(&mut self, order: &mut Vec<usize>, visited: &mut [bool], miles: f64) {
+        let last = self.stops.len() - 1;
+        if order.len() == last {
+            order.push(last);
+            self.evaluate(order);
+            order.pop();
+            return;
+        }
+        let from = order.last().map_or(0, |&i| i + 1);
+        for stop in 1..last {
+            if visited[stop] || !self.ready(stop, visited) {
+                continue;
+            }
+            let so_far = miles + self.travel(from, stop + 1).distance_miles;
+            // Once every window is met only a shorter order can do better.
+            if let Some(((0, best_miles, _), _)) = self.best {
+                if so_far > best_miles {
+                    continue;
+                }
+            }
+            visited[stop] = true;
+            order.push(stop);
+            self.visit(order, visited, so_far);
+            order.pop();
+            visited[stop] = false;
+        }
+    }
+}
+
+/// The order to visit `stops` in, as indexes into `stops`. The first and last
+/// stops stay in place and the ones between are reordered to miss as few
+/// appointment windows as possible under the HOS limits, then to drive the
+/// fewest miles. `matrix[i][j]` is the travel from place `i` to place `j`, where
+/// place 0 is `start` and place `i + 1` is `stops[i]`.
+pub fn optimize_stops(
+    start: &str,
+    stops: &[TripStop],
+    matrix: &[Vec<DistanceDuration>],
+    rule: CycleRule,
+    cycle_used: Duration,
+    departure: DateTime<Utc>,
+) -> Vec<usize> {
+    if stops.len() <= 3 {
+        return (0..stops.len()).collect();
+    }
+    let mut search = Search {
+        start,
+        stops,
+        matrix,
+        rule,
+        cycle_used,
+        departure,
+        best: None,
+    };
+    let mut visited = vec![false; stops.len()];
+    visited[0] = true;
+    let miles = search.travel(0, 1).distance_miles;
+    search.visit(&mut vec![0], &mut visited, miles);
+    search
+        .best
+        .map(|(_, order)| order)
+        .unwrap_or_else(|| (0..stops.len()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    fn stop(location: &str, stop_type: StopType, window_end: Option<DateTime<Utc>>) -> TripStop {
+        TripStop {
+            location: location.to_string(),
+            stop_type,
+            window_start: None,
+            window_end,
+        }
+    }
+
+    // Places on a straight road at these mile markers, driven at 50 mph.
+    fn road(markers: &[f64]) -> Vec<Vec<DistanceDuration>> {
+        markers
+            .iter()
+            .map(|from| {
+                markers
+                    .iter()
+                    .map(|to| DistanceDuration {
+                        distance_miles: (to - from).abs(),
+                        duration_seconds: ((to - from).abs() * 72.0) as i64,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn optimize(stops: &[TripStop], markers: &[f64]) -> Vec<usize> {
+        optimize_stops(
+            "Yard",
+            stops,
+            &road(markers),
+            CycleRule::SeventyEight,
+            Duration::zero(),
+            at(8, 0),
+        )
+    }
+
+    #[test]
+    fn short_trips_keep_their_order() {
+        let stops = [
+            stop("A", StopType::Pickup, None),
+            stop("C", StopType::Dropoff, None),
+            stop("B", StopType::Dropoff, None),
+        ];
+        assert_eq!(optimize(&stops, &[0.0, 0.0, 100.0, 50.0]), [0, 1, 2]);
+    }
+
+    #[test]
+    fn drops_are_reordered_along_the_road() {
+        // Entered as A, C, B, D; driving it that way doubles back over B.
+        let stops = [
+            stop("A", StopType::Pickup, None),
+            stop("C", StopType::Dropoff, None),
+            stop("B", StopType::Dropoff, None),
+            stop("D", StopType::Dropoff, None),
+        ];
+        assert_eq!(
+            optimize(&stops, &[0.0, 0.0, 100.0, 50.0, 150.0]),
+            [0, 2, 1, 3]
+        );
+    }
+
+    #[test]
+    fn window_is_worth_extra_miles() {
+        // Along the road C is reached at 12:00, after its window closes; going
+        // there first makes it by 11:00 and comes back for B.
+        let stops = [
+            stop("A", StopType::Pickup, None),
+            stop("B", StopType::Dropoff, None),
+            stop("C", StopType::Dropoff, Some(at(11, 30))),
+            stop("D", StopType::Dropoff, None),
+        ];
+        assert_eq!(
+            optimize(&stops, &[0.0, 0.0, 50.0, 100.0, 150.0]),
+            [0, 2, 1, 3]
+        );
+    }
+
+    #[test]
+    fn drops_wait_for_their_pickup() {
+        // B is closer but its load is picked up at P.
+        let stops = [
+            stop("A", StopType::Pickup, None),
+            stop("P", StopType::Pickup, None),
+            stop("B", StopType::Dropoff, None),
+            stop("D", StopType::Dropoff, None),
+        ];
+        assert_eq!(
+            optimize(&stops, &[0.0, 0.0, 100.0, 50.0, 150.0]),
+            [0, 1, 2, 3]
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use crate::server::hos::engine::{
+    BREAK_AFTER_DRIVING_HOURS, BREAK_MINUTES, DUTY_WINDOW_HOURS, MAX_DRIVING_HOURS, RESTART_HOURS,
+    SHIFT_RESET_HOURS,
+};
+use crate::server::hos::model::CycleRule;
+use crate::server::routing::provider::DistanceDuration;
+use crate::server::trip::model::{StopType, TripStop};
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+pub const FUEL_INTERVAL_MILES: f64 = 1000.0;
+pub const FUEL_STOP_MINUTES: i64 = 30;
+pub const PICKUP_MINUTES: i64 = 60;
+pub const DROPOFF_MINUTES: i64 = 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LegEnd {
+    Pickup,
+    Dropoff,
+    Waypoint,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Leg {
+    pub from: String,
+    pub to: String,
+    pub distance_miles: f64,
+    pub duration_minutes: i64,
+    pub ends_at: LegEnd,
+    #[serde(default)]
+    pub window_start: Option<DateTime<Utc>>, // Appointment window at `to`
+    #[serde(default)]
+    pub window_end: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlannedStop {
+    pub location: String,
+    pub stop_type: StopType,
+    pub arrival: DateTime<Utc>,
+    pub duration_minutes: u64,
+    pub mile_marker: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Itinerary {
+    pub stops: Vec<PlannedStop>,
+    pub total_distance_miles: f64,
+    pub driving_minutes: i64,
+    pub departure: DateTime<Utc>,
+    pub arrival: DateTime<Utc>,
+    #[serde(default)]
+    pub missed_windows: Vec<usize>, // Legs reaching their stop after its window closed
+}
+
+struct Clock {
+    now: DateTime<Utc>,
+    shift_start: Option<DateTime<Utc>>,
+    driving: Duration,
+    since_break: Duration,
+    cycle_used: Duration,
+    miles: f64,
+    miles_since_fuel: f64,
+    stops: Vec<PlannedStop>,
+}
+
+impl Clock {
+    fn stop(&mut self, location: String, stop_type: StopType, minutes: i64) {
+        self.stops.push(PlannedStop {
+            location,
+            stop_type,
+            arrival: self.now,
+            duration_minutes: minutes as u64,
+            mile_marker: self.miles,
+        });
+        self.now = self.now + Duration::minutes(minutes);
+    }
+
+    // On-duty work that is not driving; also satisfies the 30-minute break.
+    fn work(&mut self, location: String, stop_type: StopType, minutes: i64) {
+        self.shift_start.get_or_insert(self.now);
+        self.cycle_used = self.cycle_used + Duration::minutes(minutes);
+        if minutes >= BREAK_MINUTES {
+            self.since_break = Duration::zero();
+        }
+        self.stop(location, stop_type, minutes);
+    }
+
+    fn rest(&mut self, location: String, stop_type: StopType, hours: i64) {
+        self.stop(location, stop_type, hours * 60);
+        self.shift_start = None;
+        self.driving = Duration::zero();
+        self.since_break = Duration::zero();
+    }
+
+    // Off duty until an appointment; long enough waits reset the shift or cycle.
+    fn wait(&mut self, location: String, minutes: i64) {
+        self.stop(location, StopType::Wait, minutes);
+        if minutes >= RESTART_HOURS * 60 {
+            self.cycle_used = Duration::zero();
+        }
+        if minutes >= SHIFT_RESET_HOURS * 60 {
+            self.shift_start = None;
+            self.driving = Duration::zero();
+        }
+        if minutes >= BREAK_MINUTES {
+            self.since_break = Duration::zero();
+        }
+    }
+
+    fn shift_left(&self) -> Duration {
+        let window_left = match self.shift_start {
+            Some(start) => start + Duration::hours(DUTY_WINDOW_HOURS) - self.now,
+            None => Duration::hours(DUTY_WINDOW_HOURS),
+        };
+        (Duration::hours(MAX_DRIVING_HOURS) - self.driving).min(window_left)
+    }
+
+    fn drive_left(&self, rule: CycleRule) -> Duration {
+        self.shift_left()
+            .min(Duration::hours(BREAK_AFTER_DRIVING_HOURS) - self.since_break)
+            .min(rule.limit() - self.cycle_used)
+    }
+}
+
+fn en_route(leg: &Leg, covered: f64) -> String {
+    format!("Mile {:.0} from {} toward {}", covered, leg.from, leg.to)
+}
+
+/// Lays out the legs of a trip as driving time broken up by the stops HOS rules
+/// require. The driver is assumed to start on a fresh shift with `cycle_used` hours
+/// already on the clock.
+pub fn plan_itinerary(
+    legs: &[Leg],
+    rule: CycleRule,
+    cycle_used: Duration,
+    departure: DateTime<Utc>,
+) -> Itinerary {
+    let mut clock = Clock {
+        now: departure,
+        shift_start: None,
+        driving: Duration::zero(),
+        since_break: Duration::zero(),
+        cycle_used,
+        miles: 0.0,
+        miles_since_fuel: 0.0,
+        stops: Vec::new(),
+    };
+    let mut driving_minutes = 0;
+    let mut missed_windows = Vec::new();
+
+    for (index, leg) in legs.iter().enumerate() {
+        // Hops under a minute still take one, so their miles are not lost.
+        let duration_minutes = if leg.distance_miles > 0.0 {
+            leg.duration_minutes.max(1)
+        } else {
+            leg.duration_minutes
+        };
+        let speed = if duration_minutes > 0 {
+            leg.distance_miles / duration_minutes as f64 // Miles per minute
+        } else {
+            0.0
+        };
+        let mut covered = 0.0;
+        let mut minutes_left = duration_minutes;
+
+        while minutes_left > 0 {
+            if rule.limit() - clock.cycle_used < Duration::minutes(1) {
+                clock.rest(en_route(leg, covered), StopType::Restart, RESTART_HOURS);
+                clock.cycle_used = Duration::zero();
+                continue;
+            }
+            if clock.shift_left() <= Duration::zero() {
+                clock.rest(en_route(leg, covered), StopType::Rest, SHIFT_RESET_HOURS);
+                continue;
+            }
+            if clock.miles_since_fuel >= FUEL_INTERVAL_MILES {
+                clock.work(en_route(leg, covered), StopType::Fueling, FUEL_STOP_MINUTES);
+                clock.miles_since_fuel = 0.0;
+                continue;
+            }
+            if clock.since_break >= Duration::hours(BREAK_AFTER_DRIVING_HOURS) {
+                clock.stop(en_route(leg, covered), StopType::Break, BREAK_MINUTES);
+                clock.since_break = Duration::zero();
+                continue;
+            }
+
+            let mut chunk = minutes_left.min(clock.drive_left(rule).num_minutes());
+            if speed > 0.0 {
+                let to_fuel = ((FUEL_INTERVAL_MILES - clock.miles_since_fuel) / speed).ceil();
+                chunk = chunk.min(to_fuel.max(1.0) as i64);
+            }
+            if chunk <= 0 {
+                // Less than a minute of driving left on some clock.
+                clock.rest(en_route(leg, covered), StopType::Rest, SHIFT_RESET_HOURS);
+                continue;
+            }
+
+            let miles = speed * chunk as f64;
+            clock.shift_start.get_or_insert(clock.now);
+            clock.now = clock.now + Duration::minutes(chunk);
+            clock.driving = clock.driving + Duration::minutes(chunk);
+            clock.since_break = clock.since_break + Duration::minutes(chunk);
+            clock.cycle_used = clock.cycle_used + Duration::minutes(chunk);
+            clock.miles += miles;
+            clock.miles_since_fuel += miles;
+            covered += miles;
+            minutes_left -= chunk;
+            driving_minutes += chunk;
+        }
+
+        if let Some(opens) = leg.window_start.filter(|opens| *opens > clock.now) {
+            clock.wait(leg.to.clone(), (opens - clock.now).num_minutes().max(1));
+        }
+        if leg.window_end.is_some_and(|closes| clock.now > closes) {
+            missed_windows.push(index);
+        }
+        match leg.ends_at {
+            LegEnd::Pickup => clock.work(leg.to.clone(), StopType::Pickup, PICKUP_MINUTES),
+            LegEnd::Dropoff => clock.work(leg.to.clone(), StopType::Dropoff, DROPOFF_MINUTES),
+            LegEnd::Waypoint => (),
+        }
+    }
+
+    Itinerary {
+        total_distance_miles: clock.miles,
+        driving_minutes,
+        departure,
+        arrival: clock.now,
+        stops: clock.stops,
+        missed_windows,
+    }
+}
+
+/// Legs from `start` through `stops` in order, with the travel between each pair
+/// of consecutive places.
+pub fn stop_legs(start: &str, stops: &[TripStop], travel: &[DistanceDuration]) -> Vec<Leg> {
+    let mut from = start.to_string();
+    stops
+        .iter()
+        .zip(travel)
+        .map(|(stop, travel)| Leg {
+            from: std::mem::replace(&mut from, stop.location.clone()),
+            to: stop.location.clone(),
+            distance_miles: travel.distance_miles,
+            duration_minutes: travel.duration_seconds / 60,
+            ends_at: match stop.stop_type {
+                StopType::Pickup => LegEnd::Pickup,
+                StopType::Dropoff => LegEnd::Dropoff,
+                _ => LegEnd::Waypoint,
+            },
+            window_start: stop.window_start,
+            window_end: stop.window_end,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    // Driven at 50 mph.
+    fn leg(to: &str, miles: f64, ends_at: LegEnd) -> Leg {
+        Leg {
+            from: "Yard".to_string(),
+            to: to.to_string(),
+            distance_miles: miles,
+            duration_minutes: (miles * 1.2).round() as i64,
+            ends_at,
+            window_start: None,
+            window_end: None,
+        }
+    }
+
+    fn plan(legs: &[Leg], cycle_used: i64) -> Itinerary {
+        plan_itinerary(
+            legs,
+            CycleRule::SeventyEight,
+            Duration::hours(cycle_used),
+            at(6, 0),
+        )
+    }
+
+    // Each stop as its type and hours after departure.
+    fn stops(itinerary: &Itinerary) -> Vec<(StopType, f64)> {
+        itinerary
+            .stops
+            .iter()
+            .map(|stop| {
+                let hours = (stop.arrival - itinerary.departure).num_minutes() as f64 / 60.0;
+                (stop.stop_type, hours)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn breaks_after_eight_hours_of_driving() {
+        let itinerary = plan(&[leg("B", 450.0, LegEnd::Waypoint)], 0);
+        assert_eq!(stops(&itinerary), [(StopType::Break, 8.0)]);
+        assert_eq!(itinerary.stops[0].duration_minutes, 30);
+        assert_eq!(itinerary.arrival, at(15, 30));
+    }
+
+    #[test]
+    fn rests_after_eleven_hours_of_driving() {
+        let itinerary = plan(&[leg("B", 600.0, LegEnd::Waypoint)], 0);
+        assert_eq!(
+            stops(&itinerary),
+            [(StopType::Break, 8.0), (StopType::Rest, 11.5)]
+        );
+        assert_eq!(itinerary.stops[1].duration_minutes, 600);
+        assert_eq!(itinerary.driving_minutes, 720);
+    }
+
+    #[test]
+    fn rests_when_the_duty_window_closes() {
+        // An hour's drive, six hours waiting for the pickup and an hour loading
+        // leave six hours of the 14-hour window.
+        let mut pickup = leg("A", 50.0, LegEnd::Pickup);
+        pickup.window_start = Some(at(13, 0));
+        let itinerary = plan(&[pickup, leg("B", 500.0, LegEnd::Waypoint)], 0);
+        assert_eq!(
+            stops(&itinerary),
+            [
+                (StopType::Wait, 1.0),
+                (StopType::Pickup, 7.0),
+                (StopType::Rest, 14.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn restarts_when_the_cycle_runs_out() {
+        let itinerary = plan(&[leg("B", 100.0, LegEnd::Waypoint)], 69);
+        assert_eq!(stops(&itinerary), [(StopType::Restart, 1.0)]);
+        assert_eq!(itinerary.stops[0].duration_minutes, 34 * 60);
+        assert_eq!(itinerary.arrival - itinerary.departure, Duration::hours(36));
+    }
+
+    #[test]
+    fn fuels_every_thousand_miles() {
+        let itinerary = plan(&[leg("B", 1100.0, LegEnd::Waypoint)], 0);
+        let fueling: Vec<&PlannedStop> = itinerary
+            .stops
+            .iter()
+            .filter(|stop| stop.stop_type == StopType::Fueling)
+            .collect();
+        assert_eq!(fueling.len(), 1);
+        assert!((fueling[0].mile_marker - FUEL_INTERVAL_MILES).abs() < 1.0);
+        assert_eq!(fueling[0].duration_minutes, 30);
+        assert!((itinerary.total_distance_miles - 1100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pickup_and_dropoff_take_an_hour_each() {
+        let itinerary = plan(
+            &[
+                leg("A", 50.0, LegEnd::Pickup),
+                leg("B", 100.0, LegEnd::Dropoff),
+            ],
+            0,
+        );
+        assert_eq!(
+            stops(&itinerary),
+            [(StopType::Pickup, 1.0), (StopType::Dropoff, 4.0)]
+        );
+        assert!(itinerary
+            .stops
+            .iter()
+            .all(|stop| stop.duration_minutes == 60));
+        assert_eq!(itinerary.arrival, at(11, 0));
+    }
+
+    #[test]
+    fn missed_windows_are_reported_by_leg() {
+        // Both stops are at the same place; only the second closes too early.
+        let mut first = leg("A", 50.0, LegEnd::Pickup);
+        first.window_end = Some(at(8, 0));
+        let mut second = leg("A", 0.0, LegEnd::Dropoff);
+        second.window_end = Some(at(7, 30));
+        let itinerary = plan(&[first, second], 0);
+        assert_eq!(itinerary.missed_windows, [1]);
+    }
+
+    #[test]
+    fn short_hops_keep_their_miles() {
+        let mut hop = leg("A", 0.5, LegEnd::Waypoint);
+        hop.duration_minutes = 0;
+        let itinerary = plan(&[hop], 0);
+        assert_eq!(itinerary.total_distance_miles, 0.5);
+        assert_eq!(itinerary.driving_minutes, 1);
+    }
+}
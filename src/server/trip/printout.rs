@@ -0,0 +1,884 @@
+use crate::server::hos::model::{CategoryTotals, DutyState, StatusTotals};
+use crate::server::trip::response::DailyLogSheet;
+use chrono::prelude::*;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::{Deserialize, Serialize};
+
+// US Letter, landscape, in points; SVG and PDF share the same layout.
+const PAGE_WIDTH: f64 = 792.0;
+const PAGE_HEIGHT: f64 = 612.0;
+const GRID_X: f64 = 150.0;
+const GRID_Y: f64 = 130.0;
+const GRID_WIDTH: f64 = 552.0;
+const ROW_HEIGHT: f64 = 26.0;
+const MINUTES_PER_DAY: i64 = 24 * 60;
+const REMARK_ROWS: usize = 20;
+
+const ROW_LABELS: [&str; 4] = [
+    "1. Off Duty",
+    "2. Sleeper Berth",
+    "3. Driving",
+    "4. On Duty (not driving)",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SheetFormat {
+    Svg,
+    Pdf,
+}
+
+impl SheetFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SheetFormat::Svg => "svg",
+            SheetFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            SheetFormat::Svg => "image/svg+xml",
+            SheetFormat::Pdf => "application/pdf",
+        }
+    }
+}
+
+// Everything on the form that does not come from the log itself.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SheetHeader {
+    pub driver_name: String,
+    pub carrier_name: String,
+    pub truck_number: String,
+    pub miles_driven: f64,
+    pub miles_estimated: bool, // No odometer readings; estimated from the planned route
+    pub recorded_until: Option<DateTime<Utc>>, // For a day in progress; the graph stops here
+}
+
+// A status held from `start` to `end`, in minutes after midnight of the log date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusSpan {
+    pub status: DutyState,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+#[derive(Debug, Clone)]
+enum Mark {
+    Line {
+        from: (f64, f64),
+        to: (f64, f64),
+        width: f64,
+    },
+    Text {
+        at: (f64, f64),
+        size: f64,
+        bold: bool,
+        anchor: Anchor,
+        text: String,
+    },
+    Signature {
+        at: (f64, f64),
+        size: (f64, f64),
+        data: String,
+    },
+}
+
+// Paper logs have four lines: personal conveyance is drawn as off duty and yard
+// moves as on duty, with the special category noted in the remarks.
+fn duty_row(status: DutyState) -> usize {
+    match status {
+        DutyState::OffDuty | DutyState::PersonalConveyance => 0,
+        DutyState::Sleeper => 1,
+        DutyState::Driving => 2,
+        DutyState::OnDuty | DutyState::YardMove => 3,
+    }
+}
+
+fn minute_of_day(sheet: &DailyLogSheet, index: usize) -> i64 {
+    (sheet.entries[index].time - sheet.log.log_date)
+        .num_minutes()
+        .clamp(0, MINUTES_PER_DAY)
+}
+
+/// Each entry lasts until the next one; the last runs to the end of the day.
+pub fn status_spans(sheet: &DailyLogSheet) -> Vec<StatusSpan> {
+    let mut spans: Vec<StatusSpan> = Vec::new();
+    for (index, entry) in sheet.entries.iter().enumerate() {
+        let status = entry.status;
+        let start = minute_of_day(sheet, index);
+        let end = if index + 1 < sheet.entries.len() {
+            minute_of_day(sheet, index + 1)
+        } else {
+            MINUTES_PER_DAY
+        };
+        if end > start {
+            spans.push(StatusSpan { status, start, end });
+        }
+    }
+    spans
+}
+
+/// The spans drawn on the form: those of a day in progress end at `recorded_until`.
+pub fn recorded_spans(header: &SheetHeader, sheet: &DailyLogSheet) -> Vec<StatusSpan> {
+    let until = header.recorded_until.map_or(MINUTES_PER_DAY, |until| {
+        (until - sheet.log.log_date)
+            .num_minutes()
+            .clamp(0, MINUTES_PER_DAY)
+    });
+    status_spans(sheet)
+        .into_iter()
+        .filter(|span| span.start < until)
+        .map(|span| StatusSpan {
+            end: span.end.min(until),
+            ..span
+        })
+        .collect()
+}
+
+/// Minutes per duty line.
+pub fn row_totals(spans: &[StatusSpan]) -> StatusTotals {
+    let mut totals = StatusTotals::default();
+    for span in spans {
+        totals.add(span.status, span.end - span.start);
+    }
+    totals
+}
+
+/// Minutes of personal conveyance and of yard moves, which the duty lines include
+/// in off duty and on duty.
+pub fn category_totals(spans: &[StatusSpan]) -> CategoryTotals {
+    let mut totals = CategoryTotals::default();
+    for span in spans {
+        totals.add(span.status, span.end - span.start);
+    }
+    totals
+}
+
+fn grid_x(minute: i64) -> f64 {
+    GRID_X + GRID_WIDTH * minute as f64 / MINUTES_PER_DAY as f64
+}
+
+fn row_center(row: usize) -> f64 {
+    GRID_Y + ROW_HEIGHT * (row as f64 + 0.5)
+}
+
+fn line(from: (f64, f64), to: (f64, f64), width: f64) -> Mark {
+    Mark::Line { from, to, width }
+}
+
+fn text(at: (f64, f64), size: f64, text: impl Into<String>) -> Mark {
+    Mark::Text {
+        at,
+        size,
+        bold: false,
+        anchor: Anchor::Start,
+        text: text.into(),
+    }
+}
+
+fn bold(at: (f64, f64), size: f64, text: impl Into<String>) -> Mark {
+    Mark::Text {
+        at,
+        size,
+        bold: true,
+        anchor: Anchor::Start,
+        text: text.into(),
+    }
+}
+
+fn anchored(at: (f64, f64), size: f64, anchor: Anchor, text: impl Into<String>) -> Mark {
+    Mark::Text {
+        at,
+        size,
+        bold: false,
+        anchor,
+        text: text.into(),
+    }
+}
+
+fn hour_label(hour: i64) -> String {
+    match hour {
+        0 | 24 => "Mid".to_string(),
+        12 => "Noon".to_string(),
+        hour => (hour % 12).to_string(),
+    }
+}
+
+fn hours(minutes: i64) -> String {
+    format!("{:.2}", minutes as f64 / 60.0)
+}
+
+fn layout(header: &SheetHeader, sheet: &DailyLogSheet) -> Vec<Mark> {
+    let mut marks = Vec::new();
+    let spans = recorded_spans(header, sheet);
+    let totals = row_totals(&spans);
+    let row_minutes = [
+        totals.off_duty,
+        totals.sleeper,
+        totals.driving,
+        totals.on_duty,
+    ];
+    let grid_bottom = GRID_Y + ROW_HEIGHT * 4.0;
+    let grid_right = GRID_X + GRID_WIDTH;
+
+    marks.push(bold((36.0, 40.0), 16.0, "Driver's Daily Log (24 hours)"));
+    marks.push(anchored(
+        (PAGE_WIDTH - 36.0, 40.0),
+        10.0,
+        Anchor::End,
+        format!("Date: {}", sheet.log.log_date.format("%m/%d/%Y")),
+    ));
+    marks.push(text(
+        (36.0, 70.0),
+        10.0,
+        format!("Carrier: {}", header.carrier_name),
+    ));
+    marks.push(text(
+        (300.0, 70.0),
+        10.0,
+        format!("Driver: {}", header.driver_name),
+    ));
+    marks.push(text(
+        (540.0, 70.0),
+        10.0,
+        format!("Truck No.: {}", header.truck_number),
+    ));
+    marks.push(text(
+        (36.0, 88.0),
+        10.0,
+        format!(
+            "Total Miles Driving Today: {:.1}{}",
+            header.miles_driven,
+            if header.miles_estimated {
+                " (estimated)"
+            } else {
+                ""
+            }
+        ),
+    ));
+
+    // Grid: hour columns with quarter-hour ticks, one row per duty status.
+    for hour in 0..=24 {
+        let x = grid_x(hour * 60);
+        marks.push(anchored(
+            (x, GRID_Y - 6.0),
+            7.0,
+            Anchor::Middle,
+            hour_label(hour),
+        ));
+        marks.push(line((x, GRID_Y), (x, grid_bottom), 0.5));
+    }
+    for row in 0..=4 {
+        let y = GRID_Y + ROW_HEIGHT * row as f64;
+        marks.push(line((GRID_X, y), (grid_right, y), 1.0));
+    }
+    for row in 0..4 {
+        let top = GRID_Y + ROW_HEIGHT * row as f64;
+        for quarter in (0..MINUTES_PER_DAY).step_by(15).filter(|m| m % 60 != 0) {
+            let tick = if quarter % 60 == 30 { 8.0 } else { 4.0 };
+            marks.push(line(
+                (grid_x(quarter), top),
+                (grid_x(quarter), top + tick),
+                0.5,
+            ));
+        }
+        marks.push(text((36.0, row_center(row) + 3.0), 8.0, ROW_LABELS[row]));
+        marks.push(text(
+            (grid_right + 10.0, row_center(row) + 3.0),
+            9.0,
+            hours(row_minutes[row]),
+        ));
+    }
+    marks.push(text((grid_right + 10.0, GRID_Y - 6.0), 7.0, "Total Hours"));
+    marks.push(bold(
+        (grid_right + 10.0, grid_bottom + 14.0),
+        9.0,
+        format!("= {}", hours(totals.total())),
+    ));
+    let categories = category_totals(&spans);
+    if !categories.is_empty() {
+        marks.push(text(
+            (36.0, grid_bottom + 24.0),
+            8.0,
+            format!(
+                "Personal conveyance: {}   Yard moves: {}",
+                hours(categories.personal_conveyance),
+                hours(categories.yard_moves)
+            ),
+        ));
+    }
+
+    // Duty line, with vertical connectors at each change of status.
+    let mut previous: Option<&StatusSpan> = None;
+    for span in &spans {
+        let y = row_center(duty_row(span.status));
+        if let Some(previous) = previous {
+            let previous_y = row_center(duty_row(previous.status));
+            if previous.end == span.start && previous_y != y {
+                marks.push(line(
+                    (grid_x(span.start), previous_y),
+                    (grid_x(span.start), y),
+                    2.5,
+                ));
+            }
+        }
+        marks.push(line((grid_x(span.start), y), (grid_x(span.end), y), 2.5));
+        previous = Some(span);
+    }
+
+    // Remarks: a flag under the grid at each change, with the location it happened at.
+    let remarks_y = grid_bottom + 40.0;
+    marks.push(bold((36.0, remarks_y), 10.0, "Remarks"));
+    let mut remarks = Vec::new();
+    for (index, entry) in sheet.entries.iter().enumerate() {
+        let minute = minute_of_day(sheet, index);
+        marks.push(line(
+            (grid_x(minute), grid_bottom),
+            (grid_x(minute), grid_bottom + 8.0),
+            0.75,
+        ));
+
+        let mut remark = format!(
+            "{}  {} - {}",
+            entry.time.format("%H:%M"),
+            entry.status.label(),
+            entry.location
+        );
+        if let Some(note) = entry
+            .remarks
+            .as_ref()
+            .filter(|note| **note != entry.location)
+        {
+            remark.push_str(&format!(" ({})", note));
+        }
+        remarks.push(remark);
+    }
+    let hidden = remarks.len().saturating_sub(REMARK_ROWS * 2);
+    for (index, remark) in remarks.iter().take(REMARK_ROWS * 2).enumerate() {
+        let column = (index / REMARK_ROWS) as f64;
+        let row = (index % REMARK_ROWS) as f64;
+        marks.push(text(
+            (36.0 + column * 360.0, remarks_y + 16.0 + row * 11.0),
+            8.0,
+            remark.clone(),
+        ));
+    }
+    if hidden > 0 {
+        marks.push(text(
+            (36.0, remarks_y + 16.0 + REMARK_ROWS as f64 * 11.0),
+            8.0,
+            format!("... and {} more changes of duty status", hidden),
+        ));
+    }
+
+    // Certification and signature.
+    let signature_y = PAGE_HEIGHT - 40.0;
+    marks.push(text(
+        (36.0, signature_y),
+        8.0,
+        "I certify that these entries are true and correct.",
+    ));
+    marks.push(line(
+        (450.0, signature_y),
+        (PAGE_WIDTH - 36.0, signature_y),
+        0.75,
+    ));
+    marks.push(text((450.0, signature_y + 12.0), 8.0, "Driver's signature"));
+    if let Some(signature) = sheet.log.signature.as_ref().filter(|s| !s.is_empty()) {
+        let data = if signature.starts_with("data:") {
+            signature.clone()
+        } else {
+            format!("data:image/png;base64,{}", signature)
+        };
+        marks.push(Mark::Signature {
+            at: (450.0, signature_y - 50.0),
+            size: (PAGE_WIDTH - 486.0, 48.0),
+            data,
+        });
+    }
+
+    marks
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the sheet as a standalone SVG document.
+pub fn render_svg(header: &SheetHeader, sheet: &DailyLogSheet) -> String {
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Helvetica, Arial, sans-serif">"#,
+        w = PAGE_WIDTH,
+        h = PAGE_HEIGHT
+    );
+    svg.push_str(r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    for mark in layout(header, sheet) {
+        match mark {
+            Mark::Line { from, to, width } => svg.push_str(&format!(
+                r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="black" stroke-width="{}"/>"#,
+                from.0, from.1, to.0, to.1, width
+            )),
+            Mark::Text {
+                at,
+                size,
+                bold,
+                anchor,
+                text,
+            } => svg.push_str(&format!(
+                r#"<text x="{:.2}" y="{:.2}" font-size="{}" font-weight="{}" text-anchor="{}">{}</text>"#,
+                at.0,
+                at.1,
+                size,
+                if bold { "bold" } else { "normal" },
+                match anchor {
+                    Anchor::Start => "start",
+                    Anchor::Middle => "middle",
+                    Anchor::End => "end",
+                },
+                escape_xml(&text)
+            )),
+            Mark::Signature { at, size, data } => svg.push_str(&format!(
+                r#"<image x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" preserveAspectRatio="xMinYMax meet" href="{}"/>"#,
+                at.0,
+                at.1,
+                size.0,
+                size.1,
+                escape_xml(&data)
+            )),
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+// PDF strings only take the standard fonts' ASCII range here.
+fn escape_pdf(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' | '(' | ')' => format!("\\{}", c),
+            c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+// Signatures are rasterized at this many pixels per unit of the drawing.
+const SIGNATURE_SCALE: f64 = 2.0;
+
+// A drawn signature as a 1-bit image, rows padded to whole bytes, with the size of
+// the drawing it came from.
+struct SignatureImage {
+    width: f64,
+    height: f64,
+    columns: usize,
+    rows: usize,
+    mask: Vec<u8>,
+}
+
+fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let start = element.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let end = start + element[start..].find('"')?;
+    Some(&element[start..end])
+}
+
+// The strokes of a signature pad path: `M` starts a stroke and `L` extends it.
+fn path_strokes(path: &str) -> Option<Vec<Vec<(f64, f64)>>> {
+    let path = path.trim();
+    if !path.starts_with('M') || !path.chars().all(|c| "ML0123456789.-, ".contains(c)) {
+        return None;
+    }
+    let mut strokes: Vec<Vec<(f64, f64)>> = Vec::new();
+    for (index, command) in path.match_indices(['M', 'L']) {
+        let rest = &path[index + 1..];
+        let end = rest.find(['M', 'L']).unwrap_or(rest.len());
+        let mut numbers = rest[..end]
+            .split([' ', ','])
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<f64>().ok());
+        let point = (numbers.next()??, numbers.next()??);
+        if numbers.next().is_some() {
+            return None;
+        }
+        match command {
+            "M" => strokes.push(vec![point]),
+            _ => strokes.last_mut()?.push(point),
+        }
+    }
+    Some(strokes)
+}
+
+fn unbase64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let (mut bits, mut count) = (0u32, 0);
+    for c in text.bytes().filter(|&c| c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        bits = bits << 6 | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+// Rasterizes a signature drawn on the signature pad, which is stored as an SVG of
+// stroked paths. Other images give `None`.
+fn signature_image(data: &str) -> Option<SignatureImage> {
+    let svg =
+        String::from_utf8(unbase64(data.strip_prefix("data:image/svg+xml;base64,")?)?).ok()?;
+    let root = &svg[svg.find("<svg")?..];
+    let root = &root[..root.find('>')?];
+    let mut view_box = attribute(root, "viewBox")?
+        .split(' ')
+        .map(|n| n.parse::<f64>().ok());
+    let (left, top) = (view_box.next()??, view_box.next()??);
+    let (width, height) = (view_box.next()??, view_box.next()??);
+    let radius = svg
+        .find(" stroke-width=\"")
+        .and_then(|at| attribute(&svg[at..], "stroke-width"))
+        .and_then(|w| w.parse::<f64>().ok())
+        .unwrap_or(1.0)
+        / 2.0;
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+
+    let columns = (width * SIGNATURE_SCALE).ceil() as usize;
+    let rows = (height * SIGNATURE_SCALE).ceil() as usize;
+    let row_bytes = columns.div_ceil(8);
+    let mut mask = vec![0u8; row_bytes * rows];
+    for element in svg.split("<path").skip(1) {
+        for stroke in path_strokes(attribute(element, "d")?)? {
+            let segments = stroke.windows(2).map(|s| (s[0], s[1]));
+            let dot = (stroke.len() == 1).then(|| (stroke[0], stroke[0]));
+            for ((x1, y1), (x2, y2)) in segments.chain(dot) {
+                let (x1, y1, x2, y2) = (x1 - left, y1 - top, x2 - left, y2 - top);
+                let pixel = |v: f64| (v * SIGNATURE_SCALE).floor().max(0.0) as usize;
+                for row in pixel(y1.min(y2) - radius)..=pixel(y1.max(y2) + radius).min(rows - 1) {
+                    for column in
+                        pixel(x1.min(x2) - radius)..=pixel(x1.max(x2) + radius).min(columns - 1)
+                    {
+                        // Distance from the pixel's center to the segment.
+                        let (x, y) = (
+                            (column as f64 + 0.5) / SIGNATURE_SCALE,
+                            (row as f64 + 0.5) / SIGNATURE_SCALE,
+                        );
+                        let (dx, dy) = (x2 - x1, y2 - y1);
+                        let length = dx * dx + dy * dy;
+                        let t = if length > 0.0 {
+                            (((x - x1) * dx + (y - y1) * dy) / length).clamp(0.0, 1.0)
+                        } else {
+                            0.0
+                        };
+                        let (nx, ny) = (x1 + t * dx - x, y1 + t * dy - y);
+                        if nx * nx + ny * ny <= radius * radius {
+                            mask[row * row_bytes + column / 8] |= 0x80 >> (column % 8);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Some(SignatureImage {
+        width,
+        height,
+        columns,
+        rows,
+        mask,
+    })
+}
+
+/// Renders the sheet as a single-page PDF using the built-in Helvetica fonts,
+/// with the driver's signature embedded as an image. `None` if the sheet is
+/// signed with an image that can't be embedded.
+pub fn render_pdf(header: &SheetHeader, sheet: &DailyLogSheet) -> Option<Vec<u8>> {
+    let mut content = String::new();
+    let mut signature = None;
+    for mark in layout(header, sheet) {
+        match mark {
+            Mark::Line { from, to, width } => content.push_str(&format!(
+                "{} w {:.2} {:.2} m {:.2} {:.2} l S\n",
+                width,
+                from.0,
+                PAGE_HEIGHT - from.1,
+                to.0,
+                PAGE_HEIGHT - to.1
+            )),
+            Mark::Text {
+                at,
+                size,
+                bold,
+                anchor,
+                text,
+            } => {
+                // Helvetica averages about half an em per character.
+                let width = text.chars().count() as f64 * size * 0.5;
+                let x = match anchor {
+                    Anchor::Start => at.0,
+                    Anchor::Middle => at.0 - width / 2.0,
+                    Anchor::End => at.0 - width,
+                };
+                content.push_str(&format!(
+                    "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+                    if bold { "F2" } else { "F1" },
+                    size,
+                    x,
+                    PAGE_HEIGHT - at.1,
+                    escape_pdf(&text)
+                ));
+            }
+            Mark::Signature { at, size, data } => {
+                let image = signature_image(&data)?;
+                // Fit the image to the box, keeping it on the signature line.
+                let scale = (size.0 / image.width).min(size.1 / image.height);
+                content.push_str(&format!(
+                    "q 0.07 0.09 0.15 rg {:.2} 0 0 {:.2} {:.2} {:.2} cm /Sig Do Q\n",
+                    image.width * scale,
+                    image.height * scale,
+                    at.0,
+                    PAGE_HEIGHT - (at.1 + size.1)
+                ));
+                signature = Some(image);
+            }
+        }
+    }
+
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 4 0 R /F2 5 0 R >>{} >> /Contents 6 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            if signature.is_some() {
+                " /XObject << /Sig 7 0 R >>"
+            } else {
+                ""
+            }
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ),
+    ];
+    if let Some(image) = signature {
+        // An image mask: set bits are painted in the fill color, the rest is left clear.
+        let hex: String = image
+            .mask
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        objects.push(format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ImageMask true /BitsPerComponent 1 /Decode [1 0] /Filter /ASCIIHexDecode /Length {} >>\nstream\n{}>\nendstream",
+            image.columns,
+            image.rows,
+            hex.len() + 1,
+            hex
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", index + 1, object));
+    }
+
+    let xref = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    ));
+    Some(pdf.into_bytes())
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Wraps a rendered file in a `data:` URL the browser can download directly.
+pub fn data_url(mime_type: &str, bytes: &[u8]) -> String {
+    format!("data:{};base64,{}", mime_type, base64(bytes))
+}
+
+/// A QR code of `text` as an SVG `data:` URL, or `None` if it is too long to encode.
+pub fn qr_code(text: &str) -> Option<String> {
+    let code = QrCode::new(text.as_bytes()).ok()?;
+    let image = code.render::<svg::Color>().min_dimensions(240, 240).build();
+    Some(data_url("image/svg+xml", image.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::trip::model::{DailyLog, LogEntry};
+    use bson::oid::ObjectId;
+
+    // A signature as the signature pad stores it.
+    const SIGNATURE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="400" height="120" viewBox="0 0 400 120"><g fill="none" stroke="#111827" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"><path d="M10.0 10.0 L30.0 10.0"/><path d="M50.0 60.0"/></g></svg>"##;
+
+    fn header() -> SheetHeader {
+        SheetHeader {
+            driver_name: "Jo Driver".into(),
+            carrier_name: "Acme Freight".into(),
+            truck_number: "T-42".into(),
+            miles_driven: 120.0,
+            miles_estimated: false,
+            recorded_until: None,
+        }
+    }
+
+    fn sheet(signature: Option<String>) -> DailyLogSheet {
+        let log_date = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+        let log = DailyLog {
+            id: ObjectId::new(),
+            driver_id: ObjectId::new(),
+            trip_id: ObjectId::new(),
+            log_date,
+            signature,
+            certified_at: None,
+            entries_hash: None,
+            created_at: log_date,
+            updated_at: log_date,
+        };
+        let entries = vec![LogEntry {
+            id: ObjectId::new(),
+            log_id: log.id,
+            time: log_date,
+            status: DutyState::OffDuty,
+            location: "Chicago, IL".into(),
+            remarks: None,
+            created_at: log_date,
+            updated_at: log_date,
+        }];
+        DailyLogSheet { log, entries }
+    }
+
+    fn ink(image: &SignatureImage, x: f64, y: f64) -> bool {
+        let (column, row) = (
+            (x * SIGNATURE_SCALE) as usize,
+            (y * SIGNATURE_SCALE) as usize,
+        );
+        image.mask[row * image.columns.div_ceil(8) + column / 8] & (0x80 >> (column % 8)) != 0
+    }
+
+    #[test]
+    fn signature_strokes_are_rasterized() {
+        let image = signature_image(&data_url("image/svg+xml", SIGNATURE.as_bytes())).unwrap();
+        assert_eq!((image.columns, image.rows), (800, 240));
+        assert!(ink(&image, 20.0, 10.0));
+        assert!(!ink(&image, 20.0, 12.0));
+        assert!(!ink(&image, 35.0, 10.0));
+        // A tap without a drag still leaves a dot.
+        assert!(ink(&image, 50.0, 60.0));
+    }
+
+    #[test]
+    fn signature_is_embedded_in_the_pdf() {
+        let signature = data_url("image/svg+xml", SIGNATURE.as_bytes());
+        let pdf =
+            String::from_utf8(render_pdf(&header(), &sheet(Some(signature))).unwrap()).unwrap();
+        assert!(pdf.contains("/XObject << /Sig 7 0 R >>"));
+        assert!(pdf.contains("/Subtype /Image /Width 800 /Height 240 /ImageMask true"));
+        assert!(pdf.contains("/Sig Do"));
+        assert!(pdf.contains("/Size 8 "));
+    }
+
+    #[test]
+    fn unsigned_pdf_has_no_image() {
+        let pdf = String::from_utf8(render_pdf(&header(), &sheet(None)).unwrap()).unwrap();
+        assert!(!pdf.contains("/XObject"));
+        assert!(pdf.contains("/Size 7 "));
+    }
+
+    #[test]
+    fn other_signatures_are_not_rendered_as_pdf() {
+        let png = data_url("image/png", b"\x89PNG\r\n\x1a\n");
+        assert!(render_pdf(&header(), &sheet(Some(png))).is_none());
+        let scribble = r#"<svg viewBox="0 0 10 10"><path d="M1 1 C2 2 3 3 4 4"/></svg>"#;
+        let scribble = data_url("image/svg+xml", scribble.as_bytes());
+        assert!(render_pdf(&header(), &sheet(Some(scribble))).is_none());
+    }
+
+    #[test]
+    fn categories_are_counted_on_their_duty_lines() {
+        let span = |status, start, end| StatusSpan { status, start, end };
+        let spans = [
+            span(DutyState::OffDuty, 0, 360),
+            span(DutyState::PersonalConveyance, 360, 390),
+            span(DutyState::Driving, 390, 900),
+            span(DutyState::YardMove, 900, 915),
+            span(DutyState::OnDuty, 915, 960),
+            span(DutyState::Sleeper, 960, 1440),
+        ];
+        assert_eq!(
+            row_totals(&spans),
+            StatusTotals {
+                off_duty: 390,
+                sleeper: 480,
+                driving: 510,
+                on_duty: 60,
+            }
+        );
+        assert_eq!(row_totals(&spans).total(), MINUTES_PER_DAY);
+        assert_eq!(
+            category_totals(&spans),
+            CategoryTotals {
+                personal_conveyance: 30,
+                yard_moves: 15,
+            }
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::server::trip::model::Waypoint;
+use crate::server::hos::model::DutyState;
+use crate::server::trip::model::{StopType, TripStatus, TripStop, Waypoint};
+use crate::server::trip::printout::SheetFormat;
 use bson::oid::ObjectId;
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -7,18 +9,15 @@ use serde::{Deserialize, Serialize};
 pub struct StoreTripRequest {
     pub token: String,
     pub current_location: String,
-    pub pickup_location: String,
-    pub dropoff_location: String,
-    pub cycle_used_hours: f64,
-    pub status: String,
-    pub route_url: String,
+    pub stops: Vec<TripStop>, // In visiting order, from the first pickup to the last drop-off
+    pub status: TripStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdateTripRequest {
     pub token: String,
     pub trip_id: String,
-    pub status: Option<String>,
+    pub status: Option<TripStatus>,
     pub current_location: Option<String>,
 }
 
@@ -27,16 +26,40 @@ pub struct StoreFuelingStopRequest {
     pub token: String,
     pub trip_id: String,
     pub location: String,
+    pub jurisdiction: String,
     pub fuel_amount: f64,
+    pub price_per_gallon: Option<f64>,
+    pub odometer: Option<f64>,
+    pub receipt_reference: Option<String>,
+    pub purchased_at: Option<DateTime<Utc>>, // Now when not given
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListFuelingStopsRequest {
+    pub token: String,
+    pub trip_id: Option<String>, // All of the driver's trips when not given
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateFuelingStopRequest {
+    pub token: String,
+    pub fueling_stop_id: String,
+    pub location: String,
+    pub jurisdiction: String,
+    pub fuel_amount: f64,
+    pub price_per_gallon: Option<f64>,
+    pub odometer: Option<f64>,
+    pub receipt_reference: Option<String>,
+    pub purchased_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StoreEldLogRequest {
     pub token: String,
     pub trip_id: String,
-    pub status: String,
-    pub start_hour: f64,
-    pub end_hour: f64,
+    pub status: DutyState,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
     pub driving_hours: f64,
     pub on_duty_hours: f64,
     pub off_duty_hours: f64,
@@ -69,8 +92,9 @@ pub struct StoreRouteStopRequest {
     pub token: String,
     pub route_id: String,
     pub location: String,
-    pub stop_type: String,
+    pub stop_type: StopType,
     pub duration_minutes: u64,
+    pub scheduled_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -86,11 +110,45 @@ pub struct StoreLogEntryRequest {
     pub token: String,
     pub log_id: String,
     pub time: DateTime<Utc>,
-    pub status: String,
+    pub status: DutyState,
     pub location: String,
     pub remarks: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanTripRequest {
+    pub token: String,
+    pub trip_id: String,
+    #[serde(default)]
+    pub optimize: bool, // Reorder the stops between the first and the last
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GenerateDailyLogsRequest {
+    pub token: String,
+    pub trip_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetDailyLogsRequest {
+    pub token: String,
+    pub trip_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CertifyDailyLogRequest {
+    pub token: String,
+    pub log_id: String,
+    pub signature: String, // Data URL of the drawn signature
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportDailyLogRequest {
+    pub token: String,
+    pub log_id: String,
+    pub format: SheetFormat,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DeleteTripRequest {
     pub token: String,
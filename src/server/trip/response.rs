@@ -1,3 +1,7 @@
+use crate::server::hos::category::CategoryWarning;
+use crate::server::hos::model::{HosViolation, Restart};
+use crate::server::trip::model::{DailyLog, LogEntry};
+use crate::server::trip::planner::Itinerary;
 use bson::oid::ObjectId;
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -10,6 +14,9 @@ pub struct LogEntryResponse {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TripResponse {
     pub id: ObjectId,
+    pub cycle_used_hours: f64,
+    pub cycle_remaining_hours: f64,
+    pub cycle_exceeded: bool,
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FuelingStopResponse {
@@ -17,7 +24,11 @@ pub struct FuelingStopResponse {
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EldLogResponse {
-    pub id: ObjectId,
+    pub id: ObjectId, // Duty event that starts the recorded range
+    pub violations: Vec<HosViolation>,
+    pub restarts: Vec<Restart>,
+    pub uncertified_days: usize, // Certified days the entry touched
+    pub warnings: Vec<CategoryWarning>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -36,3 +47,21 @@ pub struct RouteStopResponse {
 pub struct DailyLogResponse {
     pub id: ObjectId,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanTripResponse {
+    pub route_id: ObjectId,
+    pub itinerary: Itinerary,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyLogSheet {
+    pub log: DailyLog,
+    pub entries: Vec<LogEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyLogExportResponse {
+    pub file_name: String,
+    pub data_url: String,
+}
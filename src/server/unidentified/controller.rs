@@ -0,0 +1,400 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use bson::doc;
+use dioxus::prelude::*;
+
+use crate::server::auth::controller::auth;
+use crate::server::auth::model::User;
+use crate::server::common::response::SuccessResponse;
+use crate::server::hos::model::{DutyEdit, DutyEditStatus, DutyEventOrigin, DutyState};
+use crate::server::hos::request::ReviewDutyEditRequest;
+use crate::server::hos::stream::events_in_range;
+use crate::server::unidentified::model::*;
+use crate::server::unidentified::request::{
+    AssignUnidentifiedDrivingRequest, GetUnidentifiedDrivingRequest,
+    ReviewUnidentifiedDrivingRequest,
+};
+
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use futures_util::TryStreamExt;
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::server::hos::controller::{apply_duty_edit, load_duty_events, review_duty_edit},
+    crate::server::telematics::controller::carrier_device_ids,
+};
+
+fn comment(text: Option<String>) -> Option<String> {
+    text.map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+// Files driving an ELD recorded while no driver was logged in to it.
+#[cfg(feature = "server")]
+pub async fn record_unidentified_driving(
+    eld_device_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    start_location: &str,
+    end_location: &str,
+    start_odometer: Option<f64>,
+    end_odometer: Option<f64>,
+) -> Result<UnidentifiedDriving, ServerFnError> {
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<UnidentifiedDriving>("unidentified_driving");
+
+    let now = Utc::now();
+    let record = UnidentifiedDriving {
+        id: ObjectId::new(),
+        eld_device_id: eld_device_id.to_string(),
+        start,
+        end,
+        start_location: start_location.to_string(),
+        end_location: end_location.to_string(),
+        start_odometer,
+        end_odometer,
+        status: UnidentifiedStatus::Unassigned,
+        driver_id: None,
+        rejected_by: Vec::new(),
+        decisions: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    collection.insert_one(record.clone()).await?;
+    Ok(record)
+}
+
+// IDs of the devices of the user's carrier.
+#[cfg(feature = "server")]
+async fn carrier_devices(user: &User) -> Result<Vec<String>, ServerFnError> {
+    match user.carrier_id {
+        Some(carrier_id) => carrier_device_ids(carrier_id).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+// Records the driver's answer to a carrier assignment, once the duty edit that
+// carried it has been reviewed.
+#[cfg(feature = "server")]
+pub async fn settle_assignment(edit: &DutyEdit) -> Result<(), ServerFnError> {
+    let Some(record_id) = edit.unidentified_id else {
+        return Ok(());
+    };
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<UnidentifiedDriving>("unidentified_driving");
+
+    let Some(mut record) = collection
+        .find_one(doc! { "_id": record_id, "status": "Pending" })
+        .await?
+    else {
+        return Ok(());
+    };
+
+    let accepted = edit.status == DutyEditStatus::Accepted;
+    if accepted {
+        record.status = UnidentifiedStatus::Assigned;
+    } else {
+        record.status = UnidentifiedStatus::Unassigned;
+        record.driver_id = None;
+        record.rejected_by.push(edit.driver_id);
+    }
+    record.decisions.push(UnidentifiedDecision {
+        action: if accepted {
+            UnidentifiedAction::Accepted
+        } else {
+            UnidentifiedAction::Rejected
+        },
+        by: edit.driver_id,
+        driver_id: Some(edit.driver_id),
+        comment: edit.review_comment.clone(),
+        at: Utc::now(),
+    });
+    record.updated_at = Utc::now();
+
+    collection
+        .replace_one(doc! { "_id": record.id, "status": "Pending" }, record)
+        .await?;
+    Ok(())
+}
+
+/// Drivers get the unassigned driving on their carrier's devices that they have
+/// not rejected, plus assignments waiting on them; carrier users get everything
+/// recorded on the carrier's devices.
+#[server]
+pub async fn get_unidentified_driving(
+    req: GetUnidentifiedDrivingRequest,
+) -> Result<SuccessResponse<Vec<UnidentifiedDriving>>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<UnidentifiedDriving>("unidentified_driving");
+
+    let devices = carrier_devices(&user).await?;
+    let filter = if user.managed_carrier().is_some() {
+        doc! { "eldDeviceId": { "$in": devices } }
+    } else {
+        doc! { "$or": [
+            {
+                "eldDeviceId": { "$in": devices },
+                "status": "Unassigned",
+                "rejectedBy": { "$ne": user.id },
+            },
+            { "status": "Pending", "driverId": user.id },
+        ]}
+    };
+    let records: Vec<UnidentifiedDriving> = collection
+        .find(filter)
+        .sort(doc! { "start": -1 })
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?
+        .try_collect()
+        .await
+        .map_err(|e| ServerFnError::new(&e.to_string()))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: records,
+    })
+}
+
+/// A driver accepts unidentified driving into their log or rejects it. Accepted
+/// driving goes through an edit, so the log keeps the records it replaced.
+#[server]
+pub async fn review_unidentified_driving(
+    req: ReviewUnidentifiedDrivingRequest,
+) -> Result<SuccessResponse<UnidentifiedDriving>, ServerFnError> {
+    let user = auth(req.token.clone())
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<UnidentifiedDriving>("unidentified_driving");
+    let edit_collection = db.collection::<DutyEdit>("duty_edits");
+
+    let record_id = ObjectId::parse_str(&req.record_id)?;
+    let mut record = collection
+        .find_one(doc! { "_id": record_id })
+        .await?
+        .ok_or(ServerFnError::new("Unidentified driving not found"))?;
+    let comment = comment(req.comment);
+
+    match record.status {
+        // The carrier's assignment is answered through its duty edit.
+        UnidentifiedStatus::Pending if record.driver_id == Some(user.id) => {
+            let edit = edit_collection
+                .find_one(doc! {
+                    "unidentifiedId": record.id,
+                    "driverId": user.id,
+                    "status": "Pending",
+                })
+                .await?
+                .ok_or(ServerFnError::new("Assignment not found"))?;
+            review_duty_edit(ReviewDutyEditRequest {
+                token: req.token,
+                edit_id: edit.id.to_hex(),
+                accept: req.accept,
+                comment,
+            })
+            .await?;
+            record = collection
+                .find_one(doc! { "_id": record.id })
+                .await?
+                .ok_or(ServerFnError::new("Unidentified driving not found"))?;
+        }
+        UnidentifiedStatus::Unassigned => {
+            if !carrier_devices(&user)
+                .await?
+                .contains(&record.eld_device_id)
+            {
+                return Err(ServerFnError::new(
+                    "This driving was recorded on another carrier's ELD",
+                ));
+            }
+            if record.rejected_by.contains(&user.id) {
+                return Err(ServerFnError::new("You already rejected this driving"));
+            }
+
+            let now = Utc::now();
+            let unassigned = record.clone();
+            if req.accept {
+                record.status = UnidentifiedStatus::Assigned;
+                record.driver_id = Some(user.id);
+            } else {
+                record.rejected_by.push(user.id);
+            }
+            record.decisions.push(UnidentifiedDecision {
+                action: if req.accept {
+                    UnidentifiedAction::Accepted
+                } else {
+                    UnidentifiedAction::Rejected
+                },
+                by: user.id,
+                driver_id: Some(user.id),
+                comment: comment.clone(),
+                at: now,
+            });
+            record.updated_at = now;
+
+            // Claim the record before touching the log, so only one accept applies.
+            let claimed = collection
+                .replace_one(
+                    doc! { "_id": record.id, "status": "Unassigned" },
+                    record.clone(),
+                )
+                .await?;
+            if claimed.matched_count == 0 {
+                return Err(ServerFnError::new("This driving has already been assigned"));
+            }
+
+            if req.accept {
+                let mut edit = DutyEdit {
+                    id: ObjectId::new(),
+                    driver_id: user.id,
+                    requested_by: user.id,
+                    by_carrier: false,
+                    status: DutyEditStatus::Pending,
+                    duty_status: DutyState::Driving,
+                    start: record.start,
+                    end: record.end,
+                    location: record.start_location.clone(),
+                    annotation: format!(
+                        "Unidentified driving on ELD {} accepted by the driver",
+                        record.eld_device_id
+                    ),
+                    original: Vec::new(),
+                    edited: Vec::new(),
+                    review_comment: comment,
+                    unidentified_id: Some(record.id),
+                    created_at: now,
+                    updated_at: now,
+                };
+                if let Err(err) = apply_duty_edit(&mut edit, DutyEventOrigin::Unidentified).await {
+                    collection
+                        .replace_one(
+                            doc! { "_id": record.id, "status": "Assigned", "driverId": user.id },
+                            unassigned,
+                        )
+                        .await?;
+                    return Err(err);
+                }
+                edit_collection.insert_one(edit).await?;
+            }
+        }
+        _ => return Err(ServerFnError::new("This driving has already been assigned")),
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: record,
+    })
+}
+
+/// A carrier user assigns unidentified driving to one of their drivers. It reaches
+/// the driver's log only once the driver accepts the resulting edit.
+#[server]
+pub async fn assign_unidentified_driving(
+    req: AssignUnidentifiedDrivingRequest,
+) -> Result<SuccessResponse<UnidentifiedDriving>, ServerFnError> {
+    let user = auth(req.token)
+        .await
+        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    if user.managed_carrier().is_none() {
+        return Err(ServerFnError::new(
+            "Only carrier users can assign unidentified driving",
+        ));
+    }
+
+    let client = get_client().await;
+    let db = client.database(&std::env::var("MONGODB_DB_NAME")?);
+    let collection = db.collection::<UnidentifiedDriving>("unidentified_driving");
+    let user_collection = db.collection::<User>("users");
+    let edit_collection = db.collection::<DutyEdit>("duty_edits");
+
+    let record_id = ObjectId::parse_str(&req.record_id)?;
+    let mut record = collection
+        .find_one(doc! { "_id": record_id })
+        .await?
+        .ok_or(ServerFnError::new("Unidentified driving not found"))?;
+    if record.status != UnidentifiedStatus::Unassigned {
+        return Err(ServerFnError::new("This driving has already been assigned"));
+    }
+    if !carrier_devices(&user)
+        .await?
+        .contains(&record.eld_device_id)
+    {
+        return Err(ServerFnError::new(
+            "This driving was recorded on another carrier's ELD",
+        ));
+    }
+
+    let driver_id = ObjectId::parse_str(&req.driver_id)?;
+    let driver = user_collection
+        .find_one(doc! { "_id": driver_id })
+        .await?
+        .ok_or(ServerFnError::new("Driver not found"))?;
+    if !user.is_carrier_of(&driver) {
+        return Err(ServerFnError::new("The driver works for another carrier"));
+    }
+
+    let comment = comment(req.comment);
+    let now = Utc::now();
+    record.status = UnidentifiedStatus::Pending;
+    record.driver_id = Some(driver.id);
+    record.decisions.push(UnidentifiedDecision {
+        action: UnidentifiedAction::Assigned,
+        by: user.id,
+        driver_id: Some(driver.id),
+        comment: comment.clone(),
+        at: now,
+    });
+    record.updated_at = now;
+
+    let claimed = collection
+        .replace_one(
+            doc! { "_id": record.id, "status": "Unassigned" },
+            record.clone(),
+        )
+        .await?;
+    if claimed.matched_count == 0 {
+        return Err(ServerFnError::new("This driving has already been assigned"));
+    }
+
+    let events = load_duty_events(driver.id, record.start).await?;
+    let edit = DutyEdit {
+        id: ObjectId::new(),
+        driver_id: driver.id,
+        requested_by: user.id,
+        by_carrier: true,
+        status: DutyEditStatus::Pending,
+        duty_status: DutyState::Driving,
+        start: record.start,
+        end: record.end,
+        location: record.start_location.clone(),
+        annotation: match &comment {
+            Some(comment) => format!("Unidentified driving assigned by the carrier: {}", comment),
+            None => "Unidentified driving assigned by the carrier".to_string(),
+        },
+        original: events_in_range(&events, record.start, record.end),
+        edited: Vec::new(),
+        review_comment: None,
+        unidentified_id: Some(record.id),
+        created_at: now,
+        updated_at: now,
+    };
+    edit_collection.insert_one(edit).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: record,
+    })
+}
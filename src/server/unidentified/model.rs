@@ -0,0 +1,61 @@
+#![allow(non_snake_case)]
+
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum UnidentifiedStatus {
+    Unassigned, // Nobody has claimed the driving yet
+    Pending,    // Assigned by the carrier, waiting on the driver
+    Assigned,   // Part of a driver's log
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum UnidentifiedAction {
+    Accepted, // The driver took the driving into their log
+    Rejected, // The driver declined it, or declined the carrier's assignment
+    Assigned, // The carrier assigned it to a driver
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UnidentifiedDecision {
+    pub action: UnidentifiedAction,
+    pub by: ObjectId,
+    #[serde(rename = "driverId")]
+    pub driver_id: Option<ObjectId>,
+    pub comment: Option<String>,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub at: DateTime<Utc>,
+}
+
+// Driving an ELD recorded while no driver was logged in.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UnidentifiedDriving {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "eldDeviceId")]
+    pub eld_device_id: String,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub start: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub end: DateTime<Utc>,
+    #[serde(rename = "startLocation")]
+    pub start_location: String,
+    #[serde(rename = "endLocation")]
+    pub end_location: String,
+    #[serde(rename = "startOdometer")]
+    pub start_odometer: Option<f64>,
+    #[serde(rename = "endOdometer")]
+    pub end_odometer: Option<f64>,
+    pub status: UnidentifiedStatus,
+    #[serde(rename = "driverId")]
+    pub driver_id: Option<ObjectId>, // The driver it is assigned or proposed to
+    #[serde(rename = "rejectedBy")]
+    pub rejected_by: Vec<ObjectId>, // Drivers who declined it; it is not offered to them again
+    pub decisions: Vec<UnidentifiedDecision>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetUnidentifiedDrivingRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewUnidentifiedDrivingRequest {
+    pub token: String,
+    pub record_id: String,
+    pub accept: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AssignUnidentifiedDrivingRequest {
+    pub token: String,
+    pub record_id: String,
+    pub driver_id: String,
+    pub comment: Option<String>,
+}